@@ -0,0 +1,718 @@
+// ============================================================================
+// Animation
+// `Tween`s move a value from one end to the other over a fixed time along an
+// easing curve; `Spring`s move it there as a damped spring would. Both are
+// `InnerMovement`s, advanced by the `delta` of the `UpdateContext` they are
+// given, so they can be stepped frame by frame in tests.
+//
+// A `World` animates properties of its nodes — opacity, offset, size and
+// colors — with `World::animate`, and advances them with `World::step` (a
+// fixed time) or `World::update` (the real clock). Finished animations hold
+// their last value and send `AnimationFinished`. Animations are keyed by the
+// node's path, so they belong to a position in the tree rather than to a
+// node: they are dropped once no node of the type they were started on is
+// there, but a node that moves leaves its animations behind.
+// ============================================================================
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::event_bus::event_bus;
+use crate::layout::LayoutNode;
+use crate::object::{NodePath, World};
+use crate::theme::BoxStyle;
+use crate::traits::{InnerMovement, Message, UpdateContext};
+use crate::types::{Color, Point};
+
+// ── Values ───────────────────────────────────────────────────────────────────
+
+/// A value that can be animated: one that lies some way between two others.
+pub trait Animatable: Clone {
+    /// The value `t` of the way from `self` to `to`. `t` may leave `0..=1`
+    /// when an easing curve or a spring overshoots.
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Animatable for Point {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Point::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Animatable for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let channel =
+            |a: u8, b: u8| (a as f32).lerp(&(b as f32), t).round().clamp(0.0, 255.0) as u8;
+        Color::rgba(
+            channel(self.r, to.r),
+            channel(self.g, to.g),
+            channel(self.b, to.b),
+            channel(self.a, to.a),
+        )
+    }
+}
+
+/// A property of a node that can be animated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnimatedProperty {
+    /// From 0 (invisible) to 1; multiplies with the opacity of the nodes
+    /// around it.
+    Opacity,
+    /// How far the node and everything inside it are moved from where
+    /// layout put them.
+    Offset,
+    /// The node's width and height, in place of those layout gave it.
+    Size,
+    Background,
+    /// The color of the node's text.
+    Foreground,
+    BorderColor,
+}
+
+/// The value of an `AnimatedProperty`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimatedValue {
+    Number(f32),
+    Point(Point),
+    /// Width and height.
+    Size(f32, f32),
+    Color(Color),
+}
+
+impl Animatable for AnimatedValue {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        use AnimatedValue::*;
+        match (self, to) {
+            (Number(a), Number(b)) => Number(a.lerp(b, t)),
+            (Point(a), Point(b)) => Point(a.lerp(b, t)),
+            (Size(aw, ah), Size(bw, bh)) => Size(aw.lerp(bw, t), ah.lerp(bh, t)),
+            (Color(a), Color(b)) => Color(a.lerp(b, t)),
+            // Values of different kinds jump at the end.
+            _ if t < 1.0 => *self,
+            _ => *to,
+        }
+    }
+}
+
+impl From<f32> for AnimatedValue {
+    fn from(n: f32) -> Self {
+        AnimatedValue::Number(n)
+    }
+}
+
+impl From<Point> for AnimatedValue {
+    fn from(p: Point) -> Self {
+        AnimatedValue::Point(p)
+    }
+}
+
+impl From<Color> for AnimatedValue {
+    fn from(c: Color) -> Self {
+        AnimatedValue::Color(c)
+    }
+}
+
+impl AnimatedValue {
+    pub fn as_number(&self) -> Option<f32> {
+        match *self {
+            AnimatedValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_point(&self) -> Option<Point> {
+        match *self {
+            AnimatedValue::Point(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    pub fn as_color(&self) -> Option<Color> {
+        match *self {
+            AnimatedValue::Color(c) => Some(c),
+            _ => None,
+        }
+    }
+}
+
+// ── Easing ───────────────────────────────────────────────────────────────────
+
+/// How a tween's progress over time maps to progress between its values.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// CSS `ease`: quick to start, slow to end.
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// A CSS `cubic-bezier(x1, y1, x2, y2)` curve.
+    CubicBezier(f32, f32, f32, f32),
+    /// Jumps in this many equal steps, at the end of each.
+    Steps(u32),
+}
+
+impl Easing {
+    /// Progress between the values at progress `t` (from 0 to 1) in time.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Easing::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Easing::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Easing::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+            Easing::Steps(n) => {
+                let n = n.max(1) as f32;
+                (t * n).floor() / n
+            }
+        }
+    }
+}
+
+/// The `y` of the curve from (0, 0) to (1, 1) with control points `(x1, y1)`
+/// and `(x2, y2)` where its `x` is `x`.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let at = |a: f32, b: f32, s: f32| {
+        let r = 1.0 - s;
+        3.0 * r * r * s * a + 3.0 * r * s * s * b + s * s * s
+    };
+    if x <= 0.0 || x >= 1.0 {
+        return x;
+    }
+    // `x` grows with `s`, so bisect for the `s` where it is `x`.
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    let mut s = x;
+    for _ in 0..32 {
+        let dx = at(x1.clamp(0.0, 1.0), x2.clamp(0.0, 1.0), s) - x;
+        if dx.abs() < 1e-6 {
+            break;
+        }
+        if dx > 0.0 {
+            hi = s;
+        } else {
+            lo = s;
+        }
+        s = (lo + hi) / 2.0;
+    }
+    at(y1, y2, s)
+}
+
+/// How many times a tween plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeat {
+    Times(u32),
+    Forever,
+}
+
+impl Default for Repeat {
+    fn default() -> Self {
+        Repeat::Times(1)
+    }
+}
+
+// ── Tween ────────────────────────────────────────────────────────────────────
+
+/// Moves a value from `from` to `to` over `duration`, along `easing`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub duration: Duration,
+    pub easing: Easing,
+    /// Time before the tween starts moving.
+    pub delay: Duration,
+    pub repeat: Repeat,
+    /// Whether every other play runs backwards, from `to` to `from`.
+    pub alternate: bool,
+    /// Time the tween has been updated for, delay included.
+    elapsed: Duration,
+}
+
+impl<T: Animatable> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Tween {
+            from,
+            to,
+            duration,
+            easing: Easing::Linear,
+            delay: Duration::ZERO,
+            repeat: Repeat::default(),
+            alternate: false,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn alternate(mut self) -> Self {
+        self.alternate = true;
+        self
+    }
+
+    /// The value at the time the tween has been updated to.
+    pub fn value(&self) -> T {
+        let Some(played) = self.elapsed.checked_sub(self.delay) else {
+            return self.from.clone();
+        };
+        let (play, t) = match self.repeat {
+            Repeat::Times(n) if self.is_finished() => (n.max(1) - 1, 1.0),
+            _ if self.duration.is_zero() => (0, 1.0),
+            _ => {
+                let d = self.duration.as_secs_f64();
+                let plays = played.as_secs_f64() / d;
+                (plays.floor() as u32, plays.fract() as f32)
+            }
+        };
+        let t = if self.alternate && play % 2 == 1 {
+            1.0 - t
+        } else {
+            t
+        };
+        self.from.lerp(&self.to, self.easing.apply(t))
+    }
+
+    /// Whether the tween has played as many times as it repeats.
+    pub fn is_finished(&self) -> bool {
+        match self.repeat {
+            Repeat::Forever => false,
+            Repeat::Times(n) => self.elapsed >= self.delay + self.duration * n.max(1),
+        }
+    }
+}
+
+impl<T: Animatable> InnerMovement for Tween<T> {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        self.elapsed += ctx.delta;
+    }
+}
+
+// ── Spring ───────────────────────────────────────────────────────────────────
+
+/// Moves a value from `from` towards `to` as a mass on a damped spring
+/// would, overshooting if the damping is low.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spring<T> {
+    pub from: T,
+    pub to: T,
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    /// Time left before the spring is let go.
+    pub delay: Duration,
+    /// Position between `from` (0) and `to` (1), and its speed per second.
+    position: f32,
+    velocity: f32,
+    /// Time not yet simulated, less than one step.
+    pending: Duration,
+    settled: bool,
+}
+
+/// Springs are simulated in steps of this length, however long the frames
+/// are, so that they move the same way at any frame rate.
+const SPRING_STEP: Duration = Duration::from_millis(1);
+/// A spring is at rest when it is this close to its target, and slower than
+/// this per second.
+const SPRING_REST: f32 = 1e-3;
+
+impl<T: Animatable> Spring<T> {
+    /// A spring with a gentle default: stiffness 170, damping 26, mass 1.
+    pub fn new(from: T, to: T) -> Self {
+        Spring {
+            from,
+            to,
+            stiffness: 170.0,
+            damping: 26.0,
+            mass: 1.0,
+            delay: Duration::ZERO,
+            position: 0.0,
+            velocity: 0.0,
+            pending: Duration::ZERO,
+            settled: false,
+        }
+    }
+
+    pub fn stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    pub fn damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn value(&self) -> T {
+        self.from.lerp(&self.to, self.position)
+    }
+
+    /// Whether the spring has come to rest at `to`.
+    pub fn is_finished(&self) -> bool {
+        self.settled
+    }
+}
+
+impl<T: Animatable> InnerMovement for Spring<T> {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if self.settled {
+            return;
+        }
+        let mut time = self.pending + ctx.delta;
+        // The delay passes before any of the time is simulated.
+        let waited = self.delay.min(time);
+        self.delay -= waited;
+        time -= waited;
+        let h = SPRING_STEP.as_secs_f32();
+        while time >= SPRING_STEP {
+            let force = -self.stiffness * (self.position - 1.0) - self.damping * self.velocity;
+            self.velocity += force / self.mass.max(f32::EPSILON) * h;
+            self.position += self.velocity * h;
+            time -= SPRING_STEP;
+            if (self.position - 1.0).abs() < SPRING_REST && self.velocity.abs() < SPRING_REST {
+                self.position = 1.0;
+                self.velocity = 0.0;
+                self.settled = true;
+                time = Duration::ZERO;
+            }
+        }
+        self.pending = time;
+    }
+}
+
+// ── Node animations ──────────────────────────────────────────────────────────
+
+/// An animation of a node property.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Animation {
+    Tween(Tween<AnimatedValue>),
+    Spring(Spring<AnimatedValue>),
+}
+
+impl Animation {
+    pub fn value(&self) -> AnimatedValue {
+        match self {
+            Animation::Tween(t) => t.value(),
+            Animation::Spring(s) => s.value(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        match self {
+            Animation::Tween(t) => t.is_finished(),
+            Animation::Spring(s) => s.is_finished(),
+        }
+    }
+}
+
+impl InnerMovement for Animation {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        match self {
+            Animation::Tween(t) => t.update(ctx),
+            Animation::Spring(s) => s.update(ctx),
+        }
+    }
+}
+
+impl From<Tween<AnimatedValue>> for Animation {
+    fn from(t: Tween<AnimatedValue>) -> Self {
+        Animation::Tween(t)
+    }
+}
+
+impl From<Spring<AnimatedValue>> for Animation {
+    fn from(s: Spring<AnimatedValue>) -> Self {
+        Animation::Spring(s)
+    }
+}
+
+/// Sent on the event bus when an animation has finished: a tween has played
+/// as often as it repeats, or a spring has come to rest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationFinished {
+    pub path: NodePath,
+    pub property: AnimatedProperty,
+}
+
+impl Message for AnimationFinished {}
+
+/// The animations of a world's nodes, and the clock that drives them.
+#[derive(Debug, Clone, Default)]
+pub struct Animations {
+    /// Finished animations stay, holding their last value, as long as their
+    /// node does.
+    animations: BTreeMap<(NodePath, AnimatedProperty), Entry>,
+    /// The last frame, once there has been one.
+    clock: Option<UpdateContext>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    animation: Animation,
+    /// Type of the node the animation was started on.
+    node: &'static str,
+}
+
+impl Animations {
+    pub fn get(&self, path: &[usize], property: AnimatedProperty) -> Option<&Animation> {
+        self.animations
+            .get(&(path.to_vec(), property))
+            .map(|e| &e.animation)
+    }
+
+    /// Whether any animation has yet to finish.
+    pub fn is_running(&self) -> bool {
+        self.animations.values().any(|e| !e.animation.is_finished())
+    }
+
+    /// The clock of the last frame.
+    pub fn clock(&self) -> Option<&UpdateContext> {
+        self.clock.as_ref()
+    }
+
+    fn value(&self, path: &[usize], property: AnimatedProperty) -> Option<AnimatedValue> {
+        self.get(path, property).map(Animation::value)
+    }
+
+    /// Advance the clock to `time`, `delta` after the last frame, and every
+    /// running animation with it. The first tick is frame 0, at `time`.
+    /// Returns the animations that finished.
+    fn tick(&mut self, time: Instant, delta: Duration) -> Vec<AnimationFinished> {
+        let mut ctx = match &mut self.clock {
+            Some(ctx) => {
+                ctx.advance(delta);
+                ctx.time = time;
+                ctx.clone()
+            }
+            None => self.clock.insert(UpdateContext::new(time)).clone(),
+        };
+        let mut finished = Vec::new();
+        for ((path, property), Entry { animation, .. }) in &mut self.animations {
+            if animation.is_finished() {
+                continue;
+            }
+            animation.update(&mut ctx);
+            if animation.is_finished() {
+                finished.push(AnimationFinished {
+                    path: path.clone(),
+                    property: *property,
+                });
+            }
+        }
+        finished
+    }
+
+    /// Move and resize `node` and what is inside it by their offsets and
+    /// sizes.
+    fn apply(&self, node: &mut LayoutNode, dx: f32, dy: f32) {
+        let offset = self
+            .value(&node.path, AnimatedProperty::Offset)
+            .and_then(|v| v.as_point())
+            .unwrap_or_default();
+        let (dx, dy) = (dx + offset.x, dy + offset.y);
+        node.rect.x += dx;
+        node.rect.y += dy;
+        if let Some(AnimatedValue::Size(w, h)) = self.value(&node.path, AnimatedProperty::Size) {
+            node.rect.width = w.max(0.0);
+            node.rect.height = h.max(0.0);
+        }
+        for child in &mut node.children {
+            self.apply(child, dx, dy);
+        }
+    }
+}
+
+impl World {
+    /// Animate `property` of the node at `path`, in place of any animation
+    /// it had. The animation stays with the path: it is dropped when no node
+    /// of the same type is there any more, not moved along with the node.
+    pub fn animate(
+        &mut self,
+        path: &[usize],
+        property: AnimatedProperty,
+        animation: impl Into<Animation>,
+    ) {
+        let entry = Entry {
+            animation: animation.into(),
+            node: self.root.node(path).map_or("", |n| n.type_name()),
+        };
+        self.animations
+            .animations
+            .insert((path.to_vec(), property), entry);
+    }
+
+    /// Tween `property` of the node at `path` from where it is now to `to`.
+    pub fn transition(
+        &mut self,
+        path: &[usize],
+        property: AnimatedProperty,
+        to: impl Into<AnimatedValue>,
+        duration: Duration,
+        easing: Easing,
+    ) {
+        let from = self.animated_value(path, property);
+        self.animate(
+            path,
+            property,
+            Tween::new(from, to.into(), duration).easing(easing),
+        );
+    }
+
+    /// Stop animating `property` of the node at `path`, and let it go back
+    /// to its value without animation.
+    pub fn stop_animation(&mut self, path: &[usize], property: AnimatedProperty) {
+        self.animations
+            .animations
+            .remove(&(path.to_vec(), property));
+    }
+
+    /// The value of `property` of the node at `path` as it is drawn now.
+    pub fn animated_value(&self, path: &[usize], property: AnimatedProperty) -> AnimatedValue {
+        if let Some(value) = self.animations.value(path, property) {
+            return value;
+        }
+        let boxed = || {
+            self.restyle_box(path, self.theme_box_style(path))
+                .unwrap_or_else(|| BoxStyle::plain(&self.theme))
+        };
+        match property {
+            AnimatedProperty::Opacity => AnimatedValue::Number(1.0),
+            AnimatedProperty::Offset => AnimatedValue::Point(Point::default()),
+            AnimatedProperty::Size => {
+                let rect = self
+                    .static_layout()
+                    .find(path)
+                    .map(|n| n.rect)
+                    .unwrap_or_default();
+                AnimatedValue::Size(rect.width, rect.height)
+            }
+            AnimatedProperty::Background => AnimatedValue::Color(boxed().background),
+            AnimatedProperty::Foreground => AnimatedValue::Color(boxed().foreground),
+            AnimatedProperty::BorderColor => AnimatedValue::Color(boxed().border),
+        }
+    }
+
+    /// How opaque the node at `path` is drawn: its own opacity times those of
+    /// the nodes around it.
+    pub fn opacity(&self, path: &[usize]) -> f32 {
+        (0..=path.len())
+            .filter_map(|n| {
+                self.animations
+                    .value(&path[..n], AnimatedProperty::Opacity)?
+                    .as_number()
+            })
+            .map(|o| o.clamp(0.0, 1.0))
+            .product()
+    }
+
+    /// Advance every animation by `delta`, as if that much time had passed
+    /// since the last frame (frame 0 being now, if there was none). Sends
+    /// `AnimationFinished` for those that finish. Returns `true` if any was
+    /// running and the world needs to be drawn again.
+    pub fn step(&mut self, delta: Duration) -> bool {
+        let Some(clock) = &self.animations.clock else {
+            let running = self.frame(Instant::now());
+            return self.step(delta) | running;
+        };
+        let time = clock.time + delta;
+        self.tick(time, delta)
+    }
+
+    /// Advance every animation to `now`, by the time since the last frame.
+    pub fn frame(&mut self, now: Instant) -> bool {
+        let delta = self
+            .animations
+            .clock
+            .as_ref()
+            .map_or(Duration::ZERO, |c| now.saturating_duration_since(c.time));
+        self.tick(now, delta)
+    }
+
+    fn tick(&mut self, time: Instant, delta: Duration) -> bool {
+        let root = &self.root;
+        self.animations
+            .animations
+            .retain(|(path, _), e| root.node(path).is_some_and(|n| n.type_name() == e.node));
+        let running = self.animations.is_running();
+        for finished in self.animations.tick(time, delta) {
+            event_bus().send(finished);
+        }
+        running
+    }
+
+    /// `layout` moved and resized by the offset and size animations.
+    pub(crate) fn animate_layout(&self, mut layout: LayoutNode) -> LayoutNode {
+        if !self.animations.animations.is_empty() {
+            self.animations.apply(&mut layout, 0.0, 0.0);
+        }
+        layout
+    }
+
+    /// `style` with the animated colors of the node at `path`. Nodes without
+    /// a box get one if any of its colors is animated.
+    pub(crate) fn animate_box(&self, path: &[usize], style: Option<BoxStyle>) -> Option<BoxStyle> {
+        let color = |property| {
+            self.animations
+                .value(path, property)
+                .and_then(|v: AnimatedValue| v.as_color())
+        };
+        let animated = [
+            AnimatedProperty::Background,
+            AnimatedProperty::Foreground,
+            AnimatedProperty::BorderColor,
+        ]
+        .into_iter()
+        .any(|p| color(p).is_some());
+        if !animated {
+            return style;
+        }
+        let mut style = style.unwrap_or_else(|| BoxStyle::plain(&self.theme));
+        if let Some(c) = color(AnimatedProperty::Background) {
+            style.background = c;
+        }
+        if let Some(c) = color(AnimatedProperty::Foreground) {
+            style.foreground = c;
+        }
+        if let Some(c) = color(AnimatedProperty::BorderColor) {
+            style.border = c;
+        }
+        Some(style)
+    }
+
+    /// The animated text color of the node at `path`, if it has one.
+    pub(crate) fn animated_foreground(&self, path: &[usize]) -> Option<Color> {
+        self.animations
+            .value(path, AnimatedProperty::Foreground)?
+            .as_color()
+    }
+}
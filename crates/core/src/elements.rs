@@ -1,28 +1,48 @@
 use crate::form::Form;
-use crate::object::Object;
-use crate::traits::IntoObject;
+use crate::ime::Preedit;
+use crate::inputs::{Checkbox, NumberInput, RadioGroup, Select, Slider};
+use crate::markdown::Markdown;
+use crate::object::{NodePath, Object};
+use crate::pointer::PointerHandler;
+use crate::resource::Query;
+use crate::runtime::LocalFuture;
+use crate::shared_str::SharedStr;
+use crate::text::{TextSpan, TextStyle};
+use crate::text_edit::{self, DisplayText, EditCommand, EditRules, TextSelection, Underline};
+use crate::traits::{IntoObject, Message};
+use crate::validation::Rule;
 
 #[derive(Debug, Clone)]
 pub enum Element {
     Text(Text),
+    RichText(RichText),
     TextClock(TextClock),
     Button(Button),
-    Form(Form),
+    /// Boxed: a form is much larger than the other elements.
+    Form(Box<Form>),
     TextInput(TextInput),
+    TextArea(TextArea),
+    Checkbox(Checkbox),
+    RadioGroup(RadioGroup),
+    Select(Select),
+    Slider(Slider),
+    NumberInput(NumberInput),
     Switch(Switch),
+    Query(Query),
+    Markdown(Markdown),
 }
 
 // ── Text ─────────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Text {
-    pub text: &'static str,
-}
-
-impl Default for Text {
-    fn default() -> Self {
-        Self { text: "" }
-    }
+    pub text: SharedStr,
+    /// Set on top of the style inherited from the containers above.
+    pub style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
 impl From<Text> for Element {
@@ -31,19 +51,58 @@ impl From<Text> for Element {
     }
 }
 
-// ── TextClock ────────────────────────────────────────────────────────────────
+// ── RichText ─────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone)]
-pub struct TextClock {
-    pub format: &'static str,
+/// A paragraph of spans set in different styles, shaped and wrapped as one
+/// text. Clicking a span with a `link` sends `LinkClicked`.
+#[derive(Debug, Clone, Default)]
+pub struct RichText {
+    pub spans: Vec<TextSpan>,
+    /// Set on top of the style inherited from the containers above; each
+    /// span's style is set on top of this one.
+    pub style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
-impl Default for TextClock {
-    fn default() -> Self {
-        Self { format: "" }
+impl RichText {
+    /// The text of all spans, joined.
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// Whether any span is a link.
+    pub fn has_links(&self) -> bool {
+        self.spans.iter().any(|s| s.link.is_some())
+    }
+}
+
+impl From<RichText> for Element {
+    fn from(t: RichText) -> Self {
+        Element::RichText(t)
     }
 }
 
+/// Sent on the event bus when a link in a `RichText` is clicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkClicked {
+    /// The `RichText` the link is in.
+    pub path: NodePath,
+    /// `TextSpan::link` of the clicked span.
+    pub target: SharedStr,
+}
+
+impl Message for LinkClicked {}
+
+// ── TextClock ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
+pub struct TextClock {
+    pub format: SharedStr,
+}
+
 impl From<TextClock> for Element {
     fn from(t: TextClock) -> Self {
         Element::TextClock(t)
@@ -54,12 +113,136 @@ impl From<TextClock> for Element {
 
 #[derive(Debug, Clone)]
 pub struct Button {
-    pub text: &'static str,
+    pub text: SharedStr,
+    /// Callback run when the button is clicked. Attached by `#[element]` when
+    /// the wrapping element has a `#[handler] impl ClickHandler`.
+    pub on_click: Option<ClickCallback>,
+    pub on_pointer: Option<PointerHandler>,
+    /// Whether the button can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order: positive values come first, in ascending order; `0` follows
+    /// tree order; negative values are skipped by `Tab`.
+    pub tab_index: i32,
+    /// A disabled button cannot be clicked or focused.
+    pub disabled: bool,
+    /// Clicks whose handler still runs in the background, until
+    /// `World::update` sees them finish. Shared with clones of the button.
+    pub pending: PendingClicks,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
 impl Default for Button {
     fn default() -> Self {
-        Self { text: "" }
+        Self {
+            text: SharedStr::default(),
+            on_click: None,
+            on_pointer: None,
+            focusable: true,
+            tab_index: 0,
+            disabled: false,
+            pending: PendingClicks::default(),
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl Button {
+    /// Deliver a click, running the attached `ClickHandler` in the background
+    /// without waiting for it; `World::update` picks it up once it is done.
+    /// Does nothing if no handler is attached.
+    pub fn click(&self) {
+        let Some(cb) = &self.on_click else {
+            return;
+        };
+        let cb = cb.clone();
+        let (tx, rx) = futures::channel::oneshot::channel();
+        crate::runtime::spawn(move || async move {
+            cb.call().await;
+            let _ = tx.send(());
+        });
+        self.pending.0.lock().unwrap().push(rx);
+    }
+
+    /// The future a click would run, for callers that are already async.
+    pub fn click_future(&self) -> LocalFuture {
+        match &self.on_click {
+            Some(cb) => cb.call(),
+            None => Box::pin(async {}),
+        }
+    }
+}
+
+/// Shared click callback stored on a `Button`.
+#[derive(Clone)]
+pub struct ClickCallback(std::sync::Arc<dyn Fn() -> LocalFuture + Send + Sync>);
+
+impl ClickCallback {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn() -> LocalFuture + Send + Sync + 'static,
+    {
+        Self(std::sync::Arc::new(f))
+    }
+
+    /// Start the handler, returning the future that completes it.
+    pub fn call(&self) -> LocalFuture {
+        (self.0)()
+    }
+}
+
+/// Clicks of a `Button` whose handler has not finished yet.
+#[derive(Clone, Default)]
+pub struct PendingClicks(
+    std::sync::Arc<std::sync::Mutex<Vec<futures::channel::oneshot::Receiver<()>>>>,
+);
+
+impl PendingClicks {
+    /// Whether a click handler is still running.
+    pub fn is_pending(&self) -> bool {
+        !self.0.lock().unwrap().is_empty()
+    }
+
+    /// Forget the clicks that are done. Returns `true` if there were any.
+    fn poll(&self) -> bool {
+        let mut clicks = self.0.lock().unwrap();
+        let before = clicks.len();
+        // A handler that panicked has dropped its sender; it is done as well.
+        clicks.retain_mut(|rx| matches!(rx.try_recv(), Ok(None)));
+        clicks.len() < before
+    }
+}
+
+impl std::fmt::Debug for PendingClicks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} running", self.0.lock().unwrap().len())
+    }
+}
+
+/// Pick up the finished clicks of all buttons in the tree. Returns `true` if
+/// any click finished.
+pub(crate) fn update_clicks(root: &Object) -> bool {
+    let mut changed = false;
+    match root {
+        Object::Element(Element::Button(b)) => changed |= b.pending.poll(),
+        Object::Element(Element::Form(f)) => {
+            changed |= f.submit_button.pending.poll();
+            changed |= f.reset_button.pending.poll();
+        }
+        _ => {}
+    }
+    for child in root.children() {
+        changed |= update_clicks(child);
+    }
+    changed
+}
+
+impl std::fmt::Debug for ClickCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<click handler>")
     }
 }
 
@@ -80,26 +263,149 @@ impl IntoObject for Button {
 #[derive(Debug, Clone)]
 pub struct TextInput {
     /// Optional label text shown next to the input field.
-    pub label: &'static str,
-    pub name: &'static str,
-    pub r#type: &'static str,
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub r#type: SharedStr,
     /// Optional maximum length for input. If `0` then no limit is applied.
     pub max_len: u32,
+    /// Whether the input can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// The current value.
+    pub value: String,
+    /// Caret and selection within `value`.
+    pub selection: TextSelection,
+    /// Input method text being composed at the caret, if any.
+    pub composition: Option<Preedit>,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Value restored by `Form::reset`. Unless set, taken from `value` when
+    /// the field is added to an object tree.
+    pub initial_value: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
 impl Default for TextInput {
     fn default() -> Self {
         Self {
-            label: "",
-            name: "",
-            r#type: "text",
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            r#type: SharedStr::new("text"),
             max_len: 0,
+            focusable: true,
+            tab_index: 0,
+            value: String::new(),
+            selection: TextSelection::default(),
+            composition: None,
+            rules: vec![],
+            error: None,
+            initial_value: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl TextInput {
+    pub fn is_password(&self) -> bool {
+        self.r#type == "password"
+    }
+
+    /// Apply an editing command. Returns `true` if the value changed.
+    pub fn edit(&mut self, cmd: &EditCommand) -> bool {
+        let rules = EditRules {
+            max_len: self.max_len,
+            single_line: true,
+            wrap_cols: 0,
+            masked: self.is_password(),
+        };
+        text_edit::apply(&mut self.value, &mut self.selection, cmd, rules)
+    }
+
+    /// Replace the selection with `text`, truncated to `max_len`.
+    pub fn insert(&mut self, text: &str) -> bool {
+        self.edit(&EditCommand::Insert(text.to_string()))
+    }
+
+    /// Replace the whole value and put the caret at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value.clear();
+        self.selection = TextSelection::default();
+        self.insert(value);
+    }
+
+    pub fn select_all(&mut self) {
+        self.edit(&EditCommand::SelectAll);
+    }
+
+    pub fn selected_text(&self) -> &str {
+        self.value.get(self.selection.range()).unwrap_or("")
+    }
+
+    /// The value as it should be drawn: masked for password inputs.
+    pub fn display_value(&self) -> std::borrow::Cow<'_, str> {
+        if self.is_password() {
+            text_edit::mask(&self.value).into()
+        } else {
+            self.value.as_str().into()
+        }
+    }
+
+    /// The value, caret, selection and composition as they should be drawn.
+    /// A composition replaces the selection until it is committed.
+    pub fn display(&self) -> DisplayText {
+        let mut sel = self.selection;
+        text_edit::clamp(&self.value, &mut sel);
+        let range = sel.range();
+        match &self.composition {
+            Some(preedit) => {
+                let mut text = self.value.clone();
+                text.replace_range(range.clone(), &preedit.text);
+                let at = range.start;
+                let mut underlines = vec![(at..at + preedit.text.len(), Underline::Thin)];
+                if let Some((s, e)) = preedit.cursor
+                    && s < e
+                {
+                    underlines.push((at + s..at + e, Underline::Thick));
+                }
+                DisplayText {
+                    text,
+                    caret: preedit.cursor.map(|(_, e)| at + e),
+                    selection: at..at,
+                    underlines,
+                }
+            }
+            None if self.is_password() => {
+                let bullet =
+                    |offset| text_edit::grapheme_len(&self.value[..offset]) * '\u{2022}'.len_utf8();
+                DisplayText {
+                    text: self.display_value().into_owned(),
+                    caret: Some(bullet(sel.caret)),
+                    selection: bullet(range.start)..bullet(range.end),
+                    underlines: vec![],
+                }
+            }
+            None => DisplayText {
+                text: self.value.clone(),
+                caret: Some(sel.caret),
+                selection: range,
+                underlines: vec![],
+            },
         }
     }
 }
 
 impl From<TextInput> for Element {
-    fn from(t: TextInput) -> Self {
+    fn from(mut t: TextInput) -> Self {
+        if t.initial_value.is_none() {
+            t.initial_value = Some(t.value.clone());
+        }
         Element::TextInput(t)
     }
 }
@@ -110,9 +416,166 @@ impl IntoObject for TextInput {
     }
 }
 
-// ── Switch ───────────────────────────────────────────────────────────────────
+// ── TextArea ─────────────────────────────────────────────────────────────────
 
+/// Multi-line text entry. Long lines are soft-wrapped at `cols` graphemes and
+/// `rows` visual lines are shown at a time, scrolled to keep the caret visible.
 #[derive(Debug, Clone)]
+pub struct TextArea {
+    /// Optional label text shown next to the text area.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    /// Number of visible lines (defaults to 3).
+    pub rows: u32,
+    /// Wrap width in graphemes (defaults to 40).
+    pub cols: u32,
+    /// Optional maximum length in graphemes, newlines included. If `0` then no
+    /// limit is applied.
+    pub max_len: u32,
+    /// Whether the text area can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// The current value.
+    pub value: String,
+    /// Caret and selection within `value`.
+    pub selection: TextSelection,
+    /// Index of the first visible visual line.
+    pub scroll: usize,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Value restored by `Form::reset`. Unless set, taken from `value` when
+    /// the field is added to an object tree.
+    pub initial_value: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for TextArea {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            rows: 3,
+            cols: 40,
+            max_len: 0,
+            focusable: true,
+            tab_index: 0,
+            value: String::new(),
+            selection: TextSelection::default(),
+            scroll: 0,
+            rules: vec![],
+            error: None,
+            initial_value: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl TextArea {
+    /// Apply an editing command and scroll the caret into view. Returns `true`
+    /// if the value changed.
+    pub fn edit(&mut self, cmd: &EditCommand) -> bool {
+        let rules = EditRules {
+            max_len: self.max_len,
+            single_line: false,
+            wrap_cols: self.cols as usize,
+            masked: false,
+        };
+        let changed = text_edit::apply(&mut self.value, &mut self.selection, cmd, rules);
+        self.scroll_to_caret();
+        changed
+    }
+
+    /// Replace the selection with `text`, truncated to `max_len`.
+    pub fn insert(&mut self, text: &str) -> bool {
+        self.edit(&EditCommand::Insert(text.to_string()))
+    }
+
+    /// Replace the whole value and put the caret at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value.clear();
+        self.selection = TextSelection::default();
+        self.scroll = 0;
+        self.insert(value);
+    }
+
+    pub fn select_all(&mut self) {
+        self.edit(&EditCommand::SelectAll);
+    }
+
+    pub fn selected_text(&self) -> &str {
+        self.value.get(self.selection.range()).unwrap_or("")
+    }
+
+    /// The value split into visual lines; see `text_edit::wrap`.
+    pub fn lines(&self) -> Vec<std::ops::Range<usize>> {
+        text_edit::wrap(&self.value, self.cols as usize)
+    }
+
+    /// Visual line and column (in graphemes) of the caret, both from zero.
+    pub fn caret_line_col(&self) -> (usize, usize) {
+        let mut sel = self.selection;
+        text_edit::clamp(&self.value, &mut sel);
+        let lines = self.lines();
+        let line = text_edit::line_of(&lines, sel.caret);
+        let col = text_edit::grapheme_len(&self.value[lines[line].start..sel.caret]);
+        (line, col)
+    }
+
+    /// The visual lines currently shown.
+    pub fn visible_lines(&self) -> Vec<&str> {
+        self.lines()
+            .into_iter()
+            .skip(self.scroll)
+            .take(self.rows as usize)
+            .map(|r| &self.value[r])
+            .collect()
+    }
+
+    /// Scroll by `lines` visual lines (negative scrolls up), staying within
+    /// the content. Returns `true` if the position changed.
+    pub fn scroll_by(&mut self, lines: isize) -> bool {
+        let max = self.lines().len().saturating_sub(self.rows as usize);
+        let scroll = self.scroll.saturating_add_signed(lines).min(max);
+        std::mem::replace(&mut self.scroll, scroll) != scroll
+    }
+
+    /// Adjust `scroll` so the caret's line is visible.
+    pub fn scroll_to_caret(&mut self) {
+        let (line, _) = self.caret_line_col();
+        let rows = (self.rows as usize).max(1);
+        if line < self.scroll {
+            self.scroll = line;
+        } else if line >= self.scroll + rows {
+            self.scroll = line + 1 - rows;
+        }
+    }
+}
+
+impl From<TextArea> for Element {
+    fn from(mut t: TextArea) -> Self {
+        if t.initial_value.is_none() {
+            t.initial_value = Some(t.value.clone());
+        }
+        Element::TextArea(t)
+    }
+}
+
+impl IntoObject for TextArea {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── Switch ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default)]
 pub struct Switch {
     /// Child objects held by the switch.
     pub children: Vec<Object>,
@@ -123,7 +586,11 @@ pub struct Switch {
 impl Switch {
     /// Change which child is active. This is a minimal implementation used by
     /// examples; out-of-range indices are clamped to `children.len().saturating_sub(1)`.
+    ///
+    /// Requests still pending for forms and resources in the child that is
+    /// hidden are cancelled (see `Form::cancel`).
     pub fn switch_to(&mut self, idx: usize) {
+        let previous = self.active;
         if self.children.is_empty() {
             self.active = 0;
         } else if idx >= self.children.len() {
@@ -131,6 +598,11 @@ impl Switch {
         } else {
             self.active = idx;
         }
+        if self.active != previous
+            && let Some(hidden) = self.children.get_mut(previous)
+        {
+            cancel_requests(hidden);
+        }
     }
 
     /// Return the currently active index.
@@ -139,13 +611,14 @@ impl Switch {
     }
 }
 
-impl Default for Switch {
-    fn default() -> Self {
-        Self {
-            children: vec![],
-            active: 0,
-        }
+/// Cancel the pending requests of every form and resource in `obj`.
+fn cancel_requests(obj: &mut Object) {
+    match obj {
+        Object::Element(Element::Form(f)) => std::mem::take(&mut f.cancel).cancel(),
+        Object::Element(Element::Query(q)) => q.hide(),
+        _ => {}
     }
+    obj.children_mut().iter_mut().for_each(cancel_requests);
 }
 
 impl From<Switch> for Element {
@@ -194,6 +667,6 @@ impl<E> Default for IntervalTimer<E> {
 impl<E> IntoObject for IntervalTimer<E> {
     fn into_object(self) -> Object {
         // zero-sized representation
-        Text { text: "" }.into()
+        Text::default().into()
     }
 }
@@ -1,21 +1,53 @@
-use crate::traits::{Message, MessageContext, MessageHandler};
+use crate::traits::{Message, MessageContext, MessageHandler, MessageMeta};
+
+/// A type-erased message as stored by the bus.
+type AnyMessage = std::sync::Arc<dyn std::any::Any + Send + Sync>;
+
+/// Subscriber channels keyed by message `TypeId`.
+type Subscribers = std::collections::HashMap<
+    std::any::TypeId,
+    Vec<futures::channel::mpsc::UnboundedSender<AnyMessage>>,
+>;
 
 /// An async-capable event bus used by examples to send and subscribe to typed messages.
 /// This implementation is thread-safe using `Arc<Mutex>`.
 pub struct EventBus {
-    inner: std::sync::Mutex<
-        std::collections::HashMap<
-            std::any::TypeId,
-            Vec<
-                futures::channel::mpsc::UnboundedSender<
-                    std::sync::Arc<dyn std::any::Any + Send + Sync>,
-                >,
-            >,
-        >,
-    >,
+    inner: std::sync::Mutex<Subscribers>,
     // Registered handlers keyed by message TypeId
     handlers:
         std::sync::Mutex<std::collections::HashMap<std::any::TypeId, Vec<Box<dyn ErasedHandler>>>>,
+    // Messages queued by `post`, delivered by `flush`.
+    pending: std::sync::Mutex<Vec<PendingMessage>>,
+    // Human-readable names of every message type the bus has seen, for `Debug`.
+    names: std::sync::Mutex<std::collections::HashMap<std::any::TypeId, &'static str>>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = self.names.lock().unwrap();
+        let name_of = |id: &std::any::TypeId| names.get(id).copied().unwrap_or("<unknown>");
+        let handlers = self.handlers.lock().unwrap();
+        let pending = self.pending.lock().unwrap();
+        f.debug_struct("EventBus")
+            .field(
+                "handlers",
+                &handlers
+                    .iter()
+                    .map(|(id, hs)| (name_of(id), hs.len()))
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "pending",
+                &pending.iter().map(|p| p.meta.name).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
 }
 
 impl EventBus {
@@ -23,14 +55,24 @@ impl EventBus {
         Self {
             inner: std::sync::Mutex::new(std::collections::HashMap::new()),
             handlers: std::sync::Mutex::new(std::collections::HashMap::new()),
+            pending: std::sync::Mutex::new(Vec::new()),
+            names: std::sync::Mutex::new(std::collections::HashMap::new()),
         }
     }
 
+    fn remember_name<T: Message>(&self) {
+        self.names
+            .lock()
+            .unwrap()
+            .insert(std::any::TypeId::of::<T>(), T::meta().name);
+    }
+
     /// Send a typed message to all subscribers (synchronous in this API) and invoke any
     /// registered `MessageHandler<T>` implementations immediately (runs their `async`
     /// handlers to completion synchronously on the current thread).
     pub fn send<T: Message + Send + Sync>(&self, msg: T) {
-        let arc = std::sync::Arc::new(msg) as std::sync::Arc<dyn std::any::Any + Send + Sync>;
+        self.remember_name::<T>();
+        let arc = std::sync::Arc::new(msg) as AnyMessage;
         // first deliver to classic subscribers
         let guard = self.inner.lock().unwrap();
         if let Some(subs) = guard.get(&std::any::TypeId::of::<T>()) {
@@ -52,6 +94,7 @@ impl EventBus {
     /// Subscribe to messages of type `T`.
     /// Returns a receiver which yields notifications when messages of that type arrive.
     pub fn subscribe<T: Message + Send + Sync>(&self) -> EventBusReceiver<T> {
+        self.remember_name::<T>();
         let (tx, rx) = futures::channel::mpsc::unbounded();
         let mut guard = self.inner.lock().unwrap();
         guard
@@ -71,6 +114,7 @@ impl EventBus {
         H: MessageHandler<T> + 'static + Send + Sync,
         T: Message + 'static + Send + Sync,
     {
+        self.remember_name::<T>();
         let mut guard = self.handlers.lock().unwrap();
         guard
             .entry(std::any::TypeId::of::<T>())
@@ -80,11 +124,63 @@ impl EventBus {
                 _marker: std::marker::PhantomData,
             }));
     }
+
+    /// Queue a message for delivery on the next `flush`.
+    ///
+    /// If `T` is marked `coalesce` and a message of the same type (and, for
+    /// enums, the same variant) is already queued, the queued one is replaced
+    /// in place.
+    pub fn post<T: Message + Send + Sync>(&self, msg: T) {
+        self.remember_name::<T>();
+        let variant = msg.variant();
+        let entry = PendingMessage {
+            type_id: std::any::TypeId::of::<T>(),
+            meta: T::meta(),
+            variant: Box::new(variant),
+            deliver: Box::new(move |bus: &EventBus| bus.send(msg)),
+        };
+        let mut pending = self.pending.lock().unwrap();
+        if entry.meta.coalesce
+            && let Some(slot) = pending
+                .iter_mut()
+                .find(|p| p.type_id == entry.type_id && p.variant.downcast_ref() == Some(&variant))
+        {
+            *slot = entry;
+            return;
+        }
+        pending.push(entry);
+    }
+
+    /// Deliver all queued messages, highest priority first (FIFO within the
+    /// same priority). Returns how many messages were delivered.
+    pub fn flush(&self) -> usize {
+        let mut batch = std::mem::take(&mut *self.pending.lock().unwrap());
+        batch.sort_by_key(|p| std::cmp::Reverse(p.meta.priority));
+        let n = batch.len();
+        for p in batch {
+            (p.deliver)(self);
+        }
+        n
+    }
+
+    /// Number of messages currently waiting for `flush`.
+    pub fn pending_len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+}
+
+/// A message queued by `EventBus::post`.
+struct PendingMessage {
+    type_id: std::any::TypeId,
+    meta: MessageMeta,
+    /// `Message::variant` of the message, for coalescing.
+    variant: Box<dyn std::any::Any + Send + Sync>,
+    deliver: Box<dyn FnOnce(&EventBus) + Send>,
 }
 
 /// Receiver wrapper that yields a notification when a message of type `T` is received.
 pub struct EventBusReceiver<T> {
-    rx: futures::channel::mpsc::UnboundedReceiver<std::sync::Arc<dyn std::any::Any + Send + Sync>>,
+    rx: futures::channel::mpsc::UnboundedReceiver<AnyMessage>,
     _marker: std::marker::PhantomData<T>,
 }
 
@@ -93,21 +189,16 @@ impl<T: Message + Send + Sync> EventBusReceiver<T> {
     /// Returns `Ok(())` when a message arrives, or `Err(())` if the sender side closed.
     pub async fn recv(&mut self) -> Result<(), ()> {
         use futures::StreamExt;
-        while let Some(arc) = self.rx.next().await {
-            let _ = arc;
-            return Ok(());
+        match self.rx.next().await {
+            Some(_) => Ok(()),
+            None => Err(()),
         }
-        Err(())
     }
 }
 
 /// Trait used to type-erase message handlers so we can store them in a single map.
 trait ErasedHandler: Send + Sync {
-    fn handle_any(
-        &self,
-        msg: std::sync::Arc<dyn std::any::Any + Send + Sync>,
-        ctx: &mut MessageContext,
-    );
+    fn handle_any(&self, msg: AnyMessage, ctx: &mut MessageContext);
 }
 
 /// A concrete wrapper that holds an `Arc<Mutex<H>>` where `H: MessageHandler<T>`.
@@ -125,12 +216,8 @@ where
     H: MessageHandler<T> + 'static + Send + Sync,
     T: Message + 'static + Send + Sync,
 {
-    fn handle_any(
-        &self,
-        msg: std::sync::Arc<dyn std::any::Any + Send + Sync>,
-        ctx: &mut MessageContext,
-    ) {
-        if let Some(m) = (&*msg).downcast_ref::<T>() {
+    fn handle_any(&self, msg: AnyMessage, ctx: &mut MessageContext) {
+        if let Some(m) = (*msg).downcast_ref::<T>() {
             let mut h = self.h.lock().unwrap();
             futures::executor::block_on(h.handle(m, ctx));
         }
@@ -158,6 +245,18 @@ impl EventBusHandle {
         guard.subscribe::<T>()
     }
 
+    /// Queue a message on the global bus; see `EventBus::post`.
+    pub fn post<T: Message + Send + Sync>(&self, msg: T) {
+        let guard = get_event_bus().lock().unwrap();
+        guard.post(msg);
+    }
+
+    /// Deliver queued messages on the global bus; see `EventBus::flush`.
+    pub fn flush(&self) -> usize {
+        let guard = get_event_bus().lock().unwrap();
+        guard.flush()
+    }
+
     /// Register a handler instance for messages of type `T` with the global event bus.
     /// The handler should be wrapped in `Arc<Mutex<_>>` since the bus stores an `Arc`.
     pub fn register_handler<H, T>(&self, h: std::sync::Arc<std::sync::Mutex<H>>)
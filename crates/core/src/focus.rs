@@ -0,0 +1,128 @@
+// ============================================================================
+// Keyboard focus
+// At most one visible, focusable node holds focus. `Tab`/`Shift+Tab` walk the
+// visible tree (the computed layout) in tab order; see `Button::tab_index`.
+// ============================================================================
+
+use crate::event_bus::event_bus;
+use crate::layout::LayoutNode;
+use crate::object::{NodePath, Object, World};
+use crate::traits::Message;
+
+/// Sent on the event bus when a node gains or loses keyboard focus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusEvent {
+    Focus(NodePath),
+    Blur(NodePath),
+}
+
+impl Message for FocusEvent {}
+
+/// Focus state kept by the `World`.
+#[derive(Debug, Clone, Default)]
+pub struct FocusState {
+    focused: Option<NodePath>,
+    /// The focused text field was edited and not yet committed.
+    pub(crate) edited: bool,
+}
+
+impl FocusState {
+    pub fn focused(&self) -> Option<&NodePath> {
+        self.focused.as_ref()
+    }
+}
+
+impl World {
+    /// The node that currently has keyboard focus.
+    pub fn focused(&self) -> Option<&NodePath> {
+        self.focus.focused()
+    }
+
+    /// Visible nodes reachable with `Tab`, in tab order.
+    pub fn tab_order(&self) -> Vec<NodePath> {
+        let mut nodes = Vec::new();
+        collect_focusable(&self.layout(), &self.root, &mut nodes);
+        nodes.retain(|(tab_index, _)| *tab_index >= 0);
+        // Stable sort: positive indices first (ascending), then tree order.
+        nodes.sort_by_key(|(tab_index, _)| match *tab_index {
+            0 => (1, 0),
+            t => (0, t),
+        });
+        nodes.into_iter().map(|(_, path)| path).collect()
+    }
+
+    /// Focus the node at `path`. Fails, leaving focus unchanged, if the node is
+    /// not visible or not focusable. Nodes with a negative tab index can still
+    /// be focused this way.
+    pub fn focus(&mut self, path: &[usize]) -> bool {
+        let focusable = self
+            .root
+            .node(path)
+            .is_some_and(|n| n.focus_tab_index().is_some());
+        if !focusable || self.layout().find(path).is_none() {
+            return false;
+        }
+        self.set_focus(Some(path.to_vec()));
+        true
+    }
+
+    /// Remove keyboard focus.
+    pub fn blur(&mut self) {
+        self.set_focus(None);
+    }
+
+    /// Move focus to the next node in tab order, wrapping around.
+    pub fn focus_next(&mut self) -> bool {
+        self.step_focus(true)
+    }
+
+    /// Move focus to the previous node in tab order, wrapping around.
+    pub fn focus_prev(&mut self) -> bool {
+        self.step_focus(false)
+    }
+
+    fn step_focus(&mut self, forward: bool) -> bool {
+        let order = self.tab_order();
+        if order.is_empty() {
+            return false;
+        }
+        let current = self
+            .focus
+            .focused
+            .as_ref()
+            .and_then(|f| order.iter().position(|p| p == f));
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % order.len(),
+            (Some(i), false) => (i + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+        self.set_focus(Some(order[next].clone()));
+        true
+    }
+
+    fn set_focus(&mut self, next: Option<NodePath>) {
+        if self.focus.focused == next {
+            return;
+        }
+        self.finish_composition();
+        self.commit_text();
+        let prev = std::mem::replace(&mut self.focus.focused, next.clone());
+        if let Some(prev) = prev {
+            event_bus().send(FocusEvent::Blur(prev));
+        }
+        if let Some(next) = next {
+            event_bus().send(FocusEvent::Focus(next));
+        }
+    }
+}
+
+/// Collect `(tab_index, path)` of focusable nodes in tree order.
+fn collect_focusable(layout: &LayoutNode, root: &Object, out: &mut Vec<(i32, NodePath)>) {
+    if let Some(tab_index) = root.node(&layout.path).and_then(|n| n.focus_tab_index()) {
+        out.push((tab_index, layout.path.clone()));
+    }
+    for child in &layout.children {
+        collect_focusable(child, root, out);
+    }
+}
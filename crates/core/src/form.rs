@@ -1,11 +1,21 @@
-use crate::elements::{Button, Element};
-use crate::object::Object;
-use crate::traits::IntoObject;
+use futures::channel::oneshot;
+
+use crate::elements::{Button, Element, TextArea, TextInput};
+use crate::event_bus::event_bus;
+use crate::inputs::{Checkbox, NumberInput, RadioGroup, Select, Slider};
+use crate::middleware::CancelToken;
+use crate::object::{NodeMut, Object, World};
+use crate::shared_str::SharedStr;
+use crate::state::State;
+use crate::traits::{IntoObject, Message};
+use crate::validation::{InvalidForm, Rule};
 
 /// Trait used to convert various handler return types into `anyhow::Result<()>`.
 ///
-/// We implement this for `()`, `Result<(), E>` and `anyhow::Result<()>` so handlers
-/// can be `async fn(&Form)`, `async fn(&Form) -> Result<(), E>` or synchronous `fn(&Form)`.
+/// We implement this for `()` and `Result<(), E>` where `E` converts into a boxed
+/// `std::error::Error` (any error type, `anyhow::Error`, `String` or `&str`) so
+/// handlers can be `async fn(&Form)`, `async fn(&Form) -> Result<(), E>` or
+/// synchronous `fn(&Form)`.
 trait SubmitReturn {
     fn into_anyhow(self) -> anyhow::Result<()>;
 }
@@ -16,12 +26,14 @@ impl SubmitReturn for () {
     }
 }
 
+// Converting instead of formatting keeps the error's chain of causes, which
+// `Form::error` and `FormSubmitFailed` show.
 impl<E> SubmitReturn for Result<(), E>
 where
-    E: std::fmt::Debug + Send + Sync + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
 {
     fn into_anyhow(self) -> anyhow::Result<()> {
-        self.map_err(|e| anyhow::anyhow!("{:?}", e))
+        self.map_err(|e| anyhow::anyhow!(e.into()))
     }
 }
 
@@ -57,23 +69,50 @@ where
 // ── Form ─────────────────────────────────────────────────────────────────────
 
 // Form element: groups input fields and exposes simple submit/reset controls.
+// Clicking the submit button (or pressing `Enter` in a field) validates the
+// fields and runs `submit_handler`; the reset button restores initial values.
 #[derive(Clone)]
 pub struct Form {
+    /// Identifies the form in `FormSubmitFailed` messages.
+    pub name: SharedStr,
     /// Handler invoked on submit. Accepts async functions/closures; the macro
     /// will box function items automatically so user code stays ergonomic.
     pub submit_handler: std::sync::Arc<dyn SubmitHandler + Send + Sync>,
     pub submit_button: Button,
     pub reset_button: Button,
     pub children: Vec<Object>,
+    /// `true` while the submit handler runs. Shared with clones of the form,
+    /// so it can be watched from elsewhere.
+    pub submitting: State<bool>,
+    /// Form-level message from the last failed submission.
+    pub error: Option<String>,
+    /// A submission started by a click or `Enter`, which `World::update`
+    /// advances until it finishes. Shared with clones of the form.
+    pub pending: PendingSubmit,
+    /// For requests sent on behalf of the form (see
+    /// `RequestBuilder::cancel_with`). Cancelled, and replaced by a fresh
+    /// token, when a `Switch` hides the form.
+    pub cancel: CancelToken,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
 impl Default for Form {
     fn default() -> Self {
         Self {
+            name: SharedStr::default(),
             submit_handler: std::sync::Arc::new(|_form: &Form| Box::pin(async move {})),
             submit_button: Button::default(),
             reset_button: Button::default(),
             children: vec![],
+            submitting: State::new(false),
+            error: None,
+            pending: PendingSubmit::default(),
+            cancel: CancelToken::new(),
+            class: SharedStr::default(),
+            key: SharedStr::default(),
         }
     }
 }
@@ -81,17 +120,79 @@ impl Default for Form {
 impl std::fmt::Debug for Form {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Form")
+            .field("name", &self.name)
             .field("submit_handler", &"<handler>")
             .field("submit_button", &self.submit_button)
             .field("reset_button", &self.reset_button)
             .field("children", &self.children)
+            .field("submitting", &self.submitting.get())
+            .field("error", &self.error)
+            .field("pending", &self.pending)
+            .field("cancel", &self.cancel)
             .finish()
     }
 }
 
+/// Sent on the event bus when a form's submit handler fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormSubmitFailed {
+    /// `Form::name` of the form.
+    pub form: SharedStr,
+    /// The handler's error, with its causes.
+    pub error: String,
+}
+
+impl Message for FormSubmitFailed {}
+
+/// The stage a submission started from the UI is in. Each stage runs in the
+/// background and is picked up by `World::update` once it is done.
+#[derive(Clone, Default)]
+pub struct PendingSubmit(std::sync::Arc<std::sync::Mutex<Option<SubmitStage>>>);
+
+enum SubmitStage {
+    Validating(oneshot::Receiver<Vec<Option<String>>>),
+    Running(oneshot::Receiver<anyhow::Result<()>>),
+}
+
+impl PendingSubmit {
+    /// Whether a submission is under way.
+    pub fn is_pending(&self) -> bool {
+        self.0.lock().unwrap().is_some()
+    }
+
+    fn set(&self, stage: SubmitStage) {
+        *self.0.lock().unwrap() = Some(stage);
+    }
+
+    fn take(&self) -> Option<SubmitStage> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+impl std::fmt::Debug for PendingSubmit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match &*self.0.lock().unwrap() {
+            None => "Idle",
+            Some(SubmitStage::Validating(_)) => "Validating",
+            Some(SubmitStage::Running(_)) => "Running",
+        })
+    }
+}
+
+/// Run `fut` in the background, receiving its output when it is done.
+fn in_background<T: Send + 'static>(
+    fut: impl Future<Output = T> + Send + 'static,
+) -> oneshot::Receiver<T> {
+    let (tx, rx) = oneshot::channel();
+    crate::runtime::spawn(move || async move {
+        let _ = tx.send(fut.await);
+    });
+    rx
+}
+
 impl From<Form> for Element {
     fn from(f: Form) -> Self {
-        Element::Form(f)
+        Element::Form(Box::new(f))
     }
 }
 
@@ -102,55 +203,604 @@ impl IntoObject for Form {
 }
 
 impl Form {
-    /// Produce a lightweight JSON representation of the form's input fields.
+    /// Validate the fields and, if they are all valid, run the submit handler.
+    /// Fails with `InvalidForm` without calling the handler otherwise.
     ///
-    /// This is intentionally small and only used by examples; it returns an
-    /// `anyhow::Result<String>` so callers can use the `?` operator in examples.
-    pub fn to_json(&self) -> anyhow::Result<String> {
-        // Simple escaping for JSON string values used in examples.
-        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    /// From validation until the handler is done, `submitting` is set and the
+    /// submit button is disabled; submitting again meanwhile does nothing. A
+    /// handler error is kept in `error`, sent as `FormSubmitFailed` and
+    /// returned.
+    ///
+    /// Clicking the submit button does the same without waiting, in the
+    /// background: see `World::update`.
+    pub async fn submit(&mut self) -> anyhow::Result<()> {
+        let Some(checks) = self.start_submit() else {
+            return Ok(());
+        };
+        let errors = checks.await;
+        let run = self.validated(errors)?;
+        let result = run.await;
+        self.submitted(result)
+    }
 
-        fn walk(obj: &Object, out: &mut Vec<String>, escape: &dyn Fn(&str) -> String) {
-            match obj {
-                Object::Element(Element::TextInput(ti)) => {
-                    out.push(format!(
-                        r#"{{"name":"{}","label":"{}","type":"{}","max_len":{}}}"#,
-                        escape(ti.name),
-                        escape(ti.label),
-                        escape(ti.r#type),
-                        ti.max_len
-                    ));
+    /// Mark the form as submitting and return the check of its fields, or
+    /// `None` if it is already submitting.
+    fn start_submit(&mut self) -> Option<impl Future<Output = Vec<Option<String>>> + use<>> {
+        if self.submitting.get() {
+            return None;
+        }
+        self.error = None;
+        self.submitting.set(true);
+        self.submit_button.disabled = true;
+        Some(self.check_fields())
+    }
+
+    /// Show the errors found by `check_fields`. If there are none, return the
+    /// submit handler's future; otherwise the submission ends with
+    /// `InvalidForm`.
+    fn validated(
+        &mut self,
+        errors: Vec<Option<String>>,
+    ) -> anyhow::Result<impl Future<Output = anyhow::Result<()>> + use<>> {
+        if !self.set_errors(errors) {
+            self.submitting.set(false);
+            self.submit_button.disabled = false;
+            return Err(InvalidForm {
+                errors: self.errors(),
+            }
+            .into());
+        }
+        Ok(self.submit_handler.call_box(self))
+    }
+
+    /// End the submission with the handler's `result`.
+    fn submitted(&mut self, result: anyhow::Result<()>) -> anyhow::Result<()> {
+        self.submitting.set(false);
+        self.submit_button.disabled = false;
+        if let Err(e) = &result {
+            let error = format!("{e:#}");
+            self.error = Some(error.clone());
+            event_bus().send(FormSubmitFailed {
+                form: self.name.clone(),
+                error,
+            });
+        }
+        result
+    }
+
+    /// Advance a submission started from the UI to its next stage, if the
+    /// current one is done. Returns `true` if the form changed.
+    fn poll_submit(&mut self) -> bool {
+        match self.pending.take() {
+            None => false,
+            Some(SubmitStage::Validating(mut rx)) => match rx.try_recv() {
+                Ok(None) => {
+                    self.pending.set(SubmitStage::Validating(rx));
+                    false
                 }
-                Object::Board(b) => {
-                    for c in &b.children {
-                        walk(c, out, escape);
+                Ok(Some(errors)) => {
+                    // The error is already on the fields.
+                    if let Ok(run) = self.validated(errors) {
+                        self.pending.set(SubmitStage::Running(in_background(run)));
                     }
+                    true
                 }
-                Object::Row(r) => {
-                    for c in &r.children {
-                        walk(c, out, escape);
-                    }
+                Err(oneshot::Canceled) => {
+                    let _ = self.submitted(Err(anyhow::anyhow!("validation stopped")));
+                    true
+                }
+            },
+            Some(SubmitStage::Running(mut rx)) => match rx.try_recv() {
+                Ok(None) => {
+                    self.pending.set(SubmitStage::Running(rx));
+                    false
+                }
+                Ok(Some(result)) => {
+                    // The error is already on the form and the event bus.
+                    let _ = self.submitted(result);
+                    true
+                }
+                Err(oneshot::Canceled) => {
+                    let _ = self.submitted(Err(anyhow::anyhow!("submit handler stopped")));
+                    true
                 }
-                Object::Card(c) => {
-                    for c2 in &c.children {
-                        walk(c2, out, escape);
+            },
+        }
+    }
+
+    /// Check every shown field against its rules, setting or clearing its
+    /// `error`. Returns `true` if all fields are valid.
+    pub async fn validate(&mut self) -> bool {
+        let errors = self.check_fields().await;
+        self.set_errors(errors)
+    }
+
+    /// The error of each shown field, found without holding on to the form.
+    fn check_fields(&self) -> impl Future<Output = Vec<Option<String>>> + Send + use<> {
+        let values = self.values_json();
+        let mut checks = Vec::new();
+        self.for_each_field(|f| {
+            checks.push((f.check(), f.rules().to_vec(), f.text().into_owned()))
+        });
+        async move {
+            let lookup = |name: &str| {
+                values.get(name).map(|v| match v {
+                    serde_json::Value::String(s) => s.clone(),
+                    v => v.to_string(),
+                })
+            };
+            let mut errors = Vec::with_capacity(checks.len());
+            for (check, rules, text) in checks {
+                let mut error = check.err();
+                for rule in rules.iter().filter(|_| error.is_none()) {
+                    if let Err(message) = rule.check(&text, &lookup).await {
+                        error = Some(message);
+                        break;
                     }
                 }
+                errors.push(error);
+            }
+            errors
+        }
+    }
+
+    /// Set the error of each shown field. Returns `true` if there are none.
+    fn set_errors(&mut self, errors: Vec<Option<String>>) -> bool {
+        let mut errors = errors.into_iter();
+        self.for_each_field_mut(false, |f| *f.error_mut() = errors.next().flatten());
+        self.is_valid()
+    }
+
+    /// Whether no shown field has a validation error.
+    pub fn is_valid(&self) -> bool {
+        self.errors().is_empty()
+    }
+
+    /// `(field name, message)` for each shown field with a validation error.
+    pub fn errors(&self) -> Vec<(SharedStr, String)> {
+        let mut errors = Vec::new();
+        self.for_each_field(|f| {
+            if let Some(e) = f.error() {
+                errors.push((f.name().clone(), e.to_string()));
+            }
+        });
+        errors
+    }
+
+    /// Restore every field to its initial value and clear all errors.
+    pub fn reset(&mut self) {
+        self.error = None;
+        self.for_each_field_mut(true, |f| f.reset());
+    }
+
+    /// The current field values as a JSON object keyed by field `name`.
+    ///
+    /// Only fields that are shown take part: the active child of a `Switch`,
+    /// but not the others. Fields without a name are skipped, and a later
+    /// field replaces an earlier one with the same name. Text inputs of type
+    /// `"number"` produce a number when their value parses as one.
+    ///
+    /// A `Checkbox` gives a boolean, a `RadioGroup` or `Select` the value of
+    /// the selected choice (or `null`), a `Slider` a number and a
+    /// `NumberInput` a number, `null` when empty, or its text when that is
+    /// not a number.
+    pub fn values_json(&self) -> serde_json::Value {
+        let mut values = serde_json::Map::new();
+        self.for_each_field(|f| {
+            if !f.name().is_empty() {
+                values.insert(f.name().to_string(), f.value());
+            }
+        });
+        serde_json::Value::Object(values)
+    }
+
+    /// Serialize the field values (see `values_json`) to a JSON string.
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(&self.values_json())?)
+    }
+
+    /// Deserialize the field values into `T`, typically a struct with one
+    /// field per form field:
+    ///
+    /// ```ignore
+    /// #[derive(serde::Deserialize)]
+    /// struct LoginRequest {
+    ///     username: String,
+    ///     password: String,
+    /// }
+    ///
+    /// let request: LoginRequest = form.values()?;
+    /// ```
+    ///
+    /// Errors name the target type and the offending field, e.g.
+    /// ``form values do not match `app::Signup`: form field `age`: invalid
+    /// type: string "x", expected u32``.
+    pub fn values<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
+        serde_path_to_error::deserialize(self.values_json()).map_err(|e| {
+            let target = std::any::type_name::<T>();
+            match e.path().to_string().as_str() {
+                "." => anyhow::anyhow!("form values do not match `{target}`: {}", e.inner()),
+                field => anyhow::anyhow!(
+                    "form values do not match `{target}`: form field `{field}`: {}",
+                    e.inner()
+                ),
+            }
+        })
+    }
+
+    /// Visit the shown fields in tree order.
+    fn for_each_field<'a>(&'a self, mut f: impl FnMut(&'a dyn FormField)) {
+        fn walk<'a>(obj: &'a Object, f: &mut dyn FnMut(&'a dyn FormField)) {
+            match obj {
+                Object::Element(e) if as_field(e).is_some() => f(as_field(e).unwrap()),
                 Object::Element(Element::Switch(s)) => {
-                    for c in &s.children {
-                        walk(c, out, escape);
+                    if let Some(active) = s.children.get(s.active) {
+                        walk(active, f);
                     }
                 }
-                _ => {}
+                // A nested form has fields of its own.
+                Object::Element(Element::Form(_)) => {}
+                _ => obj.children().iter().for_each(|c| walk(c, f)),
             }
         }
+        self.children.iter().for_each(|c| walk(c, &mut f));
+    }
 
-        let mut fields = Vec::new();
-        for child in &self.children {
-            walk(child, &mut fields, &escape);
+    /// Visit the shown fields, or all fields including those of inactive
+    /// `Switch` children, in tree order.
+    fn for_each_field_mut(&mut self, all: bool, mut f: impl FnMut(&mut dyn FormField)) {
+        fn walk(obj: &mut Object, all: bool, f: &mut dyn FnMut(&mut dyn FormField)) {
+            match obj {
+                Object::Element(e) if as_field(e).is_some() => f(as_field_mut(e).unwrap()),
+                Object::Element(Element::Switch(s)) if !all => {
+                    if let Some(active) = s.children.get_mut(s.active) {
+                        walk(active, all, f);
+                    }
+                }
+                Object::Element(Element::Form(_)) => {}
+                _ => obj.children_mut().iter_mut().for_each(|c| walk(c, all, f)),
+            }
         }
+        self.children.iter_mut().for_each(|c| walk(c, all, &mut f));
+    }
+}
 
-        let json = format!("{{\"fields\":[{}]}}", fields.join(","));
-        Ok(json)
+// ── Fields ───────────────────────────────────────────────────────────────────
+
+/// An element that contributes a named value to its form.
+pub(crate) trait FormField {
+    fn name(&self) -> &SharedStr;
+    /// The value as it appears in `Form::values_json`.
+    fn value(&self) -> serde_json::Value;
+    /// The value as text, as seen by validation rules.
+    fn text(&self) -> std::borrow::Cow<'_, str>;
+    /// Validation built into the element, checked before its `rules`.
+    fn check(&self) -> Result<(), String> {
+        Ok(())
+    }
+    fn rules(&self) -> &[Rule];
+    fn error(&self) -> Option<&str>;
+    fn error_mut(&mut self) -> &mut Option<String>;
+    /// Restore the initial value and clear the error.
+    fn reset(&mut self);
+}
+
+/// `element` as a form field, if it is one.
+pub(crate) fn as_field(element: &Element) -> Option<&dyn FormField> {
+    Some(match element {
+        Element::TextInput(t) => t,
+        Element::TextArea(t) => t,
+        Element::Checkbox(c) => c,
+        Element::RadioGroup(r) => r,
+        Element::Select(s) => s,
+        Element::Slider(s) => s,
+        Element::NumberInput(n) => n,
+        _ => return None,
+    })
+}
+
+fn as_field_mut(element: &mut Element) -> Option<&mut dyn FormField> {
+    Some(match element {
+        Element::TextInput(t) => t,
+        Element::TextArea(t) => t,
+        Element::Checkbox(c) => c,
+        Element::RadioGroup(r) => r,
+        Element::Select(s) => s,
+        Element::Slider(s) => s,
+        Element::NumberInput(n) => n,
+        _ => return None,
+    })
+}
+
+/// `n` as a JSON number, without a fraction when it is a whole number.
+fn number_value(n: f64) -> serde_json::Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        serde_json::Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(serde_json::Value::Null, Into::into)
+    }
+}
+
+/// The value of the selected choice, if any.
+fn choice_value(choice: Option<&crate::inputs::Choice>) -> serde_json::Value {
+    choice.map_or(serde_json::Value::Null, |c| c.value.as_str().into())
+}
+
+impl FormField for TextInput {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        let number = (self.r#type == "number")
+            .then(|| self.value.trim().parse::<serde_json::Number>().ok())
+            .flatten();
+        match number {
+            Some(n) => serde_json::Value::Number(n),
+            None => serde_json::Value::String(self.value.clone()),
+        }
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.value.as_str().into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        let initial = self.initial_value.clone().unwrap_or_default();
+        self.set_value(&initial);
+        self.error = None;
+    }
+}
+
+impl FormField for TextArea {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        serde_json::Value::String(self.value.clone())
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.value.as_str().into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        let initial = self.initial_value.clone().unwrap_or_default();
+        self.set_value(&initial);
+        self.error = None;
+    }
+}
+
+impl FormField for Checkbox {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        self.checked.into()
+    }
+
+    // Empty when unchecked, so `Rule::required` requires a check.
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        if self.checked { "true" } else { "" }.into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        self.checked = self.initial_checked.unwrap_or_default();
+        self.error = None;
+    }
+}
+
+impl FormField for RadioGroup {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        choice_value(self.selected_choice())
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.selected_choice().map_or("", |c| &c.value).into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        self.selected = self.initial_selected.flatten();
+        self.error = None;
+    }
+}
+
+impl FormField for Select {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        choice_value(self.selected_choice())
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.selected_choice().map_or("", |c| &c.value).into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        self.selected = self.initial_selected.flatten();
+        self.open = false;
+        self.error = None;
+    }
+}
+
+impl FormField for Slider {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        number_value(self.value)
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.value.to_string().into()
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        self.value = self.initial_value.unwrap_or(self.min);
+        self.error = None;
+    }
+}
+
+impl FormField for NumberInput {
+    fn name(&self) -> &SharedStr {
+        &self.name
+    }
+
+    fn value(&self) -> serde_json::Value {
+        if self.value.trim().is_empty() {
+            return serde_json::Value::Null;
+        }
+        match self.value.trim().parse::<f64>() {
+            Ok(n) if n.is_finite() => number_value(n),
+            _ => self.value.clone().into(),
+        }
+    }
+
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        self.value.as_str().into()
+    }
+
+    fn check(&self) -> Result<(), String> {
+        self.parse().map(|_| ())
+    }
+
+    fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn error_mut(&mut self) -> &mut Option<String> {
+        &mut self.error
+    }
+
+    fn reset(&mut self) {
+        let initial = self.initial_value.clone().unwrap_or_default();
+        self.set_value(&initial);
+        self.error = None;
+    }
+}
+
+impl World {
+    /// Start submitting the form at `path` in the background; `update`
+    /// finishes it. Failures are reported through the form.
+    pub(crate) fn submit_form(&mut self, path: &[usize]) -> bool {
+        match self.root.node_mut(path) {
+            Some(NodeMut::Object(Object::Element(Element::Form(form)))) => {
+                if let Some(checks) = form.start_submit() {
+                    form.pending
+                        .set(SubmitStage::Validating(in_background(checks)));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Reset the form at `path`.
+    pub(crate) fn reset_form(&mut self, path: &[usize]) -> bool {
+        match self.root.node_mut(path) {
+            Some(NodeMut::Object(Object::Element(Element::Form(form)))) => {
+                form.reset();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Advance the submissions of all forms in the tree. Returns `true` if any
+/// form changed.
+pub(crate) fn update_forms(root: &mut Object) -> bool {
+    let mut changed = false;
+    if let Object::Element(Element::Form(form)) = root {
+        changed |= form.poll_submit();
+    }
+    for child in root.children_mut() {
+        changed |= update_forms(child);
     }
+    changed
 }
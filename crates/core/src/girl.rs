@@ -7,59 +7,39 @@ pub struct Girl {
     pub every_morning: Vec<GirlActions>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum HairColor {
     Black,
+    #[default]
     Brown,
     Blonde,
     Red,
 }
 
-impl Default for HairColor {
-    fn default() -> Self {
-        HairColor::Brown
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum SkinColor {
     Yellow,
+    #[default]
     Light,
     Dark,
 }
 
-impl Default for SkinColor {
-    fn default() -> Self {
-        SkinColor::Light
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum BodyType {
     Slim,
+    #[default]
     Average,
     Curvy,
 }
 
-impl Default for BodyType {
-    fn default() -> Self {
-        BodyType::Average
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy)]
 pub enum Appearance {
     Beautiful,
+    #[default]
     Cute,
     Plain,
 }
 
-impl Default for Appearance {
-    fn default() -> Self {
-        Appearance::Cute
-    }
-}
-
 #[derive(Debug, Clone, Copy)]
 pub enum GirlActions {
     SayHi,
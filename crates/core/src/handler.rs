@@ -2,14 +2,19 @@
 // Inventory-based handler registration system
 // ============================================================================
 
+use crate::elements::{Button, ClickCallback};
+use crate::event_bus::event_bus;
+use crate::traits::{ClickHandler, Message, MessageHandler};
+
 /// A handler registry entry collected at compile time via `inventory`.
 /// Each entry knows how to register its handler for a specific (Element, Message) pair.
 pub struct HandlerRegistryEntry {
     /// TypeId of the element type this handler is for
     pub element_type_id: fn() -> std::any::TypeId,
-    /// Registers the handler onto the given `Arc<Mutex<dyn Any + Send + Sync>>` element instance.
-    /// Returns `true` if the element was the expected type and registration succeeded.
-    pub register_fn: fn(&std::sync::Arc<std::sync::Mutex<dyn std::any::Any + Send + Sync>>),
+    /// Registers the handler onto the given element instance, passed as
+    /// `&Arc<Mutex<Element>>` erased to `&dyn Any`. Instances of any other type
+    /// are ignored.
+    pub register_fn: fn(&dyn std::any::Any),
 }
 
 inventory::collect!(HandlerRegistryEntry);
@@ -20,18 +25,30 @@ pub fn register_handlers_for_instance<T: 'static + Send + Sync>(
     instance: &std::sync::Arc<std::sync::Mutex<T>>,
 ) {
     let target_type_id = std::any::TypeId::of::<T>();
-    // Create an Arc<Mutex<dyn Any + Send + Sync>> from the instance for type-erased registration
-    let any_arc: std::sync::Arc<std::sync::Mutex<dyn std::any::Any + Send + Sync>> =
-        std::sync::Arc::clone(instance)
-            as std::sync::Arc<std::sync::Mutex<dyn std::any::Any + Send + Sync>>;
-
     for entry in inventory::iter::<HandlerRegistryEntry> {
         if (entry.element_type_id)() == target_type_id {
-            (entry.register_fn)(&any_arc);
+            (entry.register_fn)(instance);
         }
     }
 }
 
+/// `register_fn` used by `#[handler]` and `register_handler!`: if `instance` is an
+/// `Arc<Mutex<H>>`, subscribe it to messages of type `T` on the global bus.
+pub fn register_instance_handler<H, T>(instance: &dyn std::any::Any)
+where
+    H: MessageHandler<T> + 'static + Send + Sync,
+    T: Message + 'static + Send + Sync,
+{
+    if let Some(rc) = instance.downcast_ref::<std::sync::Arc<std::sync::Mutex<H>>>() {
+        event_bus().register_handler::<H, T>(rc.clone());
+    }
+}
+
+/// Compile-time check emitted by `#[handler]` so a non-`Message` type is
+/// reported at the message type itself.
+#[doc(hidden)]
+pub fn __assert_message<T: Message>() {}
+
 /// Check if there are any registered handlers for a given element type.
 pub fn has_registered_handlers<T: 'static>() -> bool {
     let target_type_id = std::any::TypeId::of::<T>();
@@ -42,3 +59,54 @@ pub fn has_registered_handlers<T: 'static>() -> bool {
     }
     false
 }
+
+/// A click handler registry entry, submitted by `#[handler] impl ClickHandler for E`.
+pub struct ClickHandlerEntry {
+    /// TypeId of the element type this handler is for
+    pub element_type_id: fn() -> std::any::TypeId,
+    /// Builds a click callback bound to the given element instance, passed as
+    /// `&Arc<Mutex<Element>>` erased to `&dyn Any`.
+    pub make_fn: fn(&dyn std::any::Any) -> Option<ClickCallback>,
+}
+
+inventory::collect!(ClickHandlerEntry);
+
+/// `make_fn` used by `#[handler]`: bind `H::on_click` to an `Arc<Mutex<H>>` instance.
+pub fn make_click_callback<H>(instance: &dyn std::any::Any) -> Option<ClickCallback>
+where
+    H: ClickHandler + Clone + 'static + Send,
+{
+    let rc = instance
+        .downcast_ref::<std::sync::Arc<std::sync::Mutex<H>>>()?
+        .clone();
+    Some(ClickCallback::new(move || {
+        // The handler runs on a copy of the element, so the element is not
+        // locked while the handler awaits and may handle the messages it sends.
+        let mut h = rc.lock().unwrap().clone();
+        Box::pin(async move { h.on_click().await })
+    }))
+}
+
+/// Attach the registered `ClickHandler` of `instance` (if any) to `button`.
+/// This is called from the generated `into_object()` of elements wrapping a `Button`.
+pub fn attach_click_handler<T: 'static + Send + Sync>(
+    instance: &std::sync::Arc<std::sync::Mutex<T>>,
+    button: &mut Button,
+) {
+    let target_type_id = std::any::TypeId::of::<T>();
+    for entry in inventory::iter::<ClickHandlerEntry> {
+        if (entry.element_type_id)() == target_type_id
+            && let Some(cb) = (entry.make_fn)(instance)
+        {
+            button.on_click = Some(cb);
+        }
+    }
+}
+
+/// Check if a `ClickHandler` is registered for a given element type.
+pub fn has_click_handler<T: 'static>() -> bool {
+    let target_type_id = std::any::TypeId::of::<T>();
+    inventory::iter::<ClickHandlerEntry>
+        .into_iter()
+        .any(|entry| (entry.element_type_id)() == target_type_id)
+}
@@ -0,0 +1,142 @@
+// ============================================================================
+// Input method composition
+// Input methods (Chinese, Japanese, Korean, ...) first show uncommitted
+// "preedit" text at the caret and only insert text once the user commits it.
+// Platform IME events are translated into `ImeEvent`s and fed into
+// `World::dispatch_ime`; tests drive the same entry point directly.
+// ============================================================================
+
+use crate::elements::{Element, TextInput};
+use crate::event_bus::event_bus;
+use crate::object::{Node, NodeMut, Object, World};
+use crate::text_edit::{EditCommand, TextEdited, Underline};
+use crate::types::Rect;
+
+/// An input method event for the focused text field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImeEvent {
+    /// Replace the current composition. `cursor` is a byte range within
+    /// `text`, the clause being converted or an empty range at the cursor;
+    /// `None` hides the cursor. An empty `text` ends the composition.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// Insert `text`, ending the composition.
+    Commit(String),
+    /// Drop the composition without inserting anything.
+    Cancel,
+}
+
+/// Uncommitted input method text shown at the caret of a `TextInput`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Preedit {
+    pub text: String,
+    /// Byte range within `text`; see `ImeEvent::Preedit`.
+    pub cursor: Option<(usize, usize)>,
+}
+
+impl World {
+    /// Whether the platform input method should be enabled: a text field that
+    /// accepts composed input has focus. Password fields do not.
+    pub fn ime_allowed(&self) -> bool {
+        matches!(self.focused_text_input(), Some(t) if !t.is_password())
+    }
+
+    /// Deliver an input method event to the focused text field. Returns
+    /// `false` if there is no such field.
+    pub fn dispatch_ime(&mut self, event: ImeEvent) -> bool {
+        if !self.ime_allowed() {
+            return false;
+        }
+        let Some(path) = self.focused().cloned() else {
+            return false;
+        };
+        let Some(NodeMut::Object(Object::Element(Element::TextInput(input)))) =
+            self.root.node_mut(&path)
+        else {
+            return false;
+        };
+        match event {
+            ImeEvent::Preedit { text, cursor } => {
+                input.composition = (!text.is_empty()).then(|| Preedit {
+                    cursor: cursor.filter(|&(s, e)| {
+                        s <= e && text.is_char_boundary(s) && text.is_char_boundary(e)
+                    }),
+                    text,
+                });
+            }
+            ImeEvent::Commit(text) => {
+                input.composition = None;
+                if input.edit(&EditCommand::Insert(text)) {
+                    let edited = TextEdited {
+                        path,
+                        name: input.name.clone(),
+                        value: input.value.clone(),
+                    };
+                    self.focus.edited = true;
+                    event_bus().send(edited);
+                }
+            }
+            ImeEvent::Cancel => input.composition = None,
+        }
+        true
+    }
+
+    /// Where the candidate window should be placed: the caret of the focused
+    /// text field, or the active clause while composing, in viewport
+    /// coordinates.
+    pub fn ime_caret_rect(&self) -> Option<Rect> {
+        let input = self.focused_text_input()?;
+        let rect = self.layout().find(self.focused()?)?.rect;
+        let style = self.text_style_at(self.focused()?);
+        let display = input.display();
+        let underlined = |kind| {
+            display
+                .underlines
+                .iter()
+                .find(|(_, u)| *u == kind)
+                .map(|(r, _)| r.clone())
+        };
+        let (start, end) = match underlined(Underline::Thick) {
+            Some(clause) => (clause.start, clause.end),
+            None => {
+                let caret = display
+                    .caret
+                    .or(underlined(Underline::Thin).map(|r| r.end))
+                    .unwrap_or(display.text.len());
+                (caret, caret)
+            }
+        };
+        Some(crate::layout::text_input_span_rect(
+            input,
+            &style,
+            rect,
+            &display.text[..start],
+            &display.text[start..end],
+        ))
+    }
+
+    /// Commit a pending composition on the focused text field, as input
+    /// methods do when focus moves elsewhere.
+    pub(crate) fn finish_composition(&mut self) {
+        let pending = self
+            .focused_text_input()
+            .and_then(|t| t.composition.clone());
+        if let Some(preedit) = pending {
+            self.dispatch_ime(ImeEvent::Commit(preedit.text));
+        }
+    }
+
+    pub(crate) fn is_composing(&self) -> bool {
+        self.focused_text_input()
+            .is_some_and(|t| t.composition.is_some())
+    }
+
+    fn focused_text_input(&self) -> Option<&TextInput> {
+        match self.root.node(self.focused()?)? {
+            Node::Object(Object::Element(Element::TextInput(t))) => Some(t),
+            _ => None,
+        }
+    }
+}
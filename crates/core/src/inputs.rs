@@ -0,0 +1,758 @@
+// ============================================================================
+// Form controls
+// Non-text inputs that contribute a value to their `Form`: `Checkbox`,
+// `RadioGroup`, `Select`, `Slider` and `NumberInput`. Each reacts to keys
+// while focused and to pointer clicks, and reports user changes with an
+// `InputChanged` message on the event bus (`NumberInput` edits its text like
+// a `TextInput` and reports `TextEdited`/`TextCommitted` as well).
+// ============================================================================
+
+use crate::elements::Element;
+use crate::event_bus::event_bus;
+use crate::keyboard::{Key, KeyEvent};
+use crate::object::{NodeMut, NodePath, Object, World};
+use crate::pointer::PointerEventKind;
+use crate::shared_str::SharedStr;
+use crate::text_edit::{self, EditCommand, EditRules, TextSelection};
+use crate::traits::{IntoObject, Message};
+use crate::types::Point;
+use crate::validation::Rule;
+
+/// Sent on the event bus when the user changes the value of a form control.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputChanged {
+    pub path: NodePath,
+    pub name: SharedStr,
+    /// The new value, as it appears in `Form::values_json`.
+    pub value: serde_json::Value,
+}
+
+impl Message for InputChanged {}
+
+/// One option of a `RadioGroup` or `Select`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Choice {
+    /// Submitted with the form.
+    pub value: SharedStr,
+    /// Shown to the user.
+    pub label: SharedStr,
+}
+
+impl Choice {
+    pub fn new(value: impl Into<SharedStr>, label: impl Into<SharedStr>) -> Self {
+        Self {
+            value: value.into(),
+            label: label.into(),
+        }
+    }
+}
+
+/// A choice whose value is its label.
+impl From<&'static str> for Choice {
+    fn from(s: &'static str) -> Self {
+        Self::new(s, s)
+    }
+}
+
+/// A choice whose value is its label.
+impl From<String> for Choice {
+    fn from(s: String) -> Self {
+        let s = SharedStr::from(s);
+        Self::new(s.clone(), s)
+    }
+}
+
+// ── Checkbox ─────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone)]
+pub struct Checkbox {
+    /// Text shown after the box.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub checked: bool,
+    /// State restored by `Form::reset`; see `TextInput::initial_value`.
+    pub initial_checked: Option<bool>,
+    /// Whether the checkbox can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// Checked by the enclosing `Form` before it submits. `Rule::required`
+    /// requires the box to be checked.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for Checkbox {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            checked: false,
+            initial_checked: None,
+            focusable: true,
+            tab_index: 0,
+            rules: vec![],
+            error: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl Checkbox {
+    pub fn toggle(&mut self) {
+        self.checked = !self.checked;
+    }
+
+    /// Handle a key press. Returns `None` if the key is not used, otherwise
+    /// whether the value changed.
+    fn on_key(&mut self, event: &KeyEvent) -> Option<bool> {
+        match event.key {
+            Key::Char(' ') => {
+                self.toggle();
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<Checkbox> for Element {
+    fn from(mut c: Checkbox) -> Self {
+        c.initial_checked.get_or_insert(c.checked);
+        Element::Checkbox(c)
+    }
+}
+
+impl IntoObject for Checkbox {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── RadioGroup ───────────────────────────────────────────────────────────────
+
+/// A set of mutually exclusive options, all shown. The group is a single tab
+/// stop; the arrow keys change the selection.
+#[derive(Debug, Clone)]
+pub struct RadioGroup {
+    /// Optional text shown above the options.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub options: Vec<Choice>,
+    /// Index of the selected option.
+    pub selected: Option<usize>,
+    /// Selection restored by `Form::reset`; see `TextInput::initial_value`.
+    pub initial_selected: Option<Option<usize>>,
+    /// Whether the group can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for RadioGroup {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            options: vec![],
+            selected: None,
+            initial_selected: None,
+            focusable: true,
+            tab_index: 0,
+            rules: vec![],
+            error: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl RadioGroup {
+    /// Select option `index`. Returns `true` if the selection changed.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.options.len() || self.selected == Some(index) {
+            return false;
+        }
+        self.selected = Some(index);
+        true
+    }
+
+    /// The selected option.
+    pub fn selected_choice(&self) -> Option<&Choice> {
+        self.options.get(self.selected?)
+    }
+
+    fn on_key(&mut self, event: &KeyEvent) -> Option<bool> {
+        let n = self.options.len();
+        if n == 0 {
+            return None;
+        }
+        let index = match (event.key, self.selected) {
+            (Key::Up | Key::Left, Some(i)) => (i + n - 1) % n,
+            (Key::Down | Key::Right, Some(i)) => (i + 1) % n,
+            (Key::Up | Key::Left, None) => n - 1,
+            (Key::Down | Key::Right | Key::Char(' '), None) => 0,
+            (Key::Char(' '), Some(i)) => i,
+            (Key::Home, _) => 0,
+            (Key::End, _) => n - 1,
+            _ => return None,
+        };
+        Some(self.select(index))
+    }
+}
+
+impl From<RadioGroup> for Element {
+    fn from(mut r: RadioGroup) -> Self {
+        r.initial_selected.get_or_insert(r.selected);
+        Element::RadioGroup(r)
+    }
+}
+
+impl IntoObject for RadioGroup {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── Select ───────────────────────────────────────────────────────────────────
+
+/// A dropdown: shows the selected option and, while open, the list of
+/// options below it.
+#[derive(Debug, Clone)]
+pub struct Select {
+    /// Optional label text shown next to the dropdown.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub options: Vec<Choice>,
+    /// Index of the selected option.
+    pub selected: Option<usize>,
+    /// Selection restored by `Form::reset`; see `TextInput::initial_value`.
+    pub initial_selected: Option<Option<usize>>,
+    /// Whether the option list is shown.
+    pub open: bool,
+    /// Option under the keyboard cursor while the list is open.
+    pub highlighted: usize,
+    /// Whether the dropdown can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for Select {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            options: vec![],
+            selected: None,
+            initial_selected: None,
+            open: false,
+            highlighted: 0,
+            focusable: true,
+            tab_index: 0,
+            rules: vec![],
+            error: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl Select {
+    /// Select option `index`. Returns `true` if the selection changed.
+    pub fn select(&mut self, index: usize) -> bool {
+        if index >= self.options.len() || self.selected == Some(index) {
+            return false;
+        }
+        self.selected = Some(index);
+        true
+    }
+
+    /// The selected option.
+    pub fn selected_choice(&self) -> Option<&Choice> {
+        self.options.get(self.selected?)
+    }
+
+    /// Show the option list, highlighting the selected option.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.highlighted = self.selected.unwrap_or(0);
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    fn on_key(&mut self, event: &KeyEvent) -> Option<bool> {
+        let n = self.options.len();
+        if n == 0 {
+            return None;
+        }
+        if self.open {
+            match event.key {
+                Key::Up => self.highlighted = self.highlighted.saturating_sub(1),
+                Key::Down => self.highlighted = (self.highlighted + 1).min(n - 1),
+                Key::Home => self.highlighted = 0,
+                Key::End => self.highlighted = n - 1,
+                Key::Enter | Key::Char(' ') => {
+                    self.close();
+                    return Some(self.select(self.highlighted));
+                }
+                Key::Escape => self.close(),
+                _ => return None,
+            }
+            return Some(false);
+        }
+        if event.key == Key::Char(' ') || (event.key == Key::Down && event.modifiers.alt) {
+            self.open();
+            return Some(false);
+        }
+        let index = match (event.key, self.selected) {
+            (Key::Up, Some(i)) => i.saturating_sub(1),
+            (Key::Down, Some(i)) => (i + 1).min(n - 1),
+            (Key::Up | Key::Down | Key::Home, _) => 0,
+            (Key::End, _) => n - 1,
+            _ => return None,
+        };
+        Some(self.select(index))
+    }
+}
+
+impl From<Select> for Element {
+    fn from(mut s: Select) -> Self {
+        s.initial_selected.get_or_insert(s.selected);
+        Element::Select(s)
+    }
+}
+
+impl IntoObject for Select {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── Slider ───────────────────────────────────────────────────────────────────
+
+/// A value picked from `min..=max` by dragging a thumb along a track.
+#[derive(Debug, Clone)]
+pub struct Slider {
+    /// Optional label text shown next to the track.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub min: f64,
+    pub max: f64,
+    /// Values snap to `min + k * step`; `0` allows any value (defaults to `1`).
+    pub step: f64,
+    pub value: f64,
+    /// Value restored by `Form::reset`; see `TextInput::initial_value`.
+    pub initial_value: Option<f64>,
+    /// Whether the slider can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed rule from the last validation.
+    pub error: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for Slider {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            min: 0.0,
+            max: 100.0,
+            step: 1.0,
+            value: 0.0,
+            initial_value: None,
+            focusable: true,
+            tab_index: 0,
+            rules: vec![],
+            error: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl Slider {
+    /// Set the value, snapped to `step` and clamped to `min..=max`. Returns
+    /// `true` if it changed.
+    pub fn set_value(&mut self, value: f64) -> bool {
+        let mut v = value;
+        if self.step > 0.0 {
+            v = self.min + ((v - self.min) / self.step).round() * self.step;
+            v = round_to_step(v, self.step);
+        }
+        let v = v.clamp(self.min, self.max.max(self.min));
+        std::mem::replace(&mut self.value, v) != v
+    }
+
+    /// Move by `steps` steps (or hundredths of the range if `step` is `0`).
+    pub fn step_by(&mut self, steps: f64) -> bool {
+        let step = if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 100.0
+        };
+        self.set_value(self.value + steps * step)
+    }
+
+    /// Where the value lies in the range, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    fn on_key(&mut self, event: &KeyEvent) -> Option<bool> {
+        Some(match event.key {
+            Key::Left | Key::Down => self.step_by(-1.0),
+            Key::Right | Key::Up => self.step_by(1.0),
+            Key::PageDown => self.step_by(-10.0),
+            Key::PageUp => self.step_by(10.0),
+            Key::Home => self.set_value(self.min),
+            Key::End => self.set_value(self.max),
+            _ => return None,
+        })
+    }
+}
+
+impl From<Slider> for Element {
+    fn from(mut s: Slider) -> Self {
+        s.initial_value.get_or_insert(s.value);
+        Element::Slider(s)
+    }
+}
+
+impl IntoObject for Slider {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── NumberInput ──────────────────────────────────────────────────────────────
+
+/// A text field for numbers. The text is edited like a `TextInput`, limited
+/// to characters that can appear in a number; `Up`/`Down` step the value.
+/// The enclosing `Form` rejects text that does not parse or lies outside
+/// `min..=max`.
+#[derive(Debug, Clone)]
+pub struct NumberInput {
+    /// Optional label text shown next to the input field.
+    pub label: SharedStr,
+    pub name: SharedStr,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Amount added or removed by `Up`/`Down` (defaults to `1`).
+    pub step: f64,
+    /// The text as typed.
+    pub value: String,
+    /// Caret and selection within `value`.
+    pub selection: TextSelection,
+    /// Value restored by `Form::reset`; see `TextInput::initial_value`.
+    pub initial_value: Option<String>,
+    /// Whether the input can receive keyboard focus (defaults to `true`).
+    pub focusable: bool,
+    /// Tab order; see `Button::tab_index`.
+    pub tab_index: i32,
+    /// Checked by the enclosing `Form` before it submits.
+    pub rules: Vec<Rule>,
+    /// Message of the first failed check from the last validation.
+    pub error: Option<String>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Default for NumberInput {
+    fn default() -> Self {
+        Self {
+            label: SharedStr::default(),
+            name: SharedStr::default(),
+            min: None,
+            max: None,
+            step: 1.0,
+            value: String::new(),
+            selection: TextSelection::default(),
+            initial_value: None,
+            focusable: true,
+            tab_index: 0,
+            rules: vec![],
+            error: None,
+            class: SharedStr::default(),
+            key: SharedStr::default(),
+        }
+    }
+}
+
+impl NumberInput {
+    /// Parse the text: `Ok(None)` when empty, an error message when it is not
+    /// a number or out of range.
+    pub fn parse(&self) -> Result<Option<f64>, String> {
+        let text = self.value.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let n = match text.parse::<f64>() {
+            Ok(n) if n.is_finite() => n,
+            _ => return Err("Enter a number".to_string()),
+        };
+        if let Some(min) = self.min
+            && n < min
+        {
+            return Err(format!("Must be at least {min}"));
+        }
+        if let Some(max) = self.max
+            && n > max
+        {
+            return Err(format!("Must be at most {max}"));
+        }
+        Ok(Some(n))
+    }
+
+    /// The parsed value, if the text is a valid number.
+    pub fn number(&self) -> Option<f64> {
+        self.parse().ok().flatten()
+    }
+
+    /// Apply an editing command. Inserted text is limited to characters that
+    /// can appear in a number. Returns `true` if the text changed.
+    pub fn edit(&mut self, cmd: &EditCommand) -> bool {
+        let filtered;
+        let cmd = match cmd {
+            EditCommand::Insert(s) => {
+                filtered = EditCommand::Insert(
+                    s.chars()
+                        .filter(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                        .collect(),
+                );
+                &filtered
+            }
+            cmd => cmd,
+        };
+        let rules = EditRules {
+            single_line: true,
+            ..EditRules::default()
+        };
+        text_edit::apply(&mut self.value, &mut self.selection, cmd, rules)
+    }
+
+    /// Replace the text and put the caret at its end.
+    pub fn set_value(&mut self, value: &str) {
+        self.value.clear();
+        self.selection = TextSelection::default();
+        self.edit(&EditCommand::Insert(value.to_string()));
+    }
+
+    /// Add `steps * step` to the current number (or to `min`, or zero, when
+    /// the text is not a number), clamped to `min..=max`.
+    pub fn step_by(&mut self, steps: f64) -> bool {
+        let current = self
+            .value
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|n| n.is_finite());
+        let base = current.or(self.min).unwrap_or(0.0);
+        let mut n = round_to_step(base + steps * self.step, self.step);
+        if let Some(min) = self.min {
+            n = n.max(min);
+        }
+        if let Some(max) = self.max {
+            n = n.min(max);
+        }
+        let text = n.to_string();
+        if text == self.value {
+            return false;
+        }
+        self.set_value(&text);
+        true
+    }
+}
+
+impl From<NumberInput> for Element {
+    fn from(mut n: NumberInput) -> Self {
+        if n.initial_value.is_none() {
+            n.initial_value = Some(n.value.clone());
+        }
+        Element::NumberInput(n)
+    }
+}
+
+impl IntoObject for NumberInput {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+/// Round away floating point noise (`0.1 + 0.2`) to the precision of `step`.
+fn round_to_step(value: f64, step: f64) -> f64 {
+    let decimals = step
+        .to_string()
+        .split_once('.')
+        .map_or(0, |(_, frac)| frac.len());
+    format!("{value:.decimals$}").parse().unwrap_or(value)
+}
+
+// ── World integration ────────────────────────────────────────────────────────
+
+impl World {
+    /// Deliver a key press to the form control at `path`. Returns `None` if
+    /// the node is not one or does not use the key.
+    pub(crate) fn control_key(&mut self, path: &[usize], event: &KeyEvent) -> Option<bool> {
+        let Some(NodeMut::Object(Object::Element(element))) = self.root.node_mut(path) else {
+            return None;
+        };
+        let changed = match element {
+            Element::Checkbox(c) => c.on_key(event)?,
+            Element::RadioGroup(r) => r.on_key(event)?,
+            Element::Select(s) => s.on_key(event)?,
+            Element::Slider(s) => s.on_key(event)?,
+            Element::NumberInput(_) => match event.key {
+                Key::Up => return Some(self.step_number(path, 1.0)),
+                Key::Down => return Some(self.step_number(path, -1.0)),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if changed {
+            self.send_input_changed(path);
+        }
+        Some(true)
+    }
+
+    /// Default action of a pointer event on the form control at `path`: a
+    /// click toggles a checkbox, picks an option or follows a link in rich
+    /// text, and pressing a slider moves its thumb there and captures the
+    /// pointer to drag it. Returns `true` if the control handled the event.
+    pub(crate) fn control_pointer(
+        &mut self,
+        path: &[usize],
+        kind: PointerEventKind,
+        position: Point,
+    ) -> bool {
+        let Some(rect) = self.layout().find(path).map(|n| n.rect) else {
+            return false;
+        };
+        let dragging = self.pointer.captured.as_deref() == Some(path);
+        let width = self.available_width(path);
+        let style = self.text_style_at(path);
+        let Some(NodeMut::Object(Object::Element(element))) = self.root.node_mut(path) else {
+            return false;
+        };
+        let changed = match (element, kind) {
+            (Element::Checkbox(c), PointerEventKind::Up) => {
+                c.toggle();
+                true
+            }
+            (Element::RadioGroup(r), PointerEventKind::Up) => {
+                match crate::layout::radio_option_at(r, rect, position) {
+                    Some(i) => r.select(i),
+                    None => return false,
+                }
+            }
+            (Element::Select(s), PointerEventKind::Up) => {
+                match crate::layout::select_option_at(s, &style, rect, position) {
+                    Some(i) if s.open => {
+                        s.close();
+                        s.select(i)
+                    }
+                    _ if s.open => {
+                        s.close();
+                        false
+                    }
+                    _ => {
+                        s.open();
+                        false
+                    }
+                }
+            }
+            (Element::RichText(t), PointerEventKind::Up) => {
+                let link = crate::layout::rich_text_link_at(t, &style, rect, width, position);
+                let Some(target) = link else {
+                    return false;
+                };
+                event_bus().send(crate::elements::LinkClicked {
+                    path: path.to_vec(),
+                    target: target.clone(),
+                });
+                return true;
+            }
+            (Element::Slider(s), PointerEventKind::Down) => {
+                let value = crate::layout::slider_value_at(s, &style, rect, position.x);
+                self.pointer.captured = Some(path.to_vec());
+                s.set_value(value)
+            }
+            (Element::Slider(s), PointerEventKind::Move) if dragging => {
+                let value = crate::layout::slider_value_at(s, &style, rect, position.x);
+                s.set_value(value)
+            }
+            _ => return false,
+        };
+        if changed {
+            self.send_input_changed(path);
+        }
+        true
+    }
+
+    fn step_number(&mut self, path: &[usize], steps: f64) -> bool {
+        if let Some(NodeMut::Object(Object::Element(Element::NumberInput(n)))) =
+            self.root.node_mut(path)
+            && n.step_by(steps)
+        {
+            let edited = crate::text_edit::TextEdited {
+                path: path.to_vec(),
+                name: n.name.clone(),
+                value: n.value.clone(),
+            };
+            self.focus.edited = true;
+            event_bus().send(edited);
+        }
+        true
+    }
+
+    fn send_input_changed(&self, path: &[usize]) {
+        let Some(crate::object::Node::Object(Object::Element(element))) = self.root.node(path)
+        else {
+            return;
+        };
+        if let Some(field) = crate::form::as_field(element) {
+            event_bus().send(InputChanged {
+                path: path.to_vec(),
+                name: field.name().clone(),
+                value: field.value(),
+            });
+        }
+    }
+}
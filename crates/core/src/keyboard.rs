@@ -0,0 +1,191 @@
+// ============================================================================
+// Keyboard input
+// Key events go to the focused node. `Tab`/`Shift+Tab` move focus, `Enter` or
+// `Space` activate a focused button, form controls handle their own keys (see
+// `inputs`), a focused text field edits its value, and `Enter` anywhere inside
+// a `Form` submits it (`Ctrl+Enter` in a `TextArea`).
+// ============================================================================
+
+use crate::elements::Element;
+use crate::event_bus::event_bus;
+use crate::object::{Node, NodeMut, Object, World};
+use crate::text_edit::{EditCommand, TextCommitted, TextEdited};
+
+/// A key, after keyboard layout has been applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// A printable character (including `' '`).
+    Char(char),
+    Tab,
+    Enter,
+    Escape,
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+}
+
+/// Modifier keys held during a key press.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers {
+        shift: false,
+        ctrl: false,
+        alt: false,
+        meta: false,
+    };
+    pub const SHIFT: Modifiers = Modifiers {
+        shift: true,
+        ..Modifiers::NONE
+    };
+    pub const CTRL: Modifiers = Modifiers {
+        ctrl: true,
+        ..Modifiers::NONE
+    };
+}
+
+/// A key press fed into `World::dispatch_key`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: Key,
+    pub modifiers: Modifiers,
+}
+
+impl KeyEvent {
+    /// A key press without modifiers.
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: Modifiers::NONE,
+        }
+    }
+
+    pub fn with_modifiers(key: Key, modifiers: Modifiers) -> Self {
+        Self { key, modifiers }
+    }
+}
+
+impl World {
+    /// Deliver a key press. Returns `true` if it was handled.
+    pub fn dispatch_key(&mut self, event: KeyEvent) -> bool {
+        if event.key == Key::Tab {
+            return if event.modifiers.shift {
+                self.focus_prev()
+            } else {
+                self.focus_next()
+            };
+        }
+
+        let Some(focused) = self.focused().cloned() else {
+            return false;
+        };
+        // The input method owns the keyboard while it is composing.
+        if self.is_composing() {
+            return true;
+        }
+        if let Some(handled) = self.control_key(&focused, &event) {
+            return handled;
+        }
+        let node = self.root.node(&focused);
+        let is_button = node.is_some_and(|n| n.as_button().is_some());
+        let is_text_area = matches!(
+            node,
+            Some(Node::Object(Object::Element(Element::TextArea(_))))
+        );
+
+        match event.key {
+            Key::Enter | Key::Char(' ') if is_button => self.activate(&focused),
+            // `Ctrl+Enter` still submits from a text area.
+            Key::Enter if is_text_area && !event.modifiers.ctrl => {
+                self.edit_text(&focused, EditCommand::Insert("\n".to_string()))
+            }
+            Key::Enter => {
+                self.commit_text();
+                self.submit_enclosing_form(&focused)
+            }
+            _ => match EditCommand::from_key(&event) {
+                Some(cmd) => self.edit_text(&focused, cmd),
+                None => false,
+            },
+        }
+    }
+
+    /// Apply `cmd` to the text field at `path`, if that is what it is.
+    fn edit_text(&mut self, path: &[usize], cmd: EditCommand) -> bool {
+        let (changed, name, value) = match self.root.node_mut(path) {
+            Some(NodeMut::Object(Object::Element(Element::TextInput(t)))) => {
+                (t.edit(&cmd), t.name.clone(), &t.value)
+            }
+            Some(NodeMut::Object(Object::Element(Element::TextArea(t)))) => {
+                (t.edit(&cmd), t.name.clone(), &t.value)
+            }
+            Some(NodeMut::Object(Object::Element(Element::NumberInput(n)))) => {
+                (n.edit(&cmd), n.name.clone(), &n.value)
+            }
+            _ => return false,
+        };
+        if changed {
+            let edited = TextEdited {
+                path: path.to_vec(),
+                name,
+                value: value.clone(),
+            };
+            self.focus.edited = true;
+            event_bus().send(edited);
+        }
+        true
+    }
+
+    /// Send `TextCommitted` for the focused text field if it was edited since
+    /// it gained focus or was last committed.
+    pub(crate) fn commit_text(&mut self) {
+        if !std::mem::take(&mut self.focus.edited) {
+            return;
+        }
+        let Some(path) = self.focus.focused().cloned() else {
+            return;
+        };
+        let (name, value) = match self.root.node(&path) {
+            Some(Node::Object(Object::Element(Element::TextInput(t)))) => {
+                (t.name.clone(), &t.value)
+            }
+            Some(Node::Object(Object::Element(Element::TextArea(t)))) => (t.name.clone(), &t.value),
+            Some(Node::Object(Object::Element(Element::NumberInput(n)))) => {
+                (n.name.clone(), &n.value)
+            }
+            _ => return,
+        };
+        event_bus().send(TextCommitted {
+            path,
+            name,
+            value: value.clone(),
+        });
+    }
+
+    /// Submit the innermost `Form` containing `path`, if any.
+    fn submit_enclosing_form(&mut self, path: &[usize]) -> bool {
+        let form = (0..path.len()).rev().find(|&i| {
+            matches!(
+                self.root.node(&path[..i]),
+                Some(Node::Object(Object::Element(Element::Form(_))))
+            )
+        });
+        match form {
+            Some(i) => self.submit_form(&path[..i]),
+            None => false,
+        }
+    }
+}
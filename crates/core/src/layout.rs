@@ -1,5 +1,14 @@
-use crate::object::Object;
-use crate::types::{HAlign, Size, VAlign, VIEWPORT_HEIGHT, VIEWPORT_WIDTH};
+use std::collections::HashMap;
+
+use crate::elements::{Button, Element, RichText, TextArea, TextInput};
+use crate::inputs::{Checkbox, Choice, NumberInput, RadioGroup, Select, Slider};
+use crate::object::{Node, NodePath, Object};
+use crate::pointer::PointerHandler;
+use crate::shared_str::SharedStr;
+use crate::stylesheet::Style;
+use crate::text::{TextStyle, measure_rich, measure_styled};
+use crate::theme::Theme;
+use crate::types::{HAlign, Point, Rect, Size, VAlign, VIEWPORT_HEIGHT, VIEWPORT_WIDTH};
 
 #[derive(Debug, Clone)]
 pub struct Board {
@@ -8,6 +17,13 @@ pub struct Board {
     pub h_align: HAlign,
     pub v_align: VAlign,
     pub children: Vec<Object>,
+    pub on_pointer: Option<PointerHandler>,
+    /// Default text style for everything inside.
+    pub text_style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
 impl Default for Board {
@@ -18,28 +34,767 @@ impl Default for Board {
             h_align: HAlign::Center,
             v_align: VAlign::Middle,
             children: vec![],
+            on_pointer: None,
+            text_style: TextStyle::default(),
+            class: SharedStr::default(),
+            key: SharedStr::default(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Card {
     pub children: Vec<Object>,
+    pub on_pointer: Option<PointerHandler>,
+    /// Default text style for everything inside.
+    pub text_style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
-impl Default for Card {
-    fn default() -> Self {
-        Self { children: vec![] }
-    }
+#[derive(Debug, Clone, Default)]
+pub struct Column {
+    pub children: Vec<Object>,
+    pub on_pointer: Option<PointerHandler>,
+    /// Default text style for everything inside.
+    pub text_style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Row {
     pub children: Vec<Object>,
+    pub on_pointer: Option<PointerHandler>,
+    /// Default text style for everything inside.
+    pub text_style: TextStyle,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
 }
 
-impl Default for Row {
-    fn default() -> Self {
-        Self { children: vec![] }
+// ============================================================================
+// Layout computation
+// A deliberately simple box model: `Board` fills the viewport and aligns its
+// children as a vertical stack, `Card` stacks children vertically inside a
+// padding, `Column` stacks them without one and `Row` places them side by
+// side. Gaps come from the theme's spacing scale, and padding from the style
+// sheet or the theme. Leaf elements have an intrinsic
+// size; the text they show is shaped with the bundled fonts (see `text`).
+// ============================================================================
+
+/// Room an input field reserves for each character it is sized for.
+pub const CHAR_WIDTH: f32 = 8.0;
+/// Height of one line of text.
+pub const LINE_HEIGHT: f32 = 20.0;
+/// Gap between siblings in a stack with the default theme, and between the
+/// label and the field of a control.
+pub const SPACING: f32 = 8.0;
+/// Inner padding of a `Card` with the default theme.
+pub const CARD_PADDING: f32 = 12.0;
+/// Height of single-line controls (buttons, inputs).
+pub const CONTROL_HEIGHT: f32 = 28.0;
+/// Length of a `Slider` track.
+pub const SLIDER_WIDTH: f32 = 160.0;
+
+/// A node of the computed layout tree, mirroring the visible part of the
+/// `Object` tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutNode {
+    /// Where this node lives in the `Object` tree.
+    pub path: NodePath,
+    /// Position and size in viewport coordinates.
+    pub rect: Rect,
+    pub children: Vec<LayoutNode>,
+}
+
+impl LayoutNode {
+    /// Find the layout node for `path`.
+    pub fn find(&self, path: &[usize]) -> Option<&LayoutNode> {
+        if self.path == path {
+            return Some(self);
+        }
+        self.children
+            .iter()
+            .filter(|c| path.starts_with(&c.path))
+            .find_map(|c| c.find(path))
+    }
+
+    /// Return the chain of nodes under `point`, from the outermost to the
+    /// deepest one. Later siblings are on top, so they are tested first.
+    pub fn hit_test(&self, point: crate::types::Point) -> Vec<NodePath> {
+        let mut chain = Vec::new();
+        if self.rect.contains(point) {
+            self.hit_into(point, &mut chain);
+        }
+        chain
+    }
+
+    fn hit_into(&self, point: crate::types::Point, chain: &mut Vec<NodePath>) {
+        chain.push(self.path.clone());
+        if let Some(child) = self.children.iter().rev().find(|c| c.rect.contains(point)) {
+            child.hit_into(point, chain);
+        }
+    }
+}
+
+/// Lay out `root` inside `viewport`.
+pub fn compute_layout(root: &Object, viewport: Rect) -> LayoutNode {
+    compute_layout_in(root, viewport, &TextStyle::default())
+}
+
+/// Lay out `root` inside `viewport`, with everything inheriting `base`.
+/// Text roles are styled by the default theme.
+pub fn compute_layout_in(root: &Object, viewport: Rect, base: &TextStyle) -> LayoutNode {
+    compute_layout_styled(root, viewport, base, &HashMap::new(), &Theme::default())
+}
+
+/// Lay out `root` inside `viewport`, with the styles a style sheet gives
+/// nodes: text styles between what they inherit and their own, and padding
+/// instead of `theme`'s. Text roles and gaps come from `theme`.
+pub(crate) fn compute_layout_styled(
+    root: &Object,
+    viewport: Rect,
+    base: &TextStyle,
+    sheet: &HashMap<NodePath, Style>,
+    theme: &Theme,
+) -> LayoutNode {
+    let ctx = LayoutContext {
+        viewport,
+        sheet,
+        theme,
+    };
+    arrange(
+        root,
+        Vec::new(),
+        viewport.x,
+        viewport.y,
+        &ctx,
+        viewport.width,
+        base,
+    )
+}
+
+/// The room the node at `path` of `root` is measured in, and its text
+/// wrapped at, when laid out by `compute_layout_styled`.
+pub(crate) fn available_width(
+    root: &Object,
+    path: &[usize],
+    viewport: Rect,
+    base: &TextStyle,
+    sheet: &HashMap<NodePath, Style>,
+    theme: &Theme,
+) -> f32 {
+    let ctx = LayoutContext {
+        viewport,
+        sheet,
+        theme,
+    };
+    let mut avail = viewport.width;
+    let mut obj = root;
+    let mut style = text_style(root, &ctx.restyle(&[], base), theme);
+    for (depth, &i) in path.iter().enumerate() {
+        let here = &path[..depth];
+        let pad = ctx.padding(here, Node::Object(obj));
+        let inner = (avail - 2.0 * pad).max(0.0);
+        let (children, axis, room) = match obj {
+            Object::Board(b) => (
+                &b.children,
+                Axis::Vertical,
+                resolve(b.width, viewport) - 2.0 * pad,
+            ),
+            Object::Card(c) => (&c.children, Axis::Vertical, inner),
+            Object::Column(c) => (&c.children, Axis::Vertical, inner),
+            Object::Row(r) => (&r.children, Axis::Horizontal, inner),
+            Object::Element(Element::Switch(s)) => (&s.children, Axis::Vertical, avail),
+            Object::Element(Element::Query(q)) => (&q.children, Axis::Vertical, inner),
+            Object::Element(Element::Markdown(m)) => (&m.children, Axis::Vertical, inner),
+            Object::Element(Element::Form(f)) => (&f.children, Axis::Vertical, inner),
+            _ => return avail,
+        };
+        // A form's buttons do not wrap.
+        let Some(child) = children.get(i) else {
+            return avail;
+        };
+        avail = child_widths(children, here, &ctx, axis, room, &style)[i];
+        style = text_style(child, &ctx.restyle(&path[..=depth], &style), theme);
+        obj = child;
+    }
+    avail
+}
+
+/// The intrinsic size of `obj`: `(width, height)`.
+pub fn measure(obj: &Object, viewport: Rect) -> (f32, f32) {
+    let ctx = LayoutContext {
+        viewport,
+        sheet: &HashMap::new(),
+        theme: &Theme::default(),
+    };
+    measure_in(obj, &[], &ctx, viewport.width, &TextStyle::default())
+}
+
+/// What laying out a node depends on besides the node itself.
+struct LayoutContext<'a> {
+    viewport: Rect,
+    /// Styles from the style sheet, by path.
+    sheet: &'a HashMap<NodePath, Style>,
+    theme: &'a Theme,
+}
+
+impl LayoutContext<'_> {
+    /// What the node at `path` inherits, with the style sheet's style for it
+    /// on top.
+    fn restyle(&self, path: &[usize], inherited: &TextStyle) -> TextStyle {
+        restyle(self.sheet, path, inherited)
+    }
+
+    /// Room inside the box of `node` at `path`: the style sheet's, or else
+    /// the theme's.
+    fn padding(&self, path: &[usize], node: Node) -> f32 {
+        self.sheet
+            .get(path)
+            .and_then(|s| s.padding)
+            .unwrap_or_else(|| self.theme.padding(node))
+    }
+
+    /// Gap between siblings in a stack.
+    fn gap(&self) -> f32 {
+        self.theme.spacing.sm
+    }
+}
+
+fn restyle(sheet: &HashMap<NodePath, Style>, path: &[usize], inherited: &TextStyle) -> TextStyle {
+    match sheet.get(path) {
+        Some(style) => style.text.inherit(inherited),
+        None => inherited.clone(),
+    }
+}
+
+/// The text style `obj` sets for what is inside it, on top of `inherited`,
+/// with `theme`'s style for its role in between.
+fn text_style(obj: &Object, inherited: &TextStyle, theme: &Theme) -> TextStyle {
+    let own = match obj {
+        Object::Board(b) => &b.text_style,
+        Object::Card(c) => &c.text_style,
+        Object::Column(c) => &c.text_style,
+        Object::Row(r) => &r.text_style,
+        Object::Element(Element::Text(t)) => &t.style,
+        Object::Element(Element::RichText(t)) => &t.style,
+        Object::Element(Element::Markdown(m)) => &m.style,
+        _ => return inherited.clone(),
+    };
+    match own.role {
+        Some(role) => own.inherit(&theme.role_style(role).inherit(inherited)),
+        None => own.inherit(inherited),
+    }
+}
+
+impl Object {
+    /// The text style in effect at the node at `path`: its own, on top of
+    /// those set by its ancestors. Text roles are styled by the default
+    /// theme.
+    pub fn text_style_at(&self, path: &[usize]) -> TextStyle {
+        self.text_style_in(path, &TextStyle::default())
+    }
+
+    /// Like `text_style_at`, with the root inheriting `base`.
+    pub fn text_style_in(&self, path: &[usize], base: &TextStyle) -> TextStyle {
+        self.text_style_styled(path, base, &HashMap::new(), &Theme::default())
+    }
+
+    /// Like `text_style_in`, with style sheet styles by path and text roles
+    /// styled by `theme`.
+    pub(crate) fn text_style_styled(
+        &self,
+        path: &[usize],
+        base: &TextStyle,
+        sheet: &HashMap<NodePath, Style>,
+        theme: &Theme,
+    ) -> TextStyle {
+        let mut style = text_style(self, &restyle(sheet, &[], base), theme);
+        let mut obj = self;
+        for (depth, &i) in path.iter().enumerate() {
+            let inherited = restyle(sheet, &path[..=depth], &style);
+            match obj.children().get(i) {
+                Some(child) => {
+                    style = text_style(child, &inherited, theme);
+                    obj = child;
+                }
+                // A form's buttons.
+                None => return inherited,
+            }
+        }
+        style
+    }
+}
+
+/// The size of `obj` when it inherits `inherited` and may be `avail` wide.
+fn measure_in(
+    obj: &Object,
+    path: &[usize],
+    ctx: &LayoutContext,
+    avail: f32,
+    inherited: &TextStyle,
+) -> (f32, f32) {
+    let style = &text_style(obj, &ctx.restyle(path, inherited), ctx.theme);
+    let viewport = ctx.viewport;
+    let pad = ctx.padding(path, Node::Object(obj));
+    let padded = |(w, h): (f32, f32)| (w + 2.0 * pad, h + 2.0 * pad);
+    // Stacked children share the room inside the padding.
+    let inner = (avail - 2.0 * pad).max(0.0);
+    let stack = |children: &[Object], axis| stack_size(children, path, ctx, axis, inner, style);
+    match obj {
+        Object::Board(b) => (resolve(b.width, viewport), resolve(b.height, viewport)),
+        Object::Card(c) => padded(stack(&c.children, Axis::Vertical)),
+        Object::Column(c) => padded(stack(&c.children, Axis::Vertical)),
+        Object::Row(r) => padded(stack(&r.children, Axis::Horizontal)),
+        Object::Girl(_) => (100.0, 200.0),
+        Object::Element(e) => match e {
+            Element::Text(t) => {
+                let layout = measure_styled(&t.text, style, Some(avail));
+                (layout.width, layout.height)
+            }
+            Element::RichText(t) => {
+                let layout = measure_rich(&t.spans, style, Some(avail));
+                (layout.width, layout.height)
+            }
+            // Formatted times are about as wide as their format string.
+            Element::TextClock(t) => text_size(&t.format, style),
+            Element::Button(b) => button_size(b, style, pad),
+            Element::TextInput(t) => text_input_size(t, style),
+            Element::TextArea(t) => text_area_size(t, style),
+            Element::Checkbox(c) => checkbox_size(c, style),
+            Element::RadioGroup(r) => radio_group_size(r, style),
+            Element::Select(s) => select_size(s, style),
+            Element::Slider(s) => slider_size(s, style),
+            Element::NumberInput(n) => number_input_size(n, style),
+            Element::Switch(s) => s
+                .children
+                .get(s.active)
+                .map(|c| measure_in(c, &[path, &[s.active]].concat(), ctx, avail, style))
+                .unwrap_or((0.0, 0.0)),
+            Element::Query(q) => padded(stack(&q.children, Axis::Vertical)),
+            Element::Markdown(m) => padded(stack(&m.children, Axis::Vertical)),
+            Element::Form(f) => {
+                let (w, h) = stack(&f.children, Axis::Vertical);
+                let (bw, bh) = form_buttons_size(f, path, ctx, style);
+                padded((w.max(bw), h + form_gap(f, ctx) + bh))
+            }
+        },
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+fn resolve(size: Size, viewport: Rect) -> f32 {
+    match size {
+        Size::ViewportWidth => viewport.width,
+        Size::ViewportHeight => viewport.height,
+    }
+}
+
+/// The size of `text` set on one line in `style`.
+fn text_size(text: &str, style: &TextStyle) -> (f32, f32) {
+    (measure_styled(text, style, None).width, LINE_HEIGHT)
+}
+
+/// The size of button `b`, with its text `padding` in from either side.
+fn button_size(b: &Button, style: &TextStyle, padding: f32) -> (f32, f32) {
+    (text_size(&b.text, style).0 + 2.0 * padding, CONTROL_HEIGHT)
+}
+
+fn text_input_size(t: &TextInput, style: &TextStyle) -> (f32, f32) {
+    let chars = if t.max_len > 0 { t.max_len } else { 20 };
+    let field = chars as f32 * CHAR_WIDTH + SPACING;
+    let label = text_size(&t.label, style).0;
+    (
+        label + SPACING + field,
+        CONTROL_HEIGHT + error_height(&t.error),
+    )
+}
+
+/// Room for a validation message shown below a field.
+fn error_height(error: &Option<String>) -> f32 {
+    if error.is_some() { LINE_HEIGHT } else { 0.0 }
+}
+
+fn text_area_size(t: &TextArea, style: &TextStyle) -> (f32, f32) {
+    let field = t.cols as f32 * CHAR_WIDTH + SPACING;
+    let label = text_size(&t.label, style).0;
+    (
+        label + SPACING + field,
+        t.rows as f32 * LINE_HEIGHT + SPACING + error_height(&t.error),
+    )
+}
+
+fn checkbox_size(c: &Checkbox, style: &TextStyle) -> (f32, f32) {
+    // A square box of one line height, then the label.
+    (
+        LINE_HEIGHT + SPACING + text_size(&c.label, style).0,
+        CONTROL_HEIGHT + error_height(&c.error),
+    )
+}
+
+/// Width of the widest option label.
+fn options_width(options: &[Choice], style: &TextStyle) -> f32 {
+    options
+        .iter()
+        .map(|o| text_size(&o.label, style).0)
+        .fold(0.0, f32::max)
+}
+
+/// Height of the label line above a radio group's options.
+fn radio_label_height(r: &RadioGroup) -> f32 {
+    if r.label.is_empty() { 0.0 } else { LINE_HEIGHT }
+}
+
+fn radio_group_size(r: &RadioGroup, style: &TextStyle) -> (f32, f32) {
+    let options = LINE_HEIGHT + SPACING + options_width(&r.options, style);
+    (
+        text_size(&r.label, style).0.max(options),
+        radio_label_height(r) + r.options.len() as f32 * CONTROL_HEIGHT + error_height(&r.error),
+    )
+}
+
+/// Width of a select's field: the widest option plus a dropdown arrow.
+fn select_field_width(s: &Select, style: &TextStyle) -> f32 {
+    options_width(&s.options, style) + SPACING + LINE_HEIGHT
+}
+
+fn select_size(s: &Select, style: &TextStyle) -> (f32, f32) {
+    let rows = if s.open { 1 + s.options.len() } else { 1 };
+    (
+        text_size(&s.label, style).0 + SPACING + select_field_width(s, style),
+        rows as f32 * CONTROL_HEIGHT + error_height(&s.error),
+    )
+}
+
+fn slider_size(s: &Slider, style: &TextStyle) -> (f32, f32) {
+    (
+        text_size(&s.label, style).0 + SPACING + SLIDER_WIDTH,
+        CONTROL_HEIGHT + error_height(&s.error),
+    )
+}
+
+fn number_input_size(n: &NumberInput, style: &TextStyle) -> (f32, f32) {
+    (
+        text_size(&n.label, style).0 + SPACING + 10.0 * CHAR_WIDTH + SPACING,
+        CONTROL_HEIGHT + error_height(&n.error),
+    )
+}
+
+/// Index of the row of height `height` at `y`, counting from `top`.
+fn row_at(top: f32, height: f32, count: usize, y: f32) -> Option<usize> {
+    let offset = y - top;
+    let index = (offset / height).floor();
+    (offset >= 0.0 && (index as usize) < count).then_some(index as usize)
+}
+
+/// The option of radio group `r`, laid out at `rect`, under `point`.
+pub(crate) fn radio_option_at(r: &RadioGroup, rect: Rect, point: Point) -> Option<usize> {
+    let top = rect.y + radio_label_height(r);
+    row_at(top, CONTROL_HEIGHT, r.options.len(), point.y)
+}
+
+/// The option of the open list of select `s`, laid out at `rect`, under
+/// `point`. The list starts below the field.
+pub(crate) fn select_option_at(
+    s: &Select,
+    style: &TextStyle,
+    rect: Rect,
+    point: Point,
+) -> Option<usize> {
+    let left = rect.x + text_size(&s.label, style).0 + SPACING;
+    if !s.open || point.x < left {
+        return None;
+    }
+    row_at(
+        rect.y + CONTROL_HEIGHT,
+        CONTROL_HEIGHT,
+        s.options.len(),
+        point.y,
+    )
+}
+
+/// The value of slider `s`, laid out at `rect`, at horizontal position `x`.
+pub(crate) fn slider_value_at(s: &Slider, style: &TextStyle, rect: Rect, x: f32) -> f64 {
+    let left = rect.x + text_size(&s.label, style).0 + SPACING;
+    let fraction = ((x - left) / SLIDER_WIDTH).clamp(0.0, 1.0) as f64;
+    s.min + fraction * (s.max - s.min)
+}
+
+/// The link of the span of `t` at `point`, if it is a link. `t` is laid out
+/// in `rect` and wrapped at `max_width`, the room it was measured in (see
+/// `available_width`).
+pub(crate) fn rich_text_link_at<'a>(
+    t: &'a RichText,
+    style: &TextStyle,
+    rect: Rect,
+    max_width: f32,
+    point: Point,
+) -> Option<&'a SharedStr> {
+    let layout = measure_rich(&t.spans, style, Some(max_width));
+    let glyph = layout.glyph_at(point.x - rect.x, point.y - rect.y)?;
+    t.spans.get(glyph.span)?.link.as_ref()
+}
+
+/// The rectangle covering `span` of a text input's displayed text, which
+/// follows `before`. An empty span gives a one pixel wide caret.
+pub(crate) fn text_input_span_rect(
+    t: &TextInput,
+    style: &TextStyle,
+    rect: Rect,
+    before: &str,
+    span: &str,
+) -> Rect {
+    // The field follows the label and pads its text by half a spacing.
+    let text_x = rect.x + text_size(&t.label, style).0 + SPACING + SPACING / 2.0;
+    let x = text_x + text_size(before, style).0;
+    let y = rect.y + (CONTROL_HEIGHT - LINE_HEIGHT) / 2.0;
+    Rect::new(x, y, text_size(span, style).0.max(1.0), LINE_HEIGHT)
+}
+
+/// The sizes of the submit and reset buttons of form `f` at `path`.
+fn form_button_sizes(
+    f: &crate::form::Form,
+    path: &[usize],
+    ctx: &LayoutContext,
+    style: &TextStyle,
+) -> [(f32, f32); 2] {
+    let n = f.children.len();
+    [(&f.submit_button, n), (&f.reset_button, n + 1)].map(|(b, i)| {
+        let path = [path, &[i]].concat();
+        let padding = ctx.padding(&path, Node::Button(b));
+        button_size(b, &ctx.restyle(&path, style), padding)
+    })
+}
+
+/// The size of the buttons of form `f` at `path`, side by side.
+fn form_buttons_size(
+    f: &crate::form::Form,
+    path: &[usize],
+    ctx: &LayoutContext,
+    style: &TextStyle,
+) -> (f32, f32) {
+    let [(sw, sh), (rw, rh)] = form_button_sizes(f, path, ctx, style);
+    (sw + ctx.gap() + rw, sh.max(rh))
+}
+
+/// Gap between a form's fields and its buttons, including the line that
+/// shows the form-level error, if any.
+fn form_gap(f: &crate::form::Form, ctx: &LayoutContext) -> f32 {
+    let gap = if f.children.is_empty() {
+        0.0
+    } else {
+        ctx.gap()
+    };
+    gap + error_height(&f.error)
+}
+
+/// Whether the width of `obj` depends on the room it is given.
+fn wraps(obj: &Object) -> bool {
+    match obj {
+        Object::Board(_) | Object::Girl(_) => false,
+        Object::Card(_) | Object::Column(_) | Object::Row(_) => true,
+        Object::Element(e) => matches!(
+            e,
+            Element::Text(_)
+                | Element::RichText(_)
+                | Element::Markdown(_)
+                | Element::Switch(_)
+                | Element::Query(_)
+                | Element::Form(_)
+        ),
+    }
+}
+
+/// The room each of `children` gets in a stack along `axis` that may be
+/// `avail` wide. Down a column each gets all of it; along a row, children
+/// that wrap share what the others and the gaps leave, the narrowest first
+/// so what they do not use goes to the wider ones.
+fn child_widths(
+    children: &[Object],
+    path: &[usize],
+    ctx: &LayoutContext,
+    axis: Axis,
+    avail: f32,
+    style: &TextStyle,
+) -> Vec<f32> {
+    let mut widths = vec![avail; children.len()];
+    if axis == Axis::Vertical {
+        return widths;
+    }
+    let measure = |i: usize, room: f32| {
+        let child_path = [path, &[i]].concat();
+        measure_in(&children[i], &child_path, ctx, room, style).0
+    };
+    let gaps = ctx.gap() * children.len().saturating_sub(1) as f32;
+    let mut room = avail - gaps;
+    let mut flexible = Vec::new();
+    for (i, c) in children.iter().enumerate() {
+        if wraps(c) {
+            flexible.push(i);
+        } else {
+            room -= measure(i, avail);
+        }
+    }
+    let natural: Vec<f32> = flexible.iter().map(|&i| measure(i, avail)).collect();
+    let mut order: Vec<usize> = (0..flexible.len()).collect();
+    order.sort_by(|&a, &b| natural[a].total_cmp(&natural[b]));
+    for (k, &j) in order.iter().enumerate() {
+        let i = flexible[j];
+        widths[i] = (room / (order.len() - k) as f32).max(0.0);
+        room -= measure(i, widths[i]);
+    }
+    widths
+}
+
+fn stack_size(
+    children: &[Object],
+    path: &[usize],
+    ctx: &LayoutContext,
+    axis: Axis,
+    avail: f32,
+    style: &TextStyle,
+) -> (f32, f32) {
+    let (mut main, mut cross) = (0.0f32, 0.0f32);
+    let widths = child_widths(children, path, ctx, axis, avail, style);
+    for (i, c) in children.iter().enumerate() {
+        let (w, h) = measure_in(c, &[path, &[i]].concat(), ctx, widths[i], style);
+        let (m, x) = if axis == Axis::Vertical {
+            (h, w)
+        } else {
+            (w, h)
+        };
+        main += if i == 0 { m } else { ctx.gap() + m };
+        cross = cross.max(x);
+    }
+    if axis == Axis::Vertical {
+        (cross, main)
+    } else {
+        (main, cross)
+    }
+}
+
+/// Place `children` one after another starting at `at`.
+fn arrange_stack(
+    children: &[Object],
+    path: &NodePath,
+    at: Point,
+    ctx: &LayoutContext,
+    axis: Axis,
+    avail: f32,
+    style: &TextStyle,
+) -> Vec<LayoutNode> {
+    let (mut x, mut y) = (at.x, at.y);
+    let mut nodes = Vec::with_capacity(children.len());
+    let widths = child_widths(children, path, ctx, axis, avail, style);
+    for (i, c) in children.iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(i);
+        let node = arrange(c, child_path, x, y, ctx, widths[i], style);
+        if axis == Axis::Vertical {
+            y += node.rect.height + ctx.gap();
+        } else {
+            x += node.rect.width + ctx.gap();
+        }
+        nodes.push(node);
+    }
+    nodes
+}
+
+fn arrange(
+    obj: &Object,
+    path: NodePath,
+    x: f32,
+    y: f32,
+    ctx: &LayoutContext,
+    avail: f32,
+    inherited: &TextStyle,
+) -> LayoutNode {
+    let (width, height) = measure_in(obj, &path, ctx, avail, inherited);
+    let style = &text_style(obj, &ctx.restyle(&path, inherited), ctx.theme);
+    let rect = Rect::new(x, y, width, height);
+    let pad = ctx.padding(&path, Node::Object(obj));
+    // Where the content of a padded container starts, and the room it has.
+    let (x, y) = (x + pad, y + pad);
+    let at = Point::new(x, y);
+    let inner = (avail - 2.0 * pad).max(0.0);
+    let children = match obj {
+        Object::Board(b) => {
+            let (width, height) = (width - 2.0 * pad, height - 2.0 * pad);
+            let (cw, ch) = stack_size(&b.children, &path, ctx, Axis::Vertical, width, style);
+            let left = match b.h_align {
+                HAlign::Left => x,
+                HAlign::Center => x + (width - cw) / 2.0,
+                HAlign::Right => x + width - cw,
+            };
+            let top = match b.v_align {
+                VAlign::Top => y,
+                VAlign::Middle => y + (height - ch) / 2.0,
+                VAlign::Bottom => y + height - ch,
+            };
+            let at = Point::new(left, top);
+            arrange_stack(&b.children, &path, at, ctx, Axis::Vertical, width, style)
+        }
+        Object::Card(c) => arrange_stack(&c.children, &path, at, ctx, Axis::Vertical, inner, style),
+        Object::Column(c) => {
+            arrange_stack(&c.children, &path, at, ctx, Axis::Vertical, inner, style)
+        }
+        Object::Row(r) => {
+            arrange_stack(&r.children, &path, at, ctx, Axis::Horizontal, inner, style)
+        }
+        Object::Element(Element::Switch(s)) => match s.children.get(s.active) {
+            Some(active) => {
+                let mut child_path = path.clone();
+                child_path.push(s.active);
+                vec![arrange(active, child_path, x, y, ctx, avail, style)]
+            }
+            None => vec![],
+        },
+        Object::Element(Element::Query(q)) => {
+            arrange_stack(&q.children, &path, at, ctx, Axis::Vertical, inner, style)
+        }
+        Object::Element(Element::Markdown(m)) => {
+            arrange_stack(&m.children, &path, at, ctx, Axis::Vertical, inner, style)
+        }
+        Object::Element(Element::Form(f)) => {
+            let mut nodes =
+                arrange_stack(&f.children, &path, at, ctx, Axis::Vertical, inner, style);
+            let (_, fields_h) = stack_size(&f.children, &path, ctx, Axis::Vertical, inner, style);
+            let by = y + fields_h + form_gap(f, ctx);
+            let n = f.children.len();
+            let mut submit = path.clone();
+            submit.push(n);
+            let mut reset = path.clone();
+            reset.push(n + 1);
+            let [(sw, sh), (rw, rh)] = form_button_sizes(f, &path, ctx, style);
+            nodes.push(LayoutNode {
+                path: submit,
+                rect: Rect::new(x, by, sw, sh),
+                children: vec![],
+            });
+            nodes.push(LayoutNode {
+                path: reset,
+                rect: Rect::new(x + sw + ctx.gap(), by, rw, rh),
+                children: vec![],
+            });
+            nodes
+        }
+        _ => vec![],
+    };
+    LayoutNode {
+        path,
+        rect,
+        children,
     }
 }
@@ -2,42 +2,106 @@
 //
 // All implementation details are now split into separate modules for maintainability.
 
+pub mod animation;
 pub mod elements;
 pub mod event_bus;
+pub mod focus;
 pub mod form;
 pub mod girl;
 pub mod handler;
+pub mod ime;
+pub mod inputs;
+pub mod keyboard;
 pub mod layout;
+pub mod markdown;
+pub mod middleware;
+pub mod mock_server;
 pub mod object;
+pub mod offline;
+pub mod pointer;
+pub mod resource;
+pub mod runtime;
 pub mod server_api;
+pub mod shared_str;
 pub mod state;
+pub mod streams;
+pub mod stylesheet;
+pub mod text;
+pub mod text_edit;
+pub mod theme;
 pub mod traits;
 pub mod types;
+pub mod validation;
 
 // Re-export the public API for ergonomic `snow_ui::...` usage.
-pub use crate::elements::{Button, Element, IntervalTimer, Switch, Text, TextClock, TextInput};
+pub use crate::animation::{
+    Animatable, AnimatedProperty, AnimatedValue, Animation, AnimationFinished, Animations, Easing,
+    Repeat, Spring, Tween,
+};
+pub use crate::elements::{
+    Button, ClickCallback, Element, IntervalTimer, LinkClicked, RichText, Switch, Text, TextArea,
+    TextClock, TextInput,
+};
 pub use crate::event_bus::{EventBus, EventBusHandle, EventBusReceiver, event_bus};
-pub use crate::form::Form;
+pub use crate::focus::{FocusEvent, FocusState};
+pub use crate::form::{Form, FormSubmitFailed};
 pub use crate::girl::{Appearance, BodyType, Girl, GirlActions, HairColor, SkinColor};
 pub use crate::handler::{
-    HandlerRegistryEntry, has_registered_handlers, register_handlers_for_instance,
+    ClickHandlerEntry, HandlerRegistryEntry, attach_click_handler, has_click_handler,
+    has_registered_handlers, make_click_callback, register_handlers_for_instance,
+    register_instance_handler,
+};
+pub use crate::ime::{ImeEvent, Preedit};
+pub use crate::inputs::{Checkbox, Choice, InputChanged, NumberInput, RadioGroup, Select, Slider};
+pub use crate::keyboard::{Key, KeyEvent, Modifiers};
+pub use crate::layout::{Board, Card, Column, LayoutNode, Row, compute_layout, compute_layout_in};
+pub use crate::markdown::{Markdown, parse_markdown};
+pub use crate::middleware::{
+    AuthProvider, CancelToken, Cancelled, Interceptor, RefreshableToken, RetryPolicy, StaticToken,
+};
+pub use crate::mock_server::{MockResponse, MockServer, RecordedRequest};
+pub use crate::object::{Node, NodeMut, NodePath, Object, World};
+pub use crate::offline::{
+    CacheRefreshed, Outbox, OutboxChanged, Queued, QueuedRequestFailed, ResponseCache,
+};
+pub use crate::pointer::{
+    EventPhase, PointerEvent, PointerEventKind, PointerHandler, PointerInput, PointerState,
 };
-pub use crate::layout::{Board, Card, Row};
-pub use crate::object::{Object, World};
-pub use crate::server_api::ServerApi;
+pub use crate::resource::{Query, Refetch, Resource, ResourceState};
+pub use crate::server_api::{
+    HttpError, Method, Request, RequestBuilder, Response, ServerApi, Timeout, Unreachable,
+};
+pub use crate::shared_str::SharedStr;
 pub use crate::state::State;
+pub use crate::streams::{
+    ConnectionChanged, ConnectionStatus, FrameRejected, LiveStream, StreamBuilder,
+};
+pub use crate::stylesheet::{NodeState, Selector, Style, StyleRule, StyleSheet, StyleSheetError};
+pub use crate::text::{
+    FontWeight, Glyph, TextLayout, TextLine, TextRole, TextSpan, TextStyle, WrapMode,
+    font_families, load_font, measure_rich, measure_styled, measure_text,
+};
+pub use crate::text_edit::{
+    DisplayText, EditCommand, Motion, TextCommitted, TextEdited, TextSelection, Underline,
+};
+pub use crate::theme::{
+    BoxStyle, Elevation, Palette, Radii, SetTheme, SpacingScale, Theme, ThemeChanged, ThemeMode,
+    ThemeSwitch, Typography,
+};
 pub use crate::traits::{
     ClickHandler, InnerMovement, InnerTicker, IntoObject, Message, MessageContext, MessageHandler,
-    MessageReceiver, UpdateContext,
+    MessageMeta, MessageReceiver, UpdateContext,
 };
-pub use crate::types::{HAlign, Size, VAlign, VIEWPORT_HEIGHT, VIEWPORT_WIDTH};
+pub use crate::types::{Color, HAlign, Point, Rect, Size, VAlign, VIEWPORT_HEIGHT, VIEWPORT_WIDTH};
+pub use crate::validation::{InvalidForm, Rule};
 
 // Pulled in by the old-day convenient prelude and `register_handler!` macro flow.
 pub use inventory;
 
 /// Macro to register a `MessageHandler` implementation and automatically submit it to inventory.
 ///
-/// Actual implementation is the same behavior that existed in the legacy monolithic `lib.rs`.
+/// Prefer the `#[handler]` attribute, which also accepts `#[on(Msg)]` methods and
+/// generic impls; this macro is kept for existing code.
 #[macro_export]
 macro_rules! register_handler {
     (
@@ -51,17 +115,8 @@ macro_rules! register_handler {
 
         $crate::inventory::submit! {
             $crate::HandlerRegistryEntry {
-                element_type_id: || ::std::any::TypeId::of::<$elem_ty>(),
-                register_fn: |any_arc: &::std::sync::Arc<::std::sync::Mutex<dyn ::std::any::Any + Send + Sync>>| {
-                    let borrowed = any_arc.lock().unwrap();
-                    if borrowed.is::<$elem_ty>() {
-                        drop(borrowed);
-                        let ptr = ::std::sync::Arc::as_ptr(any_arc) as *const ::std::sync::Mutex<$elem_ty>;
-                        ::std::mem::forget(any_arc.clone());
-                        let concrete_arc = unsafe { ::std::sync::Arc::from_raw(ptr) };
-                        $crate::event_bus().register_handler::<$elem_ty, $msg_ty>(concrete_arc);
-                    }
-                },
+                element_type_id: ::std::any::TypeId::of::<$elem_ty>,
+                register_fn: $crate::register_instance_handler::<$elem_ty, $msg_ty>,
             }
         }
     };
@@ -85,18 +140,24 @@ macro_rules! actions {
 
 pub mod prelude {
     pub use super::{
-        Appearance, Board, BodyType, Button, Card, ClickHandler, Form, Girl, GirlActions, HAlign,
-        HairColor, HandlerRegistryEntry, InnerMovement, InnerTicker, IntervalTimer, IntoObject,
-        Message, MessageContext, MessageHandler, MessageReceiver, Object, Row, ServerApi,
-        SkinColor, State, Switch, Text, TextClock, TextInput, UpdateContext, VAlign,
-        VIEWPORT_HEIGHT, VIEWPORT_WIDTH, World, event_bus, has_registered_handlers,
+        AnimatedProperty, AnimationFinished, Appearance, Board, BodyType, Button, Card, Checkbox,
+        Choice, ClickHandler, Color, Column, Easing, EventPhase, FocusEvent, FontWeight, Form,
+        FormSubmitFailed, Girl, GirlActions, HAlign, HairColor, HandlerRegistryEntry, ImeEvent,
+        InnerMovement, InnerTicker, InputChanged, IntervalTimer, IntoObject, Key, KeyEvent,
+        LinkClicked, Markdown, Message, MessageContext, MessageHandler, MessageMeta,
+        MessageReceiver, Modifiers, NumberInput, Object, Point, PointerEvent, PointerEventKind,
+        PointerHandler, PointerInput, RadioGroup, Rect, Refetch, Repeat, Resource, RichText, Row,
+        Rule, Select, ServerApi, SetTheme, SharedStr, SkinColor, Slider, Spring, State, StyleSheet,
+        Switch, Text, TextArea, TextClock, TextCommitted, TextEdited, TextInput, TextSelection,
+        TextSpan, TextStyle, Theme, ThemeChanged, ThemeMode, Tween, UpdateContext, VAlign,
+        VIEWPORT_HEIGHT, VIEWPORT_WIDTH, World, WrapMode, event_bus, has_registered_handlers,
         register_handlers_for_instance,
     };
 
     pub use super::inventory;
     pub use crate::actions;
     pub use crate::register_handler;
-    pub use snow_ui_macros::{IntoObject, Message, element, message};
+    pub use snow_ui_macros::{IntoObject, Message, element, handler, message};
     pub use snow_ui_macros::{list, obj};
 
     pub fn default<T: Default>() -> T {
@@ -0,0 +1,375 @@
+// ============================================================================
+// Markdown
+// A `Markdown` element shows CommonMark text as ordinary objects: paragraphs
+// and headings become `RichText`, lists and tables `Column`s and `Row`s,
+// block quotes and code blocks `Card`s. The result is laid out and styled
+// like any other part of the tree: headings, quotes and links take their
+// sizes and colors from the theme, and links send `LinkClicked`.
+// ============================================================================
+
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+
+use crate::elements::{Element, RichText, Text};
+use crate::layout::{Card, Column, Row};
+use crate::object::Object;
+use crate::shared_str::SharedStr;
+use crate::text::{FontWeight, TextRole, TextSpan, TextStyle, WrapMode};
+
+/// CommonMark text, shown as the objects it describes.
+#[derive(Debug, Clone, Default)]
+pub struct Markdown {
+    pub source: SharedStr,
+    /// Set on top of the style inherited from the containers above.
+    pub style: TextStyle,
+    /// `source` as objects, one per block. Made when the element is created
+    /// if empty, and again by `set_source`.
+    pub children: Vec<Object>,
+    /// Style sheet classes, separated by spaces.
+    pub class: SharedStr,
+    /// Style sheet key, selected with `#key`.
+    pub key: SharedStr,
+}
+
+impl Markdown {
+    pub fn new(source: impl Into<SharedStr>) -> Self {
+        let source = source.into();
+        Self {
+            children: parse_markdown(&source),
+            source,
+            ..Default::default()
+        }
+    }
+
+    /// Show `source` instead.
+    pub fn set_source(&mut self, source: impl Into<SharedStr>) {
+        self.source = source.into();
+        self.children = parse_markdown(&self.source);
+    }
+}
+
+impl From<Markdown> for Element {
+    fn from(mut m: Markdown) -> Self {
+        if m.children.is_empty() {
+            m.children = parse_markdown(&m.source);
+        }
+        Element::Markdown(m)
+    }
+}
+
+impl From<Markdown> for Object {
+    fn from(m: Markdown) -> Self {
+        Object::Element(m.into())
+    }
+}
+
+/// Parse `source` as CommonMark, with tables, strikethrough and task lists,
+/// into one object per block.
+pub fn parse_markdown(source: &str) -> Vec<Object> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    blocks(&mut Parser::new_ext(source, options).peekable())
+}
+
+type Events<'a> = std::iter::Peekable<Parser<'a>>;
+
+// ── Blocks ───────────────────────────────────────────────────────────────────
+
+/// The blocks up to the end of the enclosing container, which is consumed.
+fn blocks(events: &mut Events) -> Vec<Object> {
+    let mut out = Vec::new();
+    loop {
+        // Tight list items hold their text without a paragraph around it.
+        if events.peek().is_some_and(is_inline) {
+            out.push(paragraph(inlines(events), TextStyle::default()));
+            continue;
+        }
+        let Some(event) = events.next() else {
+            break;
+        };
+        match event {
+            Event::End(_) => break,
+            Event::Start(Tag::Paragraph) => {
+                let spans = inlines(events);
+                events.next();
+                out.push(paragraph(spans, TextStyle::default()));
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                let spans = inlines(events);
+                events.next();
+                let style = TextStyle {
+                    role: Some(TextRole::Heading(level as u8)),
+                    ..Default::default()
+                };
+                out.push(paragraph(spans, style));
+            }
+            Event::Start(Tag::BlockQuote(_)) => out.push(
+                Card {
+                    children: blocks(events),
+                    text_style: TextStyle {
+                        italic: Some(true),
+                        role: Some(TextRole::Muted),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+                .into(),
+            ),
+            Event::Start(Tag::CodeBlock(_)) => out.push(code_block(events)),
+            Event::Start(Tag::List(start)) => out.push(list(events, start)),
+            Event::Start(Tag::Table(_)) => out.push(table(events)),
+            Event::Start(Tag::FootnoteDefinition(label)) => {
+                out.push(item(format!("[{label}]"), blocks(events)))
+            }
+            Event::Start(Tag::HtmlBlock | Tag::MetadataBlock(_)) => skip(events),
+            Event::Rule => out.push(
+                Text {
+                    text: "———".into(),
+                    style: TextStyle {
+                        role: Some(TextRole::Muted),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+                .into(),
+            ),
+            // Definition lists and anything else: their blocks, in order.
+            Event::Start(_) => out.extend(blocks(events)),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn paragraph(spans: Vec<TextSpan>, style: TextStyle) -> Object {
+    RichText {
+        spans,
+        style,
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A code block, kept as written: in a monospaced font and not wrapped.
+fn code_block(events: &mut Events) -> Object {
+    let mut code = String::new();
+    for event in events.by_ref() {
+        match event {
+            Event::Text(text) => code.push_str(&text),
+            Event::End(_) => break,
+            _ => {}
+        }
+    }
+    code.truncate(code.trim_end_matches('\n').len());
+    Card {
+        children: vec![
+            Text {
+                text: code.into(),
+                style: TextStyle {
+                    family: Some("monospace".into()),
+                    wrap: Some(WrapMode::None),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+            .into(),
+        ],
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A list: each item a row of its marker and its blocks.
+fn list(events: &mut Events, start: Option<u64>) -> Object {
+    let mut items = Vec::new();
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(Tag::Item) => {
+                let marker = match start {
+                    Some(n) => format!("{}.", n + items.len() as u64),
+                    None => "•".to_string(),
+                };
+                items.push(item(marker, blocks(events)));
+            }
+            Event::End(_) => break,
+            _ => {}
+        }
+    }
+    Column {
+        children: items,
+        ..Default::default()
+    }
+    .into()
+}
+
+fn item(marker: String, children: Vec<Object>) -> Object {
+    Row {
+        children: vec![
+            Text {
+                text: marker.into(),
+                ..Default::default()
+            }
+            .into(),
+            Column {
+                children,
+                ..Default::default()
+            }
+            .into(),
+        ],
+        ..Default::default()
+    }
+    .into()
+}
+
+/// A table, laid out column by column so that the cells of each column line
+/// up. The header cells are bold.
+fn table(events: &mut Events) -> Object {
+    let mut columns: Vec<Vec<Object>> = Vec::new();
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(Tag::TableHead) => table_row(events, &mut columns, true),
+            Event::Start(Tag::TableRow) => table_row(events, &mut columns, false),
+            Event::End(_) => break,
+            _ => {}
+        }
+    }
+    Row {
+        children: columns
+            .into_iter()
+            .map(|children| {
+                Column {
+                    children,
+                    ..Default::default()
+                }
+                .into()
+            })
+            .collect(),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn table_row(events: &mut Events, columns: &mut Vec<Vec<Object>>, header: bool) {
+    let mut i = 0;
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(Tag::TableCell) => {
+                let spans = inlines(events);
+                events.next();
+                let style = TextStyle {
+                    weight: header.then_some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+                if columns.len() <= i {
+                    columns.push(Vec::new());
+                }
+                columns[i].push(paragraph(spans, style));
+                i += 1;
+            }
+            Event::End(_) => break,
+            _ => {}
+        }
+    }
+}
+
+/// Skip to the end of the enclosing container.
+fn skip(events: &mut Events) {
+    let mut depth = 0;
+    for event in events.by_ref() {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) if depth == 0 => break,
+            Event::End(_) => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+// ── Inlines ──────────────────────────────────────────────────────────────────
+
+fn is_inline(event: &Event) -> bool {
+    match event {
+        Event::Start(tag) => matches!(
+            tag,
+            Tag::Emphasis
+                | Tag::Strong
+                | Tag::Strikethrough
+                | Tag::Superscript
+                | Tag::Subscript
+                | Tag::Link { .. }
+                | Tag::Image { .. }
+        ),
+        Event::End(_) | Event::Rule | Event::Html(_) => false,
+        _ => true,
+    }
+}
+
+/// Formatting in effect at a point of the text.
+#[derive(Default)]
+struct Inline {
+    bold: usize,
+    italic: usize,
+    struck: usize,
+    links: Vec<SharedStr>,
+}
+
+impl Inline {
+    fn span(&self, text: String, code: bool) -> TextSpan {
+        let mut span = TextSpan::new(text);
+        span.style.weight = (self.bold > 0).then_some(FontWeight::BOLD);
+        span.style.italic = (self.italic > 0).then_some(true);
+        span.style.strikethrough = (self.struck > 0).then_some(true);
+        if code {
+            span.style.family = Some("monospace".into());
+        }
+        match self.links.last() {
+            Some(target) => span.underline().link(target.clone()),
+            None => span,
+        }
+    }
+}
+
+/// The text up to the end of the enclosing block, which is left unconsumed.
+fn inlines(events: &mut Events) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    let mut inline = Inline::default();
+    while let Some(event) = events.peek() {
+        match event {
+            Event::Start(tag) if is_inline(event) => match tag {
+                Tag::Strong => inline.bold += 1,
+                // Images show their description.
+                Tag::Emphasis | Tag::Image { .. } => inline.italic += 1,
+                Tag::Strikethrough => inline.struck += 1,
+                Tag::Link { dest_url, .. } => inline.links.push(dest_url.to_string().into()),
+                _ => {}
+            },
+            Event::End(end) => match end {
+                TagEnd::Strong => inline.bold -= 1,
+                TagEnd::Emphasis | TagEnd::Image => inline.italic -= 1,
+                TagEnd::Strikethrough => inline.struck -= 1,
+                TagEnd::Link => {
+                    inline.links.pop();
+                }
+                TagEnd::Superscript | TagEnd::Subscript => {}
+                _ => break,
+            },
+            Event::Text(text) => spans.push(inline.span(text.to_string(), false)),
+            Event::Code(code) | Event::InlineMath(code) | Event::DisplayMath(code) => {
+                spans.push(inline.span(code.to_string(), true))
+            }
+            Event::SoftBreak => spans.push(inline.span(" ".to_string(), false)),
+            Event::HardBreak => spans.push(inline.span("\n".to_string(), false)),
+            Event::FootnoteReference(label) => spans.push(inline.span(format!("[{label}]"), false)),
+            Event::TaskListMarker(done) => {
+                let marker = if *done { "[x] " } else { "[ ] " };
+                spans.push(inline.span(marker.to_string(), false))
+            }
+            // Raw HTML is not shown.
+            Event::InlineHtml(_) => {}
+            _ => break,
+        }
+        events.next();
+    }
+    spans
+}
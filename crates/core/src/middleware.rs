@@ -0,0 +1,264 @@
+// ============================================================================
+// Server API middleware
+// Hooks that `ServerApi` runs around every request: interceptors see and may
+// change each request and response, an auth provider supplies a bearer token
+// (refreshed once when the server answers 401), a retry policy re-sends
+// requests that failed transiently, and a `CancelToken` abandons a request
+// when the element that issued it goes away.
+// ============================================================================
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+
+use crate::server_api::{HttpError, Method, Request, Response, Timeout};
+
+/// Sees every request before it is sent and every response before it is
+/// checked. Returning an error fails the request.
+pub trait Interceptor: Send + Sync {
+    fn on_request(&self, request: &mut Request) -> anyhow::Result<()> {
+        let _ = request;
+        Ok(())
+    }
+
+    fn on_response(&self, request: &Request, response: &mut Response) -> anyhow::Result<()> {
+        let _ = (request, response);
+        Ok(())
+    }
+}
+
+// ── Auth ─────────────────────────────────────────────────────────────────────
+
+/// Supplies the bearer token sent in the `Authorization` header.
+pub trait AuthProvider: Send + Sync {
+    /// The token for the next request, if any.
+    fn token(&self) -> BoxFuture<'_, anyhow::Result<Option<String>>>;
+
+    /// Called when the server rejected `rejected` with a 401. Returns `true`
+    /// if a new token is available, in which case the request is sent once
+    /// more.
+    fn refresh<'a>(&'a self, rejected: &'a str) -> BoxFuture<'a, anyhow::Result<bool>> {
+        let _ = rejected;
+        Box::pin(async { Ok(false) })
+    }
+}
+
+/// A fixed token, e.g. an API key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticToken(pub String);
+
+impl StaticToken {
+    pub fn new(token: &str) -> Self {
+        Self(token.to_string())
+    }
+}
+
+impl AuthProvider for StaticToken {
+    fn token(&self) -> BoxFuture<'_, anyhow::Result<Option<String>>> {
+        Box::pin(async { Ok(Some(self.0.clone())) })
+    }
+}
+
+type RefreshFn = dyn Fn() -> BoxFuture<'static, anyhow::Result<String>> + Send + Sync;
+
+/// A token obtained from `refresh`, on first use and again whenever the
+/// server rejects it. Concurrent requests rejected with the same token
+/// trigger a single refresh.
+#[derive(Clone)]
+pub struct RefreshableToken {
+    token: std::sync::Arc<tokio::sync::Mutex<Option<String>>>,
+    refresh: std::sync::Arc<RefreshFn>,
+}
+
+impl RefreshableToken {
+    pub fn new<F, Fut>(refresh: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = anyhow::Result<String>> + Send + 'static,
+    {
+        Self {
+            token: Default::default(),
+            refresh: std::sync::Arc::new(move || Box::pin(refresh())),
+        }
+    }
+
+    /// Start with `token` instead of refreshing on first use.
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = std::sync::Arc::new(tokio::sync::Mutex::new(Some(token.to_string())));
+        self
+    }
+}
+
+impl std::fmt::Debug for RefreshableToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RefreshableToken(<refresh>)")
+    }
+}
+
+impl AuthProvider for RefreshableToken {
+    fn token(&self) -> BoxFuture<'_, anyhow::Result<Option<String>>> {
+        Box::pin(async {
+            let mut token = self.token.lock().await;
+            if token.is_none() {
+                *token = Some((self.refresh)().await?);
+            }
+            Ok(token.clone())
+        })
+    }
+
+    fn refresh<'a>(&'a self, rejected: &'a str) -> BoxFuture<'a, anyhow::Result<bool>> {
+        Box::pin(async move {
+            let mut token = self.token.lock().await;
+            // Another request already replaced the rejected token.
+            if token.as_deref() == Some(rejected) {
+                *token = Some((self.refresh)().await?);
+            }
+            Ok(true)
+        })
+    }
+}
+
+// ── Retry ────────────────────────────────────────────────────────────────────
+
+/// When and how often to re-send a failed request.
+///
+/// Connection errors, timeouts and the statuses in `statuses` count as
+/// transient. Only idempotent methods are retried unless `non_idempotent` is
+/// set. The delay before retry `n` (from 0) is `base_delay * 2^n`, capped at
+/// `max_delay`, then reduced by a random fraction of up to `jitter`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// From `0.0` (fixed delays) to `1.0` (anywhere between zero and the
+    /// full delay).
+    pub jitter: f64,
+    pub statuses: Vec<u16>,
+    /// Also retry `POST` and `PATCH`.
+    pub non_idempotent: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.5,
+            statuses: vec![408, 429, 500, 502, 503, 504],
+            non_idempotent: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Never retry.
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// The delay before retry number `attempt`, counting from 0.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let full = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * random_fraction();
+        full.mul_f64(1.0 - jitter)
+    }
+
+    /// Whether a request with `method` that failed with `error` may be sent
+    /// again.
+    pub fn should_retry(&self, method: Method, error: &anyhow::Error) -> bool {
+        if matches!(method, Method::Post | Method::Patch) && !self.non_idempotent {
+            return false;
+        }
+        if let Some(e) = error.downcast_ref::<HttpError>() {
+            return self.statuses.contains(&e.status);
+        }
+        error.is::<Timeout>() || error.chain().any(|c| c.is::<std::io::Error>())
+    }
+}
+
+/// A number in `0.0..1.0`, different on each call.
+fn random_fraction() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// ── Cancellation ─────────────────────────────────────────────────────────────
+
+/// Cancels the requests sent with it (see `RequestBuilder::cancel_with`).
+/// Clones share the same state; once cancelled, a token stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(std::sync::Arc<CancelState>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: std::sync::atomic::AtomicBool,
+    notify: tokio::sync::Notify,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abandon all requests using this token. Pending ones fail with
+    /// `Cancelled`; later ones fail without being sent.
+    pub fn cancel(&self) {
+        self.0
+            .cancelled
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        self.0.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        let mut notified = std::pin::pin!(self.0.notify.notified());
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Tokens are equal if they are clones of each other.
+impl PartialEq for CancelToken {
+    fn eq(&self, other: &Self) -> bool {
+        std::sync::Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancelToken {}
+
+/// Returned when a request is cancelled through its `CancelToken`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cancelled {
+    pub method: Method,
+    pub url: String,
+}
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} was cancelled", self.method, self.url)
+    }
+}
+
+impl std::error::Error for Cancelled {}
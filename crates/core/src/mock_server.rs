@@ -0,0 +1,351 @@
+// ============================================================================
+// Loopback mock server
+// An HTTP/1.1 server on `127.0.0.1` for tests of code that uses `ServerApi`.
+// Stub responses per method and path, run the code under test against
+// `MockServer::url`, then assert on the recorded requests. Stubs can also
+// accept a WebSocket upgrade or serve an event stream. The server runs on
+// its own threads, so it works with any runtime, or none.
+// ============================================================================
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::server_api::{Method, find, find_header, reason_phrase};
+
+/// A canned response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Wait this long before answering, e.g. to trigger timeouts.
+    pub delay: Duration,
+    /// If set, accept a WebSocket upgrade, send these text frames and close.
+    pub frames: Option<Vec<String>>,
+}
+
+impl MockResponse {
+    /// An empty response with `status`.
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            headers: vec![],
+            body: vec![],
+            delay: Duration::ZERO,
+            frames: None,
+        }
+    }
+
+    pub fn text(status: u16, body: &str) -> Self {
+        Self::new(status)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(body)
+    }
+
+    pub fn json<T: serde::Serialize + ?Sized>(status: u16, value: &T) -> Self {
+        let body = serde_json::to_vec(value).expect("mock response body serializes");
+        Self::new(status)
+            .header("Content-Type", "application/json")
+            .body(body)
+    }
+
+    /// A `text/event-stream` response; `events` is the stream as sent,
+    /// e.g. `"event: price\ndata: {}\n\n"`.
+    pub fn event_stream(events: &str) -> Self {
+        Self::new(200)
+            .header("Content-Type", "text/event-stream")
+            .body(events)
+    }
+
+    /// Accept a WebSocket upgrade, send `frames` as text frames, then close
+    /// the connection.
+    pub fn websocket(frames: &[&str]) -> Self {
+        Self {
+            frames: Some(frames.iter().map(|f| f.to_string()).collect()),
+            ..Self::new(101)
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request received by a `MockServer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path and query, as sent.
+    pub path: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The value of header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// The body as text (lossily decoded).
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the JSON body.
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_slice(&self.body)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Routes {
+    stubs: Vec<(Method, String, VecDeque<MockResponse>)>,
+    requests: Vec<RecordedRequest>,
+}
+
+impl Routes {
+    /// The next response for `method path`: stubs for a path with its query
+    /// take precedence over stubs for the bare path.
+    fn respond(&mut self, method: &str, path: &str) -> MockResponse {
+        let bare = path.split('?').next().unwrap_or(path);
+        let method = Method::parse(method);
+        for target in [path, bare] {
+            let stub = self
+                .stubs
+                .iter_mut()
+                .rev()
+                .find(|(m, p, _)| Some(*m) == method && p == target);
+            if let Some((_, _, responses)) = stub {
+                // The last response repeats.
+                return match responses.len() {
+                    1 => responses[0].clone(),
+                    _ => responses
+                        .pop_front()
+                        .unwrap_or_else(|| MockResponse::new(500)),
+                };
+            }
+        }
+        MockResponse::text(404, "no stub for this request")
+    }
+}
+
+/// An HTTP server on a free loopback port, shut down when dropped.
+///
+/// ```ignore
+/// let server = MockServer::start();
+/// server.stub(Method::Get, "/users/1", MockResponse::json(200, &user));
+/// let api = ServerApi::new(&server.url(""));
+/// // ... exercise code using `api` ...
+/// assert_eq!(server.requests()[0].path, "/users/1");
+/// ```
+#[derive(Debug)]
+pub struct MockServer {
+    addr: std::net::SocketAddr,
+    routes: std::sync::Arc<std::sync::Mutex<Routes>>,
+    shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Start a server on `127.0.0.1` with an OS-assigned port.
+    ///
+    /// # Panics
+    ///
+    /// If no loopback port can be bound.
+    pub fn start() -> Self {
+        Self::start_on(([127, 0, 0, 1], 0).into())
+    }
+
+    /// Start a server on `addr`, e.g. the address of a dropped server, to
+    /// simulate it coming back.
+    ///
+    /// # Panics
+    ///
+    /// If `addr` cannot be bound.
+    pub fn start_on(addr: std::net::SocketAddr) -> Self {
+        let listener = std::net::TcpListener::bind(addr).expect("bind a loopback port");
+        let addr = listener.local_addr().expect("bound address");
+        let routes = std::sync::Arc::new(std::sync::Mutex::new(Routes::default()));
+        let shutdown = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let thread = {
+            let routes = routes.clone();
+            let shutdown = shutdown.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    if shutdown.load(std::sync::atomic::Ordering::SeqCst) {
+                        break;
+                    }
+                    let Ok(stream) = stream else { continue };
+                    let routes = routes.clone();
+                    // Each connection gets a thread, so delayed responses
+                    // do not hold up other requests.
+                    std::thread::spawn(move || {
+                        let _ = serve(stream, &routes);
+                    });
+                }
+            })
+        };
+        Self {
+            addr,
+            routes,
+            shutdown,
+            thread: Some(thread),
+        }
+    }
+
+    pub fn addr(&self) -> std::net::SocketAddr {
+        self.addr
+    }
+
+    /// The URL of `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{path}", self.addr)
+    }
+
+    /// Answer `method path` with `response`. Stubbing the same route again
+    /// queues another response: responses are used in order and the last
+    /// one repeats. A `path` with a query only matches that exact query.
+    pub fn stub(&self, method: Method, path: &str, response: MockResponse) -> &Self {
+        let mut routes = self.routes.lock().unwrap();
+        match routes
+            .stubs
+            .iter_mut()
+            .find(|(m, p, _)| *m == method && p == path)
+        {
+            Some((_, _, responses)) => responses.push_back(response),
+            None => routes
+                .stubs
+                .push((method, path.to_string(), VecDeque::from([response]))),
+        }
+        self
+    }
+
+    /// The requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.routes.lock().unwrap().requests.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.shutdown
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag.
+        let _ = std::net::TcpStream::connect(self.addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn serve(
+    mut stream: std::net::TcpStream,
+    routes: &std::sync::Mutex<Routes>,
+) -> std::io::Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let end = loop {
+        if let Some(end) = find(&buf, b"\r\n\r\n") {
+            break end;
+        }
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_string(), v.trim().to_string()))
+        .collect();
+    let len = find_header(&headers, "Content-Length")
+        .and_then(|l| l.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = buf[end + 4..].to_vec();
+    while body.len() < len {
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(len);
+
+    let key = find_header(&headers, "Sec-WebSocket-Key").map(str::to_string);
+    let response = {
+        let mut routes = routes.lock().unwrap();
+        let response = routes.respond(&method, &path);
+        routes.requests.push(RecordedRequest {
+            method,
+            path,
+            headers,
+            body,
+        });
+        response
+    };
+    std::thread::sleep(response.delay);
+    if let Some(frames) = response.frames {
+        return serve_websocket(stream, key.as_deref().unwrap_or_default(), frames);
+    }
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(&response.body)?;
+    stream.flush()
+}
+
+fn serve_websocket(
+    mut stream: std::net::TcpStream,
+    key: &str,
+    frames: Vec<String>,
+) -> std::io::Result<()> {
+    use tokio_tungstenite::tungstenite;
+
+    let accept = tungstenite::handshake::derive_accept_key(key.as_bytes());
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+         Connection: Upgrade\r\nSec-WebSocket-Accept: {accept}\r\n\r\n"
+    )?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut socket =
+        tungstenite::WebSocket::from_raw_socket(stream, tungstenite::protocol::Role::Server, None);
+    for frame in frames {
+        socket
+            .send(tungstenite::Message::text(frame))
+            .map_err(std::io::Error::other)?;
+    }
+    socket.close(None).map_err(std::io::Error::other)?;
+    // Wait for the client to acknowledge the close.
+    while socket.read().is_ok() {}
+    Ok(())
+}
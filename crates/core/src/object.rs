@@ -1,21 +1,102 @@
-use crate::elements::{Element, Text, TextClock};
+use crate::animation::Animations;
+use crate::elements::{Button, Element, RichText, Text, TextClock};
+use crate::focus::FocusState;
 use crate::girl::Girl;
-use crate::layout::{Board, Card, Row};
+use crate::layout::{Board, Card, Column, LayoutNode, Row};
+use crate::pointer::{PointerHandler, PointerState};
+use crate::shared_str::SharedStr;
+use crate::stylesheet::StyleSheet;
+use crate::theme::{Theme, ThemeSwitch};
 use crate::traits::IntoObject;
+use crate::types::Rect;
 
 #[derive(Debug)]
 pub struct World {
     pub root: Object,
+    /// The area the root is laid out in.
+    pub viewport: Rect,
+    /// Hover, press and capture state of the pointer.
+    pub pointer: PointerState,
+    /// Which node has keyboard focus.
+    pub focus: FocusState,
+    /// The design tokens everything is drawn with.
+    pub theme: Theme,
+    /// The last `SetTheme` this world has adopted.
+    pub theme_switch: ThemeSwitch,
+    /// Styles for particular nodes, on top of the theme.
+    pub styles: StyleSheet,
+    /// Animated properties of nodes, on top of their styles and layout.
+    pub animations: Animations,
 }
 
 impl Default for World {
     fn default() -> Self {
+        // Listen for `SetTheme` from the start, so no switch is missed.
+        crate::theme::latest_theme();
         Self {
             root: Object::Board(Board::default()),
+            viewport: Rect::new(0.0, 0.0, 800.0, 600.0),
+            pointer: PointerState::default(),
+            focus: FocusState::default(),
+            theme: Theme::default(),
+            theme_switch: ThemeSwitch::default(),
+            styles: StyleSheet::default(),
+            animations: Animations::default(),
         }
     }
 }
 
+impl World {
+    /// Deliver the messages posted on the event bus, adopt the theme last
+    /// sent with `SetTheme`, advance animations to the current time and form
+    /// submissions to their next stage, start and refetch the resources
+    /// shown, and show each resource's child for its current state. Call once
+    /// per frame; returns `true` if anything changed and the world needs to
+    /// be drawn again.
+    pub fn update(&mut self) -> bool {
+        let delivered = crate::event_bus::event_bus().flush() > 0;
+        let themed = self.update_theme();
+        let animated = self.frame(std::time::Instant::now());
+        let clicked = crate::elements::update_clicks(&self.root);
+        let submitted = crate::form::update_forms(&mut self.root);
+        crate::resource::update_queries(&mut self.root)
+            | submitted
+            | clicked
+            | themed
+            | animated
+            | delivered
+    }
+
+    /// Compute the layout of the whole tree for the current viewport, with
+    /// nodes moved and resized by their animations.
+    pub fn layout(&self) -> LayoutNode {
+        self.animate_layout(self.static_layout())
+    }
+
+    /// The room the node at `path` is laid out in, which its text wraps at.
+    pub(crate) fn available_width(&self, path: &[usize]) -> f32 {
+        crate::layout::available_width(
+            &self.root,
+            path,
+            self.viewport,
+            &self.theme.text_style(),
+            &self.sheet_styles(),
+            &self.theme,
+        )
+    }
+
+    /// The layout without animations.
+    pub(crate) fn static_layout(&self) -> LayoutNode {
+        crate::layout::compute_layout_styled(
+            &self.root,
+            self.viewport,
+            &self.theme.text_style(),
+            &self.sheet_styles(),
+            &self.theme,
+        )
+    }
+}
+
 // ── Object enum ──────────────────────────────────────────────────────────────
 
 #[derive(Debug, Clone)]
@@ -23,10 +104,176 @@ pub enum Object {
     Board(Board),
     Girl(Girl),
     Card(Card),
+    Column(Column),
     Row(Row),
     Element(Element),
 }
 
+// ── Tree navigation ──────────────────────────────────────────────────────────
+
+/// Path from the root to a node: the child index taken at each level.
+///
+/// A `Form`'s submit and reset buttons follow its children, at indices
+/// `children.len()` and `children.len() + 1`.
+pub type NodePath = Vec<usize>;
+
+/// A borrowed node of the object tree.
+#[derive(Debug, Clone, Copy)]
+pub enum Node<'a> {
+    Object(&'a Object),
+    /// One of a `Form`'s buttons, which are not `Object`s themselves.
+    Button(&'a Button),
+}
+
+/// A mutably borrowed node of the object tree.
+#[derive(Debug)]
+pub enum NodeMut<'a> {
+    Object(&'a mut Object),
+    Button(&'a mut Button),
+}
+
+impl Object {
+    /// Child objects in tree order. Every child of a `Switch` is included, not
+    /// only the active one.
+    pub fn children(&self) -> &[Object] {
+        match self {
+            Object::Board(b) => &b.children,
+            Object::Card(c) => &c.children,
+            Object::Column(c) => &c.children,
+            Object::Row(r) => &r.children,
+            Object::Element(Element::Form(f)) => &f.children,
+            Object::Element(Element::Switch(s)) => &s.children,
+            Object::Element(Element::Query(q)) => &q.children,
+            Object::Element(Element::Markdown(m)) => &m.children,
+            _ => &[],
+        }
+    }
+
+    /// Mutable access to the child objects; see `children`.
+    pub fn children_mut(&mut self) -> &mut [Object] {
+        match self {
+            Object::Board(b) => &mut b.children,
+            Object::Card(c) => &mut c.children,
+            Object::Column(c) => &mut c.children,
+            Object::Row(r) => &mut r.children,
+            Object::Element(Element::Form(f)) => &mut f.children,
+            Object::Element(Element::Switch(s)) => &mut s.children,
+            Object::Element(Element::Query(q)) => &mut q.children,
+            Object::Element(Element::Markdown(m)) => &mut m.children,
+            _ => &mut [],
+        }
+    }
+
+    /// Look up the node at `path` (the empty path is `self`).
+    pub fn node(&self, path: &[usize]) -> Option<Node<'_>> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(Node::Object(self));
+        };
+        if let Object::Element(Element::Form(f)) = self
+            && first >= f.children.len()
+        {
+            return match (first - f.children.len(), rest.is_empty()) {
+                (0, true) => Some(Node::Button(&f.submit_button)),
+                (1, true) => Some(Node::Button(&f.reset_button)),
+                _ => None,
+            };
+        }
+        self.children().get(first)?.node(rest)
+    }
+
+    /// Mutable variant of `node`.
+    pub fn node_mut(&mut self, path: &[usize]) -> Option<NodeMut<'_>> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(NodeMut::Object(self));
+        };
+        let form_button = match &*self {
+            Object::Element(Element::Form(f)) if first >= f.children.len() => {
+                Some(first - f.children.len())
+            }
+            _ => None,
+        };
+        if let Some(button) = form_button {
+            let Object::Element(Element::Form(f)) = self else {
+                unreachable!()
+            };
+            return match (button, rest.is_empty()) {
+                (0, true) => Some(NodeMut::Button(&mut f.submit_button)),
+                (1, true) => Some(NodeMut::Button(&mut f.reset_button)),
+                _ => None,
+            };
+        }
+        self.children_mut().get_mut(first)?.node_mut(rest)
+    }
+}
+
+impl<'a> Node<'a> {
+    /// The pointer handler attached to this node, if any.
+    pub fn pointer_handler(&self) -> Option<&'a PointerHandler> {
+        match *self {
+            Node::Object(Object::Board(b)) => b.on_pointer.as_ref(),
+            Node::Object(Object::Card(c)) => c.on_pointer.as_ref(),
+            Node::Object(Object::Column(c)) => c.on_pointer.as_ref(),
+            Node::Object(Object::Row(r)) => r.on_pointer.as_ref(),
+            Node::Object(Object::Element(Element::Button(b))) | Node::Button(b) => {
+                b.on_pointer.as_ref()
+            }
+            _ => None,
+        }
+    }
+
+    /// The button at this node, if it is one.
+    pub fn as_button(&self) -> Option<&'a Button> {
+        match *self {
+            Node::Object(Object::Element(Element::Button(b))) | Node::Button(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// Whether pointer events may target this node: it has a pointer handler,
+    /// is a button or is a form control.
+    pub fn accepts_pointer(&self) -> bool {
+        self.pointer_handler().is_some()
+            || self.as_button().is_some()
+            || matches!(
+                self,
+                Node::Object(Object::Element(
+                    Element::Checkbox(_)
+                        | Element::RadioGroup(_)
+                        | Element::Select(_)
+                        | Element::Slider(_)
+                ))
+            )
+            || matches!(self, Node::Object(Object::Element(Element::RichText(t))) if t.has_links())
+    }
+
+    /// The tab index of this node if it can take keyboard focus.
+    pub fn focus_tab_index(&self) -> Option<i32> {
+        match *self {
+            Node::Object(Object::Element(Element::Button(b))) | Node::Button(b) => {
+                (b.focusable && !b.disabled).then_some(b.tab_index)
+            }
+            Node::Object(Object::Element(Element::TextInput(t))) => {
+                t.focusable.then_some(t.tab_index)
+            }
+            Node::Object(Object::Element(Element::TextArea(t))) => {
+                t.focusable.then_some(t.tab_index)
+            }
+            Node::Object(Object::Element(Element::Checkbox(c))) => {
+                c.focusable.then_some(c.tab_index)
+            }
+            Node::Object(Object::Element(Element::RadioGroup(r))) => {
+                r.focusable.then_some(r.tab_index)
+            }
+            Node::Object(Object::Element(Element::Select(s))) => s.focusable.then_some(s.tab_index),
+            Node::Object(Object::Element(Element::Slider(s))) => s.focusable.then_some(s.tab_index),
+            Node::Object(Object::Element(Element::NumberInput(n))) => {
+                n.focusable.then_some(n.tab_index)
+            }
+            _ => None,
+        }
+    }
+}
+
 // ── From impls ───────────────────────────────────────────────────────────────
 
 impl From<Board> for Object {
@@ -47,6 +294,12 @@ impl From<Card> for Object {
     }
 }
 
+impl From<Column> for Object {
+    fn from(c: Column) -> Self {
+        Object::Column(c)
+    }
+}
+
 impl From<Row> for Object {
     fn from(r: Row) -> Self {
         Object::Row(r)
@@ -66,6 +319,12 @@ impl From<Text> for Object {
     }
 }
 
+impl From<RichText> for Object {
+    fn from(t: RichText) -> Self {
+        Object::Element(t.into())
+    }
+}
+
 impl From<TextClock> for Object {
     fn from(t: TextClock) -> Self {
         // Convert TextClock -> Element and wrap into Object::Element
@@ -75,10 +334,23 @@ impl From<TextClock> for Object {
 
 impl From<u128> for Object {
     fn from(n: u128) -> Self {
-        // Convert number to a textual representation for demonstration.
-        let s = format!("{}", n);
-        let leaked: &'static str = Box::leak(s.into_boxed_str());
-        Text { text: leaked }.into()
+        n.to_string().into()
+    }
+}
+
+impl From<String> for Object {
+    fn from(text: String) -> Self {
+        SharedStr::from(text).into()
+    }
+}
+
+impl From<SharedStr> for Object {
+    fn from(text: SharedStr) -> Self {
+        Text {
+            text,
+            ..Default::default()
+        }
+        .into()
     }
 }
 
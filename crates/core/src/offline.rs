@@ -0,0 +1,555 @@
+// ============================================================================
+// Offline support for `ServerApi`
+// An `Outbox` keeps writes that could not reach the server in a directory,
+// one file per request, and replays them in order once the server can be
+// reached again. Only writes that never left the client are queued: one that
+// timed out may have been applied, and replaying it could apply it twice.
+// A `ResponseCache` keeps `GET` responses per URL and credentials as allowed
+// by their `Cache-Control` and `Vary` headers, revalidates them with
+// `ETag`/`Last-Modified`, can serve stale responses while revalidating in
+// the background, and falls back to a stale response while offline.
+// ============================================================================
+
+use std::time::{Duration, Instant};
+
+use anyhow::Context as _;
+
+use crate::event_bus::event_bus;
+use crate::server_api::{HttpError, Method, Request, Response, ServerApi, Timeout, Unreachable};
+use crate::state::State;
+use crate::traits::Message;
+
+/// Whether `error` means the server could not be reached at all, as opposed
+/// to the server answering with an error.
+pub(crate) fn is_offline(error: &anyhow::Error) -> bool {
+    error.is::<Timeout>() || error.chain().any(|c| c.is::<std::io::Error>())
+}
+
+/// Whether `error` means no connection to the server could be opened, so the
+/// request was certainly not sent.
+pub(crate) fn is_unreachable(error: &anyhow::Error) -> bool {
+    error.is::<Unreachable>()
+}
+
+// ── Outbox ───────────────────────────────────────────────────────────────────
+
+/// Sent on the event bus whenever the number of queued requests changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutboxChanged {
+    pub pending: usize,
+}
+
+impl Message for OutboxChanged {}
+
+/// Sent on the event bus when the server rejects a replayed request, or it
+/// fails after it may have reached the server (e.g. it timed out). The
+/// request is dropped from the outbox rather than risk sending it twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedRequestFailed {
+    pub method: Method,
+    pub url: String,
+    pub error: String,
+}
+
+impl Message for QueuedRequestFailed {}
+
+/// Returned by `ServerApi::send` when no connection to the server could be
+/// opened for a write, which was queued in the outbox instead.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Queued {
+    pub method: Method,
+    pub url: String,
+    /// Requests waiting in the outbox, this one included.
+    pub pending: usize,
+}
+
+impl std::fmt::Display for Queued {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} was queued until the server can be reached ({} pending)",
+            self.method, self.url, self.pending
+        )
+    }
+}
+
+impl std::error::Error for Queued {}
+
+/// A request as stored on disk.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Entry {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    timeout_ms: u64,
+}
+
+/// Writes (`POST`, `PUT`, `PATCH` and `DELETE`) that failed because no
+/// connection to the server could be opened, kept in a directory until they
+/// are replayed. Writes that time out are not queued, as the server may have
+/// applied them. Clones share the same queue.
+///
+/// ```ignore
+/// let outbox = Outbox::open(dirs::data_dir().unwrap().join("my-app/outbox"))?;
+/// let api = ServerApi::new("https://example.com/api").outbox(outbox.clone());
+/// // Bind `outbox.pending` to show "3 changes pending".
+/// ```
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    /// Number of queued requests.
+    pub pending: State<usize>,
+    inner: std::sync::Arc<OutboxInner>,
+}
+
+#[derive(Debug)]
+struct OutboxInner {
+    dir: std::path::PathBuf,
+    next_id: std::sync::Mutex<u64>,
+    /// Held while replaying, so requests are replayed once and in order.
+    replaying: tokio::sync::Mutex<()>,
+}
+
+impl Outbox {
+    /// Open the outbox kept in `dir`, creating the directory if needed.
+    /// Requests queued by earlier runs are kept.
+    pub fn open(dir: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("could not create outbox `{}`", dir.display()))?;
+        let outbox = Self {
+            pending: State::new(0),
+            inner: std::sync::Arc::new(OutboxInner {
+                dir,
+                next_id: std::sync::Mutex::new(0),
+                replaying: tokio::sync::Mutex::new(()),
+            }),
+        };
+        let ids = outbox.ids()?;
+        *outbox.inner.next_id.lock().unwrap() = ids.last().map_or(0, |id| id + 1);
+        outbox.pending.set(ids.len());
+        Ok(outbox)
+    }
+
+    /// Whether requests with `method` are queued when offline.
+    pub fn queues(method: Method) -> bool {
+        matches!(
+            method,
+            Method::Post | Method::Put | Method::Patch | Method::Delete
+        )
+    }
+
+    /// The queued requests, oldest first.
+    pub fn requests(&self) -> anyhow::Result<Vec<Request>> {
+        self.ids()?.into_iter().map(|id| self.read(id)).collect()
+    }
+
+    /// Drop all queued requests.
+    pub fn clear(&self) -> anyhow::Result<()> {
+        for id in self.ids()? {
+            self.remove(id)?;
+        }
+        Ok(())
+    }
+
+    fn path(&self, id: u64) -> std::path::PathBuf {
+        self.inner.dir.join(format!("{id:020}.json"))
+    }
+
+    /// Ids of the queued requests, in order.
+    fn ids(&self) -> anyhow::Result<Vec<u64>> {
+        let mut ids = vec![];
+        let entries = std::fs::read_dir(&self.inner.dir)
+            .with_context(|| format!("could not read outbox `{}`", self.inner.dir.display()))?;
+        for entry in entries {
+            let name = entry?.file_name();
+            if let Some(id) = name
+                .to_str()
+                .and_then(|n| n.strip_suffix(".json"))
+                .and_then(|n| n.parse().ok())
+            {
+                ids.push(id);
+            }
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn read(&self, id: u64) -> anyhow::Result<Request> {
+        let path = self.path(id);
+        let entry: Entry = std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| Ok(serde_json::from_slice(&data)?))
+            .with_context(|| format!("could not read queued request `{}`", path.display()))?;
+        Ok(Request {
+            method: Method::parse(&entry.method)
+                .with_context(|| format!("invalid method in `{}`", path.display()))?,
+            url: entry.url,
+            headers: entry.headers,
+            body: entry.body,
+            timeout: Duration::from_millis(entry.timeout_ms),
+            cancel: None,
+        })
+    }
+
+    /// Queue `request` and return the number of queued requests.
+    fn push(&self, request: &Request) -> anyhow::Result<usize> {
+        let entry = Entry {
+            method: request.method.as_str().to_string(),
+            url: request.url.clone(),
+            headers: request.headers.clone(),
+            body: request.body.clone(),
+            timeout_ms: request.timeout.as_millis() as u64,
+        };
+        let id = {
+            let mut next = self.inner.next_id.lock().unwrap();
+            *next += 1;
+            *next - 1
+        };
+        // Write then rename, so a crash never leaves half a request.
+        let path = self.path(id);
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec(&entry)?)
+            .and_then(|_| std::fs::rename(&tmp, &path))
+            .with_context(|| format!("could not queue request in `{}`", path.display()))?;
+        Ok(self.changed(1, 0))
+    }
+
+    fn remove(&self, id: u64) -> anyhow::Result<()> {
+        let path = self.path(id);
+        std::fs::remove_file(&path)
+            .with_context(|| format!("could not remove queued request `{}`", path.display()))?;
+        self.changed(0, 1);
+        Ok(())
+    }
+
+    /// Update the pending count and announce it.
+    fn changed(&self, added: usize, removed: usize) -> usize {
+        let mut pending = 0;
+        self.pending.update(|n| {
+            *n = (*n + added).saturating_sub(removed);
+            pending = *n;
+        });
+        event_bus().send(OutboxChanged { pending });
+        pending
+    }
+}
+
+impl ServerApi {
+    /// Send the requests queued in the outbox, oldest first, and return how
+    /// many were sent. Stops at the first one that still cannot reach the
+    /// server; requests that fail otherwise are dropped and reported with
+    /// `QueuedRequestFailed`.
+    pub async fn replay_outbox(&self) -> anyhow::Result<usize> {
+        let Some(outbox) = self.outbox_ref() else {
+            return Ok(0);
+        };
+        let _replaying = outbox.inner.replaying.lock().await;
+        let mut sent = 0;
+        for id in outbox.ids()? {
+            let request = outbox.read(id)?;
+            match self.send_with_retries(request.clone()).await {
+                Err(e) if is_unreachable(&e) => break,
+                Err(e) => event_bus().send(QueuedRequestFailed {
+                    method: request.method,
+                    url: request.url,
+                    error: format!("{e:#}"),
+                }),
+                Ok(_) => sent += 1,
+            }
+            outbox.remove(id)?;
+        }
+        Ok(sent)
+    }
+}
+
+/// Send a write through `outbox`: behind any requests already queued, and
+/// into the queue if no connection to the server can be opened.
+pub(crate) async fn send_queued(
+    api: &ServerApi,
+    outbox: &Outbox,
+    request: Request,
+) -> anyhow::Result<Response> {
+    if outbox.pending.get() > 0 {
+        api.replay_outbox().await?;
+    }
+    let result = if outbox.pending.get() > 0 {
+        None
+    } else {
+        Some(api.send_with_retries(request.clone()).await)
+    };
+    match result {
+        Some(Err(e)) if is_unreachable(&e) => {}
+        Some(result) => return result,
+        None => {}
+    }
+    let pending = outbox.push(&request)?;
+    Err(Queued {
+        method: request.method,
+        url: request.url,
+        pending,
+    }
+    .into())
+}
+
+/// The server was reached: replay queued requests in the background.
+pub(crate) fn replay_in_background(api: &ServerApi) {
+    if api.outbox_ref().is_some_and(|o| o.pending.get() > 0) {
+        let api = api.clone();
+        crate::runtime::spawn(move || async move {
+            let _ = api.replay_outbox().await;
+        });
+    }
+}
+
+// ── Response cache ───────────────────────────────────────────────────────────
+
+/// Sent on the event bus when a background revalidation (see
+/// `ResponseCache::stale_while_revalidate`) got a new response for `url`.
+/// Requesting it again returns the new response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheRefreshed {
+    pub url: String,
+}
+
+impl Message for CacheRefreshed {}
+
+/// A cache of `GET` responses, keyed by URL and the credentials they were
+/// requested with, so one user's responses are never served to another.
+/// Clones share the same entries.
+///
+/// A response is only used for requests that send the same values for the
+/// headers named in its `Vary` header, and not kept at all with `Vary: *`.
+/// Responses are kept unless `Cache-Control` says `no-store`, and used
+/// without asking the server for `max-age` seconds (never with `no-cache`).
+/// After that they are revalidated with `If-None-Match`/`If-Modified-Since`.
+/// Within the `stale-while-revalidate` window, the stale response is
+/// returned right away and revalidated in the background. If the server
+/// cannot be reached, a stale response is returned rather than the error.
+#[derive(Debug, Clone, Default)]
+pub struct ResponseCache {
+    entries: std::sync::Arc<std::sync::Mutex<std::collections::HashMap<CacheKey, Cached>>>,
+    stale_while_revalidate: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    url: String,
+    /// Hash of the `Authorization` the response was requested with.
+    credentials: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+struct Cached {
+    response: Response,
+    stored: Instant,
+    policy: CachePolicy,
+    /// The headers named by `Vary`, with the values they were requested with.
+    vary: Vec<(String, Option<String>)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct CachePolicy {
+    max_age: Duration,
+    stale_while_revalidate: Duration,
+    no_cache: bool,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve stale responses while revalidating them for up to `window`
+    /// after they expire, even if the server did not allow it.
+    pub fn stale_while_revalidate(mut self, window: Duration) -> Self {
+        self.stale_while_revalidate = window;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget the responses for `url`, whoever requested them.
+    pub fn remove(&self, url: &str) {
+        self.entries.lock().unwrap().retain(|key, _| key.url != url);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// The response kept for `request`, if its `Vary` headers match.
+    fn get(&self, key: &CacheKey, request: &Request) -> Option<Cached> {
+        let entries = self.entries.lock().unwrap();
+        let cached = entries.get(key)?;
+        cached
+            .vary
+            .iter()
+            .all(|(name, value)| request.header(name) == value.as_deref())
+            .then(|| cached.clone())
+    }
+
+    /// Keep `response` to `request` if its headers allow it.
+    fn store(&self, key: &CacheKey, request: &Request, response: &Response) {
+        let policy = CachePolicy::of(response);
+        let vary = response.header("Vary").unwrap_or_default();
+        let mut entries = self.entries.lock().unwrap();
+        let Some(policy) = policy.filter(|_| vary.trim() != "*") else {
+            entries.remove(key);
+            return;
+        };
+        let vary = vary
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| (name.to_string(), request.header(name).map(str::to_string)))
+            .collect();
+        entries.insert(
+            key.clone(),
+            Cached {
+                response: response.clone(),
+                stored: Instant::now(),
+                policy,
+                vary,
+            },
+        );
+    }
+
+    /// The server confirmed the response for `key` is still current.
+    fn renew(&self, key: &CacheKey) {
+        if let Some(cached) = self.entries.lock().unwrap().get_mut(key) {
+            cached.stored = Instant::now();
+        }
+    }
+}
+
+impl CacheKey {
+    async fn of(api: &ServerApi, request: &Request) -> anyhow::Result<Self> {
+        let credentials = api.credentials(request).await?.map(|value| {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        });
+        Ok(Self {
+            url: request.url.clone(),
+            credentials,
+        })
+    }
+}
+
+impl CachePolicy {
+    /// The policy for `response`, or `None` if it must not be stored.
+    fn of(response: &Response) -> Option<Self> {
+        let mut policy = Self::default();
+        for directive in response
+            .header("Cache-Control")
+            .unwrap_or_default()
+            .split(',')
+        {
+            let (name, value) = directive.split_once('=').unwrap_or((directive, ""));
+            let seconds = || value.trim().trim_matches('"').parse().ok();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "no-store" => return None,
+                "no-cache" => policy.no_cache = true,
+                "max-age" => policy.max_age = Duration::from_secs(seconds()?),
+                "stale-while-revalidate" => {
+                    policy.stale_while_revalidate = Duration::from_secs(seconds()?)
+                }
+                _ => {}
+            }
+        }
+        let validated =
+            response.header("ETag").is_some() || response.header("Last-Modified").is_some();
+        let usable =
+            policy.max_age > Duration::ZERO || policy.stale_while_revalidate > Duration::ZERO;
+        (validated || usable).then_some(policy)
+    }
+}
+
+/// Send a `GET` through `cache`.
+pub(crate) async fn send_cached(
+    api: &ServerApi,
+    cache: &ResponseCache,
+    request: Request,
+) -> anyhow::Result<Response> {
+    let key = CacheKey::of(api, &request).await?;
+    let cached = cache.get(&key, &request);
+    if let Some(cached) = &cached
+        && !cached.policy.no_cache
+    {
+        let age = cached.stored.elapsed();
+        if age < cached.policy.max_age {
+            return Ok(cached.response.clone());
+        }
+        let window = cached
+            .policy
+            .stale_while_revalidate
+            .max(cache.stale_while_revalidate);
+        if age < cached.policy.max_age + window {
+            let (api, cache, key, stale) =
+                (api.clone(), cache.clone(), key.clone(), cached.clone());
+            let request = Request {
+                cancel: None,
+                ..request
+            };
+            crate::runtime::spawn(move || async move {
+                let url = request.url.clone();
+                if let Ok(response) = revalidate(&api, &cache, &key, request, Some(&stale)).await
+                    && response.body != stale.response.body
+                {
+                    event_bus().send(CacheRefreshed { url });
+                }
+            });
+            return Ok(cached.response.clone());
+        }
+    }
+    match (
+        revalidate(api, cache, &key, request, cached.as_ref()).await,
+        cached,
+    ) {
+        (Err(e), Some(cached)) if is_offline(&e) => Ok(cached.response),
+        (result, _) => result,
+    }
+}
+
+/// Fetch `request`, conditionally if there is a `cached` response, and
+/// update the cache.
+async fn revalidate(
+    api: &ServerApi,
+    cache: &ResponseCache,
+    key: &CacheKey,
+    mut request: Request,
+    cached: Option<&Cached>,
+) -> anyhow::Result<Response> {
+    if let Some(cached) = cached {
+        if let Some(etag) = cached.response.header("ETag") {
+            request.set_header("If-None-Match", etag);
+        }
+        if let Some(modified) = cached.response.header("Last-Modified") {
+            request.set_header("If-Modified-Since", modified);
+        }
+    }
+    match (api.send_with_retries(request.clone()).await, cached) {
+        (Ok(response), _) => {
+            cache.store(key, &request, &response);
+            replay_in_background(api);
+            Ok(response)
+        }
+        (Err(e), Some(cached))
+            if e.downcast_ref::<HttpError>()
+                .is_some_and(|e| e.status == 304) =>
+        {
+            cache.renew(key);
+            replay_in_background(api);
+            Ok(cached.response.clone())
+        }
+        (Err(e), _) => Err(e),
+    }
+}
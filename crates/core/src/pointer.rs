@@ -0,0 +1,341 @@
+// ============================================================================
+// Pointer events
+// Input is hit-tested against the computed layout and routed like DOM events:
+// a capture phase from the root down to the target, the target itself, then a
+// bubble phase back up. The target is the deepest node under the pointer that
+// accepts pointer events (see `Node::accepts_pointer`).
+// ============================================================================
+
+use crate::elements::Element;
+use crate::object::{Node, NodeMut, NodePath, Object, World};
+use crate::types::Point;
+
+/// Raw pointer input fed into `World::dispatch_pointer`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerInput {
+    Down(Point),
+    Up(Point),
+    Move(Point),
+    Wheel {
+        position: Point,
+        delta_x: f32,
+        delta_y: f32,
+    },
+}
+
+impl PointerInput {
+    pub fn position(&self) -> Point {
+        match *self {
+            PointerInput::Down(p) | PointerInput::Up(p) | PointerInput::Move(p) => p,
+            PointerInput::Wheel { position, .. } => position,
+        }
+    }
+}
+
+/// What happened, as seen by a handler.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEventKind {
+    Down,
+    Up,
+    Move,
+    /// The pointer entered the node. Not propagated.
+    Enter,
+    /// The pointer left the node. Not propagated.
+    Leave,
+    Wheel {
+        delta_x: f32,
+        delta_y: f32,
+    },
+}
+
+/// Which leg of the route an event is currently on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventPhase {
+    /// Travelling from the root towards the target.
+    Capture,
+    /// At the target itself.
+    Target,
+    /// Travelling from the target back to the root.
+    Bubble,
+}
+
+/// A pointer event as delivered to a `PointerHandler`.
+#[derive(Debug, Clone)]
+pub struct PointerEvent {
+    pub kind: PointerEventKind,
+    pub position: Point,
+    pub phase: EventPhase,
+    /// The node the event was routed to.
+    pub target: NodePath,
+    /// The node whose handler is running.
+    pub current: NodePath,
+    propagation_stopped: bool,
+    capture: Option<bool>,
+}
+
+impl PointerEvent {
+    fn new(kind: PointerEventKind, position: Point, target: NodePath) -> Self {
+        Self {
+            kind,
+            position,
+            phase: EventPhase::Target,
+            current: target.clone(),
+            target,
+            propagation_stopped: false,
+            capture: None,
+        }
+    }
+
+    /// Do not deliver this event to any further node.
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+
+    pub fn is_propagation_stopped(&self) -> bool {
+        self.propagation_stopped
+    }
+
+    /// Route all following pointer events to the current node until the
+    /// pointer is released, even if it moves outside of it.
+    pub fn capture_pointer(&mut self) {
+        self.capture = Some(true);
+    }
+
+    /// Undo `capture_pointer`.
+    pub fn release_pointer_capture(&mut self) {
+        self.capture = Some(false);
+    }
+}
+
+/// Shared pointer event callback stored on elements.
+#[derive(Clone)]
+pub struct PointerHandler(std::sync::Arc<dyn Fn(&mut PointerEvent) + Send + Sync>);
+
+impl PointerHandler {
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&mut PointerEvent) + Send + Sync + 'static,
+    {
+        Self(std::sync::Arc::new(f))
+    }
+
+    pub fn call(&self, event: &mut PointerEvent) {
+        (self.0)(event)
+    }
+}
+
+impl std::fmt::Debug for PointerHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("<pointer handler>")
+    }
+}
+
+/// Pointer state kept by the `World` between inputs.
+#[derive(Debug, Clone, Default)]
+pub struct PointerState {
+    /// Nodes under the pointer, outermost first.
+    hovered: Vec<NodePath>,
+    /// Target of the last `Down`, until the matching `Up`.
+    pressed: Option<NodePath>,
+    /// Node that captured the pointer, if any.
+    pub(crate) captured: Option<NodePath>,
+}
+
+impl PointerState {
+    pub fn hovered(&self) -> &[NodePath] {
+        &self.hovered
+    }
+
+    pub fn pressed(&self) -> Option<&NodePath> {
+        self.pressed.as_ref()
+    }
+
+    pub fn captured(&self) -> Option<&NodePath> {
+        self.captured.as_ref()
+    }
+}
+
+impl World {
+    /// Hit-test `input` against the current layout and deliver the resulting
+    /// events. A `Down` followed by an `Up` on the same button clicks it.
+    ///
+    /// Returns `true` if any handler ran or a button was clicked.
+    pub fn dispatch_pointer(&mut self, input: PointerInput) -> bool {
+        let position = input.position();
+        let chain = self.layout().hit_test(position);
+        let mut handled = self.update_hover(&chain, position);
+
+        // Pressing moves focus to the deepest focusable node, or clears it.
+        if let PointerInput::Down(_) = input {
+            let focusable = chain
+                .iter()
+                .rev()
+                .find(|p| {
+                    self.root
+                        .node(p)
+                        .is_some_and(|n| n.focus_tab_index().is_some())
+                })
+                .cloned();
+            match focusable {
+                Some(path) => {
+                    self.focus(&path);
+                }
+                None => self.blur(),
+            }
+        }
+
+        let target = match &self.pointer.captured {
+            Some(path) => Some(path.clone()),
+            None => chain
+                .iter()
+                .rev()
+                .find(|p| self.root.node(p).is_some_and(|n| n.accepts_pointer()))
+                .cloned(),
+        };
+        let Some(target) = target else {
+            match input {
+                PointerInput::Up(_) => self.pointer.pressed = None,
+                PointerInput::Wheel { delta_y, .. } => {
+                    handled |= self.scroll_text_area(&chain, delta_y);
+                }
+                _ => {}
+            }
+            return handled;
+        };
+
+        let kind = match input {
+            PointerInput::Down(_) => PointerEventKind::Down,
+            PointerInput::Up(_) => PointerEventKind::Up,
+            PointerInput::Move(_) => PointerEventKind::Move,
+            PointerInput::Wheel {
+                delta_x, delta_y, ..
+            } => PointerEventKind::Wheel { delta_x, delta_y },
+        };
+        let mut event = PointerEvent::new(kind, position, target.clone());
+        handled |= self.route(&mut event);
+
+        match event.capture {
+            Some(true) => self.pointer.captured = Some(target.clone()),
+            Some(false) => self.pointer.captured = None,
+            None => {}
+        }
+
+        match input {
+            PointerInput::Down(_) => {
+                if !event.propagation_stopped {
+                    handled |= self.control_pointer(&target, kind, position);
+                }
+                self.pointer.pressed = Some(target);
+            }
+            PointerInput::Move(_) if !event.propagation_stopped => {
+                handled |= self.control_pointer(&target, kind, position);
+            }
+            PointerInput::Up(_) => {
+                let pressed = self.pointer.pressed.take();
+                self.pointer.captured = None;
+                if pressed.as_ref() == Some(&target) && chain.contains(&target) {
+                    handled |= self.activate(&target);
+                    handled |= self.control_pointer(&target, kind, position);
+                }
+            }
+            PointerInput::Wheel { delta_y, .. } if !event.propagation_stopped => {
+                handled |= self.scroll_text_area(&chain, delta_y);
+            }
+            _ => {}
+        }
+        handled
+    }
+
+    /// Default action of a wheel event: scroll the deepest `TextArea` under
+    /// the pointer by whole lines. Positive `delta_y` scrolls down.
+    fn scroll_text_area(&mut self, chain: &[NodePath], delta_y: f32) -> bool {
+        let lines = (delta_y / crate::layout::LINE_HEIGHT).round() as isize;
+        for path in chain.iter().rev() {
+            if let Some(NodeMut::Object(Object::Element(Element::TextArea(t)))) =
+                self.root.node_mut(path)
+            {
+                return t.scroll_by(lines);
+            }
+        }
+        false
+    }
+
+    /// Default action of a completed click on `path`: click the button, and
+    /// submit or reset the form if it is one of a form's buttons. Disabled
+    /// buttons do nothing.
+    pub(crate) fn activate(&mut self, path: &[usize]) -> bool {
+        let Some(button) = self.root.node(path).and_then(|n| n.as_button()) else {
+            return false;
+        };
+        if button.disabled {
+            return false;
+        }
+        button.click();
+
+        if let Some((&index, parent)) = path.split_last()
+            && let Some(Node::Object(Object::Element(Element::Form(form)))) = self.root.node(parent)
+        {
+            let fields = form.children.len();
+            if index == fields {
+                self.submit_form(parent);
+            } else if index == fields + 1 {
+                self.reset_form(parent);
+            }
+        }
+        true
+    }
+
+    /// Deliver `event` along the route from the root to `event.target` and back.
+    fn route(&self, event: &mut PointerEvent) -> bool {
+        let target = event.target.clone();
+        let ancestors: Vec<&[usize]> = (0..target.len()).map(|i| &target[..i]).collect();
+        let mut handled = false;
+
+        event.phase = EventPhase::Capture;
+        for path in &ancestors {
+            handled |= self.call_handler(path, event);
+            if event.propagation_stopped {
+                return handled;
+            }
+        }
+
+        event.phase = EventPhase::Target;
+        handled |= self.call_handler(&target, event);
+        if event.propagation_stopped {
+            return handled;
+        }
+
+        event.phase = EventPhase::Bubble;
+        for path in ancestors.iter().rev() {
+            handled |= self.call_handler(path, event);
+            if event.propagation_stopped {
+                break;
+            }
+        }
+        handled
+    }
+
+    fn call_handler(&self, path: &[usize], event: &mut PointerEvent) -> bool {
+        let Some(handler) = self.root.node(path).and_then(|n| n.pointer_handler()) else {
+            return false;
+        };
+        event.current = path.to_vec();
+        handler.call(event);
+        true
+    }
+
+    /// Send `Leave`/`Enter` to nodes that stopped/started being under the pointer.
+    fn update_hover(&mut self, chain: &[NodePath], position: Point) -> bool {
+        let old = std::mem::replace(&mut self.pointer.hovered, chain.to_vec());
+        let mut handled = false;
+        for path in old.iter().rev().filter(|p| !chain.contains(p)) {
+            let mut event = PointerEvent::new(PointerEventKind::Leave, position, path.clone());
+            handled |= self.call_handler(path, &mut event);
+        }
+        for path in chain.iter().filter(|p| !old.contains(p)) {
+            let mut event = PointerEvent::new(PointerEventKind::Enter, position, path.clone());
+            handled |= self.call_handler(path, &mut event);
+        }
+        handled
+    }
+}
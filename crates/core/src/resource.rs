@@ -0,0 +1,429 @@
+// ============================================================================
+// Resources
+// A `Resource<T>` fetches a `T` (usually through a `ServerApi`) and shows a
+// different child for each stage of the fetch: loading, failed or ready. In
+// the tree it is a `Query` element. `World::update` starts the fetch when the
+// query is shown and swaps in the child for the current state once the fetch,
+// which runs in the background, completes. Hiding the query in a `Switch` or
+// dropping it cancels the fetch; sending `Refetch` starts it again.
+// ============================================================================
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures::future::LocalBoxFuture;
+
+use crate::elements::{Element, Text};
+use crate::event_bus::event_bus;
+use crate::middleware::CancelToken;
+use crate::object::Object;
+use crate::server_api::ServerApi;
+use crate::state::State;
+use crate::traits::{IntoObject, Message, MessageContext, MessageHandler};
+
+/// The stage a `Resource`'s fetch is in.
+#[derive(Debug, Default)]
+pub enum ResourceState<T> {
+    /// Not fetched yet: the resource has not been shown.
+    #[default]
+    Idle,
+    Loading,
+    /// The fetch failed with this error.
+    Failed(String),
+    Ready(std::sync::Arc<T>),
+}
+
+impl<T> ResourceState<T> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, ResourceState::Loading)
+    }
+
+    /// The error of a failed fetch.
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            ResourceState::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// The fetched value, once ready.
+    pub fn data(&self) -> Option<&T> {
+        match self {
+            ResourceState::Ready(data) => Some(data),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Clone for ResourceState<T> {
+    fn clone(&self) -> Self {
+        match self {
+            ResourceState::Idle => ResourceState::Idle,
+            ResourceState::Loading => ResourceState::Loading,
+            ResourceState::Failed(error) => ResourceState::Failed(error.clone()),
+            ResourceState::Ready(data) => ResourceState::Ready(data.clone()),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for ResourceState<T> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ResourceState::Idle, ResourceState::Idle)
+            | (ResourceState::Loading, ResourceState::Loading) => true,
+            (ResourceState::Failed(a), ResourceState::Failed(b)) => a == b,
+            (ResourceState::Ready(a), ResourceState::Ready(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// Fetch every shown resource named `resource` again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refetch {
+    pub resource: &'static str,
+}
+
+impl Message for Refetch {}
+
+type Fetch<T> =
+    std::sync::Arc<dyn Fn(CancelToken) -> LocalBoxFuture<'static, anyhow::Result<T>> + Send + Sync>;
+type RenderError = std::sync::Arc<dyn Fn(&str) -> Object + Send + Sync>;
+type RenderData<T> = std::sync::Arc<dyn Fn(&T) -> Object + Send + Sync>;
+
+/// Data fetched for the UI, shown as `loading`, `error` or `ready` depending
+/// on how the fetch went.
+///
+/// Keep a clone of `state` before turning the resource into an `Object` to
+/// follow the fetch from elsewhere.
+pub struct Resource<T> {
+    /// Names the resource for `Refetch`.
+    pub name: &'static str,
+    pub state: State<ResourceState<T>>,
+    fetch: Fetch<T>,
+    loading: Object,
+    error: RenderError,
+    ready: RenderData<T>,
+}
+
+impl<T> std::fmt::Debug for Resource<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Resource")
+            .field("name", &self.name)
+            .field("loading", &self.loading)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: Send + Sync + 'static> Resource<T> {
+    /// A resource fetched by `fetch`. The fetch should give up when the token
+    /// it is passed is cancelled, e.g. with `RequestBuilder::cancel_with`.
+    pub fn new<F, Fut>(name: &'static str, fetch: F) -> Self
+    where
+        F: Fn(CancelToken) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = anyhow::Result<T>> + 'static,
+    {
+        Self {
+            name,
+            state: State::default(),
+            fetch: std::sync::Arc::new(move |cancel| Box::pin(fetch(cancel))),
+            loading: Text {
+                text: "Loading…".into(),
+                ..Default::default()
+            }
+            .into(),
+            error: std::sync::Arc::new(|_| {
+                Text {
+                    text: "Failed to load".into(),
+                    ..Default::default()
+                }
+                .into()
+            }),
+            ready: std::sync::Arc::new(|_| Text::default().into()),
+        }
+    }
+
+    /// A resource decoded from the JSON answer to `GET path`.
+    pub fn get(name: &'static str, api: &ServerApi, path: &str) -> Self
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let api = api.clone();
+        let path = path.to_string();
+        Self::new(name, move |cancel| {
+            let request = api.get(&path).cancel_with(&cancel);
+            async move { request.send().await?.json() }
+        })
+    }
+
+    /// Shown while fetching.
+    pub fn loading(mut self, child: impl Into<Object>) -> Self {
+        self.loading = child.into();
+        self
+    }
+
+    /// Shown when the fetch failed, given its error.
+    pub fn error(mut self, render: impl Fn(&str) -> Object + Send + Sync + 'static) -> Self {
+        self.error = std::sync::Arc::new(render);
+        self
+    }
+
+    /// Shown once the data is ready.
+    pub fn ready(mut self, render: impl Fn(&T) -> Object + Send + Sync + 'static) -> Self {
+        self.ready = std::sync::Arc::new(render);
+        self
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Resource<T>> for Element {
+    fn from(r: Resource<T>) -> Self {
+        let source = Source {
+            state: r.state,
+            fetch: r.fetch,
+            loading: r.loading,
+            error: r.error,
+            ready: r.ready,
+            version: std::sync::Arc::new(AtomicU64::new(0)),
+            task: std::sync::Mutex::new(Task::default()),
+        };
+        Element::Query(Query {
+            name: r.name,
+            children: vec![source.render()],
+            source: std::sync::Arc::new(source),
+            shown: 0,
+        })
+    }
+}
+
+impl<T: Send + Sync + 'static> IntoObject for Resource<T> {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+// ── Query ────────────────────────────────────────────────────────────────────
+
+/// A `Resource` in the tree, whatever type it fetches.
+#[derive(Clone)]
+pub struct Query {
+    pub name: &'static str,
+    /// The child for the current state; always exactly one.
+    pub children: Vec<Object>,
+    source: std::sync::Arc<dyn AnySource>,
+    /// The version of the state `children` shows.
+    shown: u64,
+}
+
+impl std::fmt::Debug for Query {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Query")
+            .field("name", &self.name)
+            .field("children", &self.children)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Query {
+    /// Whether the fetch is running.
+    pub fn is_loading(&self) -> bool {
+        self.source.is_loading()
+    }
+
+    /// Start the fetch if the query was just shown or a `Refetch` asked for
+    /// it, and show the child for the current state. Returns `true` if the
+    /// child changed.
+    pub(crate) fn update(&mut self) -> bool {
+        let requested = refetches(self.name);
+        {
+            let mut task = self.source.task().lock().unwrap();
+            if !task.shown || task.refetches != requested {
+                task.shown = true;
+                task.refetches = requested;
+                drop(task);
+                self.source.start();
+            }
+        }
+        let version = self.source.version();
+        if version == self.shown {
+            return false;
+        }
+        self.shown = version;
+        self.children = vec![self.source.render()];
+        true
+    }
+
+    /// Cancel the fetch; the next `update` starts it again.
+    pub(crate) fn hide(&self) {
+        self.source.stop();
+    }
+}
+
+impl From<Query> for Element {
+    fn from(q: Query) -> Self {
+        Element::Query(q)
+    }
+}
+
+impl IntoObject for Query {
+    fn into_object(self) -> Object {
+        Element::from(self).into()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Task {
+    /// Whether the query is in the shown part of the tree.
+    shown: bool,
+    /// `refetches` for this query's name when it last started fetching.
+    refetches: u64,
+    cancel: CancelToken,
+}
+
+/// The type-erased side of a `Resource<T>`.
+trait AnySource: Send + Sync {
+    fn task(&self) -> &std::sync::Mutex<Task>;
+    /// Bumped whenever the state changes.
+    fn version(&self) -> u64;
+    fn is_loading(&self) -> bool;
+    fn render(&self) -> Object;
+    fn start(&self);
+    fn stop(&self);
+}
+
+struct Source<T> {
+    state: State<ResourceState<T>>,
+    fetch: Fetch<T>,
+    loading: Object,
+    error: RenderError,
+    ready: RenderData<T>,
+    version: std::sync::Arc<AtomicU64>,
+    task: std::sync::Mutex<Task>,
+}
+
+impl<T: Send + Sync + 'static> AnySource for Source<T> {
+    fn task(&self) -> &std::sync::Mutex<Task> {
+        &self.task
+    }
+
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    fn is_loading(&self) -> bool {
+        self.state.borrow().is_loading()
+    }
+
+    fn render(&self) -> Object {
+        match &*self.state.borrow() {
+            ResourceState::Idle | ResourceState::Loading => self.loading.clone(),
+            ResourceState::Failed(error) => (self.error)(error),
+            ResourceState::Ready(data) => (self.ready)(data),
+        }
+    }
+
+    fn start(&self) {
+        let cancel = CancelToken::new();
+        std::mem::replace(&mut self.task.lock().unwrap().cancel, cancel.clone()).cancel();
+        self.state.set(ResourceState::Loading);
+        self.version.fetch_add(1, Ordering::SeqCst);
+
+        let (fetch, state, version) =
+            (self.fetch.clone(), self.state.clone(), self.version.clone());
+        crate::runtime::spawn(move || async move {
+            let stop = std::pin::pin!(cancel.cancelled());
+            let result = match futures::future::select(fetch(cancel.clone()), stop).await {
+                futures::future::Either::Left((result, _)) => result,
+                futures::future::Either::Right(_) => return,
+            };
+            // Checked under the state's lock, so a `stop` that has already
+            // reset the state is not undone.
+            state.update(|s| {
+                if !cancel.is_cancelled() {
+                    *s = match result {
+                        Ok(data) => ResourceState::Ready(std::sync::Arc::new(data)),
+                        Err(e) => ResourceState::Failed(format!("{e:#}")),
+                    };
+                    version.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+        });
+    }
+
+    fn stop(&self) {
+        let mut task = self.task.lock().unwrap();
+        task.shown = false;
+        task.cancel.cancel();
+        self.state.update(|s| {
+            if s.is_loading() {
+                *s = ResourceState::Idle;
+                self.version.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+    }
+}
+
+impl<T> Drop for Source<T> {
+    fn drop(&mut self) {
+        self.task.lock().unwrap().cancel.cancel();
+    }
+}
+
+// ── Refetch ──────────────────────────────────────────────────────────────────
+
+/// How many times each resource name was refetched.
+static REFETCHES: std::sync::OnceLock<std::sync::Mutex<std::collections::HashMap<&str, u64>>> =
+    std::sync::OnceLock::new();
+
+fn refetch_counts() -> &'static std::sync::Mutex<std::collections::HashMap<&'static str, u64>> {
+    REFETCHES.get_or_init(|| {
+        // Registered here rather than when a resource is created, which may
+        // happen inside a handler, while the event bus is locked.
+        event_bus().register_handler::<RefetchHandler, Refetch>(std::sync::Arc::new(
+            std::sync::Mutex::new(RefetchHandler),
+        ));
+        Default::default()
+    })
+}
+
+fn refetches(name: &str) -> u64 {
+    refetch_counts()
+        .lock()
+        .unwrap()
+        .get(name)
+        .copied()
+        .unwrap_or(0)
+}
+
+struct RefetchHandler;
+
+impl MessageHandler<Refetch> for RefetchHandler {
+    async fn handle(&mut self, msg: &Refetch, _: &mut MessageContext) {
+        *refetch_counts()
+            .lock()
+            .unwrap()
+            .entry(msg.resource)
+            .or_default() += 1;
+    }
+}
+
+/// Start the fetches of newly shown resources and refetch those a `Refetch`
+/// asked for, then show each resource's child for its current state. Returns
+/// `true` if anything changed.
+pub(crate) fn update_queries(root: &mut Object) -> bool {
+    let mut changed = false;
+    if let Object::Element(Element::Query(q)) = root {
+        changed |= q.update();
+    }
+    match root {
+        Object::Element(Element::Switch(s)) => {
+            if let Some(active) = s.children.get_mut(s.active) {
+                changed |= update_queries(active);
+            }
+        }
+        _ => {
+            for child in root.children_mut() {
+                changed |= update_queries(child);
+            }
+        }
+    }
+    changed
+}
@@ -0,0 +1,72 @@
+// ============================================================================
+// UI runtime
+// A current-thread tokio runtime, one per UI thread, for callers that wait on
+// futures produced by element callbacks (e.g. `Button::click_future`). Those
+// futures own what they use of the element and need not be `Send`. Timers and
+// network I/O (`ServerApi`) are enabled.
+//
+// Work that outlives its caller (clicks, streams, fetches, outbox replays)
+// runs on a single background thread with a runtime of its own.
+// ============================================================================
+
+/// A boxed, non-`Send` future as produced by element callbacks.
+pub type LocalFuture = std::pin::Pin<Box<dyn std::future::Future<Output = ()>>>;
+
+thread_local! {
+    static RUNTIME: tokio::runtime::Runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .enable_io()
+        .build()
+        .expect("failed to build the snow_ui runtime");
+}
+
+/// Run `fut` to completion on this thread's UI runtime.
+///
+/// Must not be called from inside another tokio runtime; async callers should
+/// await the future directly instead.
+pub fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    RUNTIME.with(|rt| rt.block_on(fut))
+}
+
+type Job = Box<dyn FnOnce() -> futures::future::LocalBoxFuture<'static, ()> + Send>;
+
+/// Run the future made by `task` in the background, for work that outlives
+/// the caller, such as a live stream.
+///
+/// All background work shares one thread and its runtime, started on first
+/// use; `task` is called there, so its future need not be `Send`.
+pub(crate) fn spawn<F, Fut>(task: F)
+where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + 'static,
+{
+    let job: Job = Box::new(move || Box::pin(task()));
+    background()
+        .unbounded_send(job)
+        .expect("the background thread has stopped");
+}
+
+fn background() -> &'static futures::channel::mpsc::UnboundedSender<Job> {
+    static JOBS: std::sync::OnceLock<futures::channel::mpsc::UnboundedSender<Job>> =
+        std::sync::OnceLock::new();
+    JOBS.get_or_init(|| {
+        let (tx, mut jobs) = futures::channel::mpsc::unbounded::<Job>();
+        std::thread::Builder::new()
+            .name("snow-ui-background".to_string())
+            .spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .enable_io()
+                    .build()
+                    .expect("failed to build the background runtime");
+                let local = tokio::task::LocalSet::new();
+                local.block_on(&rt, async move {
+                    while let Some(job) = futures::StreamExt::next(&mut jobs).await {
+                        tokio::task::spawn_local(job());
+                    }
+                });
+            })
+            .expect("failed to spawn the background thread");
+        tx
+    })
+}
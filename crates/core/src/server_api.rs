@@ -1,28 +1,963 @@
-/// Minimal Server API helper used by `examples/login.rs`.
-#[derive(Debug, Clone)]
+// ============================================================================
+// Server API
+// A small HTTP/1.1 client for talking to an app's backend. Each request opens
+// its own connection (plain TCP for `http://`, TLS with the bundled webpki
+// roots for `https://`), sends `Connection: close` and reads the response to
+// the end. Responses outside the 2xx range fail with `HttpError`; requests
+// that take longer than their timeout fail with `Timeout`. Auth, interceptors,
+// retries and cancellation live in `middleware`, WebSocket and SSE streams
+// in `streams`, the outbox and response cache in `offline`.
+// ============================================================================
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::middleware::{AuthProvider, CancelToken, Cancelled, Interceptor, RetryPolicy};
+use crate::offline::{Outbox, ResponseCache};
+
+/// Used when neither the `ServerApi` nor the request sets a timeout.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP request method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Head,
+}
+
+impl Method {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+            Method::Head => "HEAD",
+        }
+    }
+
+    /// Parse an upper-case method name.
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "GET" => Method::Get,
+            "POST" => Method::Post,
+            "PUT" => Method::Put,
+            "PATCH" => Method::Patch,
+            "DELETE" => Method::Delete,
+            "HEAD" => Method::Head,
+            _ => return None,
+        })
+    }
+}
+
+impl std::fmt::Display for Method {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Look up header `name` (case-insensitively) in `headers`.
+pub(crate) fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+// ── Request / Response ───────────────────────────────────────────────────────
+
+/// A request about to be sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    /// Absolute `http://` or `https://` URL.
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    pub timeout: Duration,
+    /// Abandons the request when cancelled.
+    pub cancel: Option<CancelToken>,
+}
+
+impl Request {
+    /// The value of header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    /// Set header `name`, replacing any previous value.
+    pub fn set_header(&mut self, name: &str, value: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+    }
+}
+
+/// A response received from the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub reason: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// The value of header `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The body as UTF-8 text.
+    pub fn text(&self) -> anyhow::Result<String> {
+        String::from_utf8(self.body.clone()).context("response body is not valid UTF-8")
+    }
+
+    /// Deserialize the JSON body into `T`.
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> anyhow::Result<T> {
+        serde_json::from_slice(&self.body).with_context(|| {
+            format!(
+                "response body is not a valid `{}`",
+                std::any::type_name::<T>()
+            )
+        })
+    }
+}
+
+/// Returned when the server answers with a status outside the 2xx range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpError {
+    pub method: Method,
+    pub url: String,
+    pub status: u16,
+    pub reason: String,
+    /// The response body, which often explains the error.
+    pub body: String,
+}
+
+impl std::fmt::Display for HttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} failed: {} {}",
+            self.method, self.url, self.status, self.reason
+        )?;
+        let body = self.body.trim();
+        if !body.is_empty() {
+            // Keep error messages readable when the server sends a page.
+            match body.char_indices().nth(200) {
+                Some((end, _)) => write!(f, ": {}…", &body[..end])?,
+                None => write!(f, ": {body}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// Returned when a request does not complete within its timeout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timeout {
+    pub method: Method,
+    pub url: String,
+    pub after: Duration,
+}
+
+impl std::fmt::Display for Timeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} timed out after {:?}",
+            self.method, self.url, self.after
+        )
+    }
+}
+
+impl std::error::Error for Timeout {}
+
+/// Attached to errors where no connection to the server could be opened
+/// (refused, unreachable, unknown host, or timed out while connecting), so
+/// the request certainly never reached it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unreachable {
+    pub host: String,
+    pub port: u16,
+}
+
+impl std::fmt::Display for Unreachable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not connect to {}:{}", self.host, self.port)
+    }
+}
+
+impl std::error::Error for Unreachable {}
+
+// ── ServerApi ────────────────────────────────────────────────────────────────
+
+/// Client for an HTTP API rooted at `endpoint`.
+///
+/// ```ignore
+/// let api = ServerApi::new("https://example.com/api").header("Accept-Language", "en");
+/// let user: User = api.get("/users/1").send().await?.json()?;
+/// api.post("/login").json(&request).send().await?;
+/// ```
+#[derive(Clone)]
 pub struct ServerApi {
     endpoint: String,
+    headers: Vec<(String, String)>,
+    timeout: Duration,
+    interceptors: Vec<std::sync::Arc<dyn Interceptor>>,
+    auth: Option<std::sync::Arc<dyn AuthProvider>>,
+    retry: RetryPolicy,
+    cache: Option<ResponseCache>,
+    outbox: Option<Outbox>,
+}
+
+impl std::fmt::Debug for ServerApi {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerApi")
+            .field("endpoint", &self.endpoint)
+            .field("headers", &self.headers)
+            .field("timeout", &self.timeout)
+            .field("interceptors", &self.interceptors.len())
+            .field("auth", &self.auth.as_ref().map(|_| "<auth provider>"))
+            .field("retry", &self.retry)
+            .field("cache", &self.cache.as_ref().map(|c| c.len()))
+            .field("outbox", &self.outbox.as_ref().map(|o| o.pending.get()))
+            .finish()
+    }
 }
 
 impl ServerApi {
     /// Create a new `ServerApi` pointing at the given endpoint.
     pub fn new(endpoint: &str) -> Self {
         Self {
-            endpoint: endpoint.to_string(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            headers: vec![],
+            timeout: DEFAULT_TIMEOUT,
+            interceptors: vec![],
+            auth: None,
+            retry: RetryPolicy::none(),
+            cache: None,
+            outbox: None,
         }
     }
 
-    /// Post JSON to the configured endpoint and return a textual response.
-    ///
-    /// This is a minimal synchronous stub wrapped in `async` so examples can
-    /// `await` it without adding an HTTP client dependency. It simply returns
-    /// a small acknowledgement that includes the posted payload.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Send header `name` with every request.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Default timeout for the whole exchange of each request (each attempt,
+    /// when retrying).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Run `interceptor` on every request and response, after those added
+    /// before it.
+    pub fn interceptor(mut self, interceptor: impl Interceptor + 'static) -> Self {
+        self.interceptors.push(std::sync::Arc::new(interceptor));
+        self
+    }
+
+    /// Authenticate requests with a bearer token from `auth`.
+    pub fn auth(mut self, auth: impl AuthProvider + 'static) -> Self {
+        self.auth = Some(std::sync::Arc::new(auth));
+        self
+    }
+
+    /// Retry transient failures (the default is not to retry).
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Cache `GET` responses in `cache`.
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Queue writes that cannot reach the server in `outbox`; they fail
+    /// with `Queued` and are replayed later, in order.
+    pub fn outbox(mut self, outbox: Outbox) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
+    pub(crate) fn outbox_ref(&self) -> Option<&Outbox> {
+        self.outbox.as_ref()
+    }
+
+    /// The absolute URL for `path`: appended to the endpoint, unless it is
+    /// an absolute URL itself.
+    pub fn url(&self, path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else if path.is_empty() || path.starts_with(['/', '?']) {
+            format!("{}{path}", self.endpoint)
+        } else {
+            format!("{}/{path}", self.endpoint)
+        }
+    }
+
+    /// Start building a request to `path`.
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        RequestBuilder {
+            api: self.clone(),
+            request: Request {
+                method,
+                url: self.url(path),
+                headers: self.headers.clone(),
+                body: vec![],
+                timeout: self.timeout,
+                cancel: None,
+            },
+            error: None,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> RequestBuilder {
+        self.request(Method::Get, path)
+    }
+
+    pub fn post(&self, path: &str) -> RequestBuilder {
+        self.request(Method::Post, path)
+    }
+
+    pub fn put(&self, path: &str) -> RequestBuilder {
+        self.request(Method::Put, path)
+    }
+
+    pub fn delete(&self, path: &str) -> RequestBuilder {
+        self.request(Method::Delete, path)
+    }
+
+    /// `GET path` and deserialize the JSON response.
+    pub async fn get_json<T: serde::de::DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.get(path).send().await?.json()
+    }
+
+    /// Post a JSON document to the endpoint itself and return the response
+    /// body as text.
     pub async fn post_json(&self, json: &str) -> anyhow::Result<String> {
-        // Minimal stub: echo the endpoint and payload. Replace with real HTTP
-        // client if/when network behavior is required.
-        Ok(format!(
-            "{{\"endpoint\":\"{}\",\"echo\":{}}}",
-            self.endpoint, json
-        ))
+        self.post("")
+            .header("Content-Type", "application/json")
+            .body(json)
+            .send()
+            .await?
+            .text()
+    }
+
+    /// Send `request` through the middleware. Fails with `Timeout` if an
+    /// attempt takes longer than `request.timeout`, with `HttpError` unless
+    /// the final status is 2xx, and with `Cancelled` once `request.cancel`
+    /// is cancelled.
+    pub async fn send(&self, request: Request) -> anyhow::Result<Response> {
+        let Some(cancel) = request.cancel.clone() else {
+            return self.dispatch(request).await;
+        };
+        let cancelled = Cancelled {
+            method: request.method,
+            url: request.url.clone(),
+        };
+        if cancel.is_cancelled() {
+            return Err(cancelled.into());
+        }
+        let send = std::pin::pin!(self.dispatch(request));
+        let stop = std::pin::pin!(cancel.cancelled());
+        match futures::future::select(send, stop).await {
+            futures::future::Either::Left((result, _)) => result,
+            futures::future::Either::Right(_) => Err(cancelled.into()),
+        }
+    }
+
+    /// Send `request` through the response cache or the outbox, if set.
+    /// Reaching the server starts replaying the outbox.
+    async fn dispatch(&self, request: Request) -> anyhow::Result<Response> {
+        match (&self.cache, &self.outbox) {
+            (Some(cache), _) if request.method == Method::Get => {
+                crate::offline::send_cached(self, cache, request).await
+            }
+            (_, Some(outbox)) if Outbox::queues(request.method) => {
+                crate::offline::send_queued(self, outbox, request).await
+            }
+            _ => {
+                let response = self.send_with_retries(request).await?;
+                crate::offline::replay_in_background(self);
+                Ok(response)
+            }
+        }
+    }
+
+    pub(crate) async fn send_with_retries(&self, request: Request) -> anyhow::Result<Response> {
+        let mut attempt = 0;
+        loop {
+            match self.send_once(request.clone()).await {
+                Err(e)
+                    if attempt < self.retry.max_retries
+                        && self.retry.should_retry(request.method, &e) =>
+                {
+                    tokio::time::sleep(self.retry.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Run the request interceptors and add the auth token, which is
+    /// returned.
+    pub(crate) async fn prepare(&self, request: &mut Request) -> anyhow::Result<Option<String>> {
+        for interceptor in &self.interceptors {
+            interceptor.on_request(request)?;
+        }
+        let mut token = None;
+        if let Some(auth) = &self.auth {
+            token = auth.token().await?;
+            if let Some(token) = &token {
+                request.set_header("Authorization", &format!("Bearer {token}"));
+            }
+        }
+        Ok(token)
+    }
+
+    /// The `Authorization` `request` is sent with: its own, or the one the
+    /// auth provider adds.
+    pub(crate) async fn credentials(&self, request: &Request) -> anyhow::Result<Option<String>> {
+        if let Some(value) = request.header("Authorization") {
+            return Ok(Some(value.to_string()));
+        }
+        Ok(match &self.auth {
+            Some(auth) => auth.token().await?.map(|token| format!("Bearer {token}")),
+            None => None,
+        })
+    }
+
+    /// After the server rejected token `rejected`, ask the auth provider for
+    /// a new one and put it in `request`. Returns `false` if there is none.
+    pub(crate) async fn refresh_token(
+        &self,
+        request: &mut Request,
+        rejected: &str,
+    ) -> anyhow::Result<bool> {
+        if let Some(auth) = &self.auth
+            && auth.refresh(rejected).await?
+            && let Some(token) = auth.token().await?
+        {
+            request.set_header("Authorization", &format!("Bearer {token}"));
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// One attempt: interceptors, auth (with one refresh after a 401), the
+    /// exchange itself and the status check.
+    async fn send_once(&self, mut request: Request) -> anyhow::Result<Response> {
+        let token = self.prepare(&mut request).await?;
+        let mut response = transfer(&request).await?;
+        if response.status == 401
+            && let Some(rejected) = token
+            && self.refresh_token(&mut request, &rejected).await?
+        {
+            response = transfer(&request).await?;
+        }
+
+        for interceptor in &self.interceptors {
+            interceptor.on_response(&request, &mut response)?;
+        }
+        if !response.is_success() {
+            return Err(HttpError {
+                method: request.method,
+                url: request.url,
+                status: response.status,
+                reason: response.reason.clone(),
+                body: String::from_utf8_lossy(&response.body).into_owned(),
+            }
+            .into());
+        }
+        Ok(response)
+    }
+}
+
+/// Exchange `request` for a response of any status within its timeout. A
+/// timeout while connecting is also `Unreachable`.
+async fn transfer(request: &Request) -> anyhow::Result<Response> {
+    let deadline = tokio::time::Instant::now() + request.timeout;
+    let timed_out = || Timeout {
+        method: request.method,
+        url: request.url.clone(),
+        after: request.timeout,
+    };
+    let failed = || format!("{} {} failed", request.method, request.url);
+    let url = Url::parse(&request.url).with_context(failed)?;
+    let stream = match tokio::time::timeout_at(deadline, connect(&url)).await {
+        Ok(stream) => stream.with_context(failed)?,
+        Err(_) => {
+            return Err(anyhow::Error::new(timed_out()).context(Unreachable {
+                host: url.host,
+                port: url.port,
+            }));
+        }
+    };
+    tokio::time::timeout_at(deadline, round_trip(stream, &url, request))
+        .await
+        .map_err(|_| timed_out())?
+        .with_context(failed)
+}
+
+/// A request being built by `ServerApi::request` and friends.
+#[derive(Debug)]
+pub struct RequestBuilder {
+    api: ServerApi,
+    request: Request,
+    /// First error hit while building, reported by `send`.
+    error: Option<anyhow::Error>,
+}
+
+impl RequestBuilder {
+    /// Set header `name`, replacing any previous value.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.request.set_header(name, value);
+        self
+    }
+
+    /// Timeout for this request only.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.request.timeout = timeout;
+        self
+    }
+
+    /// Abandon the request when `token` is cancelled, e.g. `Form::cancel`
+    /// of the form that sends it.
+    pub fn cancel_with(mut self, token: &CancelToken) -> Self {
+        self.request.cancel = Some(token.clone());
+        self
+    }
+
+    pub fn body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.request.body = body.into();
+        self
+    }
+
+    /// Send `value` serialized as JSON.
+    pub fn json<T: serde::Serialize + ?Sized>(mut self, value: &T) -> Self {
+        match serde_json::to_vec(value) {
+            Ok(body) => {
+                self.request.body = body;
+                self.request.set_header("Content-Type", "application/json");
+            }
+            Err(e) => {
+                self.error.get_or_insert(e.into());
+            }
+        }
+        self
+    }
+
+    /// The request as built so far.
+    pub fn build(self) -> anyhow::Result<Request> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.request),
+        }
+    }
+
+    pub async fn send(self) -> anyhow::Result<Response> {
+        let api = self.api.clone();
+        api.send(self.build()?).await
+    }
+}
+
+// ── HTTP/1.1 ─────────────────────────────────────────────────────────────────
+
+/// The parts of an `http`, `https`, `ws` or `wss` URL needed to connect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Url {
+    pub(crate) tls: bool,
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    /// Path and query, starting with `/`.
+    pub(crate) target: String,
+}
+
+impl Url {
+    pub(crate) fn parse(url: &str) -> anyhow::Result<Self> {
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("ws://") {
+            (false, rest)
+        } else if let Some(rest) = url.strip_prefix("wss://") {
+            (true, rest)
+        } else {
+            anyhow::bail!("unsupported URL `{url}`: expected http:// or https://");
+        };
+        let rest = rest.split('#').next().unwrap_or_default();
+        let (authority, target) = match rest.find(['/', '?']) {
+            Some(i) if rest[i..].starts_with('?') => (&rest[..i], format!("/{}", &rest[i..])),
+            Some(i) => (&rest[..i], rest[i..].to_string()),
+            None => (rest, "/".to_string()),
+        };
+        let default_port = if tls { 443 } else { 80 };
+        // IPv6 hosts are written in brackets: `[::1]:8080`.
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(rest) => rest.split_once(']').unwrap_or((rest, "")),
+            None => authority
+                .rsplit_once(':')
+                .map_or((authority, ""), |(h, p)| (h, p)),
+        };
+        let port = match port.trim_start_matches(':') {
+            "" => default_port,
+            port => port
+                .parse()
+                .with_context(|| format!("invalid port in URL `{url}`"))?,
+        };
+        anyhow::ensure!(!host.is_empty(), "missing host in URL `{url}`");
+        Ok(Self {
+            tls,
+            host: host.to_string(),
+            port,
+            target,
+        })
+    }
+
+    /// Value of the `Host` header.
+    pub(crate) fn host_header(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        if self.port == if self.tls { 443 } else { 80 } {
+            host
+        } else {
+            format!("{host}:{}", self.port)
+        }
+    }
+}
+
+/// A plain or TLS connection.
+pub(crate) trait Connection: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<S: AsyncRead + AsyncWrite + Unpin + Send> Connection for S {}
+
+/// Open a connection to `url`'s host, with TLS for `https` and `wss`.
+pub(crate) async fn connect(url: &Url) -> anyhow::Result<Box<dyn Connection>> {
+    let tcp = tokio::net::TcpStream::connect((url.host.as_str(), url.port))
+        .await
+        .with_context(|| Unreachable {
+            host: url.host.clone(),
+            port: url.port,
+        })?;
+    if !url.tls {
+        return Ok(Box::new(tcp));
+    }
+    let name = rustls::pki_types::ServerName::try_from(url.host.clone())
+        .with_context(|| format!("invalid TLS server name `{}`", url.host))?;
+    let tls = tls_connector()
+        .connect(name, tcp)
+        .await
+        .context("TLS handshake failed")?;
+    Ok(Box::new(tls))
+}
+
+fn tls_connector() -> tokio_rustls::TlsConnector {
+    static CONFIG: std::sync::OnceLock<std::sync::Arc<rustls::ClientConfig>> =
+        std::sync::OnceLock::new();
+    let config = CONFIG.get_or_init(|| {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let provider = std::sync::Arc::new(rustls::crypto::ring::default_provider());
+        let config = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .expect("ring supports the default TLS versions")
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        std::sync::Arc::new(config)
+    });
+    tokio_rustls::TlsConnector::from(config.clone())
+}
+
+async fn round_trip<S>(mut stream: S, url: &Url, request: &Request) -> anyhow::Result<Response>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    write_request(&mut stream, url, request).await?;
+    let mut reader = ResponseReader::new(request.method);
+    let mut chunk = [0u8; 8192];
+    loop {
+        let n = match stream.read(&mut chunk).await {
+            Ok(n) => n,
+            // Servers often close TLS connections without a close_notify.
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => 0,
+            Err(e) => return Err(e.into()),
+        };
+        if n == 0 {
+            return reader
+                .finish()
+                .context("connection closed before the response was complete");
+        }
+        if let Some(response) = reader.push(&chunk[..n])? {
+            return Ok(response);
+        }
+    }
+}
+
+/// Send the head and body of `request`.
+pub(crate) async fn write_request<S>(
+    stream: &mut S,
+    url: &Url,
+    request: &Request,
+) -> anyhow::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n",
+        request.method,
+        url.target,
+        url.host_header()
+    );
+    if request.header("User-Agent").is_none() {
+        head.push_str(concat!(
+            "User-Agent: snow-ui/",
+            env!("CARGO_PKG_VERSION"),
+            "\r\n"
+        ));
+    }
+    if !request.body.is_empty() || matches!(request.method, Method::Post | Method::Put) {
+        head.push_str(&format!("Content-Length: {}\r\n", request.body.len()));
+    }
+    for (name, value) in &request.headers {
+        anyhow::ensure!(
+            !name.contains([':', '\r', '\n']) && !value.contains(['\r', '\n']),
+            "invalid header `{name}`"
+        );
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&request.body).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+/// The head of a response: status, reason and headers, with the length of
+/// the head including its blank line. `None` if more bytes are needed.
+/// Interim `1xx` responses are skipped.
+pub(crate) fn parse_head(buf: &[u8]) -> anyhow::Result<Option<(Response, usize)>> {
+    let Some(end) = find(buf, b"\r\n\r\n") else {
+        return Ok(None);
+    };
+    let head = std::str::from_utf8(&buf[..end]).context("invalid response header")?;
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().unwrap_or_default();
+    let mut parts = status_line.splitn(3, ' ');
+    let (Some(version), Some(status)) = (parts.next(), parts.next()) else {
+        anyhow::bail!("invalid status line `{status_line}`");
+    };
+    anyhow::ensure!(
+        version.starts_with("HTTP/1."),
+        "invalid status line `{status_line}`"
+    );
+    let status: u16 = status
+        .parse()
+        .with_context(|| format!("invalid status line `{status_line}`"))?;
+    let reason = parts.next().unwrap_or_default().to_string();
+    let headers = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(n, v)| (n.trim().to_string(), v.trim().to_string()))
+        .collect::<Vec<_>>();
+
+    // Interim responses such as `100 Continue` precede the real one.
+    if (100..200).contains(&status) {
+        return Ok(parse_head(&buf[end + 4..])?.map(|(head, len)| (head, end + 4 + len)));
+    }
+    let head = Response {
+        status,
+        reason,
+        headers,
+        body: vec![],
+    };
+    Ok(Some((head, end + 4)))
+}
+
+/// Whether `headers` announce a chunked body.
+pub(crate) fn is_chunked(headers: &[(String, String)]) -> bool {
+    find_header(headers, "Transfer-Encoding")
+        .is_some_and(|te| te.to_ascii_lowercase().contains("chunked"))
+}
+
+/// Reads a response as it arrives. The head is parsed once; after that only
+/// the new data is looked at to tell whether the body is complete.
+struct ResponseReader {
+    method: Method,
+    /// Everything received, until the head is complete.
+    head: Vec<u8>,
+    /// The parsed head and how its body ends, once known.
+    body: Option<(Response, BodyEnd)>,
+}
+
+enum BodyEnd {
+    /// Complete with the head.
+    Empty,
+    /// After this many bytes.
+    Length(usize),
+    /// With the terminating chunk. Holds the data of an incomplete chunk.
+    Chunked(Vec<u8>),
+    /// When the server closes the connection.
+    Close,
+}
+
+impl ResponseReader {
+    fn new(method: Method) -> Self {
+        Self {
+            method,
+            head: Vec::new(),
+            body: None,
+        }
+    }
+
+    /// Add received `data`; returns the response once it is complete.
+    fn push(&mut self, data: &[u8]) -> anyhow::Result<Option<Response>> {
+        let data = match &self.body {
+            Some(_) => data.to_vec(),
+            None => {
+                // Only look for the end of the head where new data could
+                // have completed it.
+                let from = self.head.len().saturating_sub(3);
+                self.head.extend_from_slice(data);
+                if find(&self.head[from..], b"\r\n\r\n").is_none() {
+                    return Ok(None);
+                }
+                let Some((head, len)) = parse_head(&self.head)? else {
+                    return Ok(None);
+                };
+                let end = if self.method == Method::Head || head.status == 204 || head.status == 304
+                {
+                    BodyEnd::Empty
+                } else if is_chunked(&head.headers) {
+                    BodyEnd::Chunked(vec![])
+                } else if let Some(len) = head.header("Content-Length") {
+                    BodyEnd::Length(len.parse().context("invalid Content-Length")?)
+                } else {
+                    BodyEnd::Close
+                };
+                let rest = self.head.split_off(len);
+                self.body = Some((head, end));
+                rest
+            }
+        };
+
+        let Some((response, end)) = &mut self.body else {
+            unreachable!("the head was parsed above");
+        };
+        let done = match end {
+            BodyEnd::Empty => true,
+            BodyEnd::Length(len) => {
+                response.body.extend_from_slice(&data);
+                if response.body.len() >= *len {
+                    response.body.truncate(*len);
+                    true
+                } else {
+                    false
+                }
+            }
+            BodyEnd::Chunked(pending) => {
+                pending.extend_from_slice(&data);
+                let (chunks, done) = take_chunks(pending)?;
+                response.body.extend_from_slice(&chunks);
+                done
+            }
+            BodyEnd::Close => {
+                response.body.extend_from_slice(&data);
+                false
+            }
+        };
+        Ok(done.then(|| self.body.take().unwrap().0))
+    }
+
+    /// The response when the connection closes, if it is complete.
+    fn finish(self) -> Option<Response> {
+        match self.body? {
+            (response, BodyEnd::Close) => Some(response),
+            _ => None,
+        }
+    }
+}
+
+/// Remove the complete chunks at the start of `pending` and return their
+/// data, and whether the terminating chunk was among them.
+pub(crate) fn take_chunks(pending: &mut Vec<u8>) -> anyhow::Result<(Vec<u8>, bool)> {
+    let mut body = Vec::new();
+    let mut data = pending.as_slice();
+    let done = loop {
+        let Some(line_end) = find(data, b"\r\n") else {
+            break false;
+        };
+        let size = std::str::from_utf8(&data[..line_end])
+            .ok()
+            .and_then(|l| usize::from_str_radix(l.split(';').next()?.trim(), 16).ok())
+            .context("invalid chunk size")?;
+        if size == 0 {
+            data = &data[line_end + 2..];
+            break true;
+        }
+        if data.len() < line_end + 2 + size + 2 {
+            break false;
+        }
+        body.extend_from_slice(&data[line_end + 2..line_end + 2 + size]);
+        data = &data[line_end + 2 + size + 2..];
+    };
+    let consumed = pending.len() - data.len();
+    pending.drain(..consumed);
+    Ok((body, done))
+}
+
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// The standard reason phrase for `status`.
+pub fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "",
     }
 }
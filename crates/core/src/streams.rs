@@ -0,0 +1,624 @@
+// ============================================================================
+// Live streams
+// WebSocket and server-sent event (SSE) connections opened from a
+// `ServerApi`. Each connection runs in the background, decodes the frames it
+// receives into the `Message` types registered for them and posts those on
+// the global event bus, so `MessageHandler<T>` impls react on the UI thread
+// at the next `World::update`. Dropped
+// connections are re-opened with backoff; `LiveStream::status` tracks the
+// connection for the UI.
+// ============================================================================
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use futures::StreamExt;
+use tokio::io::AsyncReadExt;
+use tokio_tungstenite::tungstenite;
+
+use crate::event_bus::event_bus;
+use crate::middleware::{CancelToken, RetryPolicy};
+use crate::server_api::{
+    HttpError, Method, Request, ServerApi, Timeout, Url, connect, is_chunked, parse_head,
+    reason_phrase, take_chunks, write_request,
+};
+use crate::traits::Message;
+
+/// The state of a `LiveStream`'s connection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConnectionStatus {
+    /// Opening the connection for the first time.
+    #[default]
+    Connecting,
+    Connected,
+    /// The connection dropped or could not be opened; waiting to try again.
+    /// `attempt` counts from 1 and restarts after each successful connect.
+    Reconnecting {
+        attempt: u32,
+    },
+    /// Gave up: the error was not transient, or there were too many
+    /// attempts.
+    Failed(String),
+    /// Closed by `LiveStream::close`, by its cancel token, or by the server
+    /// (an SSE endpoint answering 204).
+    Closed,
+}
+
+impl std::fmt::Display for ConnectionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConnectionStatus::Connecting => f.write_str("Connecting"),
+            ConnectionStatus::Connected => f.write_str("Connected"),
+            ConnectionStatus::Reconnecting { attempt } => write!(f, "Reconnecting ({attempt})"),
+            ConnectionStatus::Failed(error) => write!(f, "Failed: {error}"),
+            ConnectionStatus::Closed => f.write_str("Closed"),
+        }
+    }
+}
+
+/// Posted on the event bus whenever a `LiveStream`'s status changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionChanged {
+    pub url: String,
+    pub status: ConnectionStatus,
+}
+
+impl Message for ConnectionChanged {}
+
+/// Posted on the event bus when a frame could not be decoded into the message
+/// type registered for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FrameRejected {
+    pub url: String,
+    /// The SSE event name or WebSocket frame `type`.
+    pub name: String,
+    pub error: String,
+}
+
+impl Message for FrameRejected {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    WebSocket,
+    EventSource,
+}
+
+type Decoder = Box<dyn Fn(&str) -> anyhow::Result<()> + Send + Sync>;
+
+/// A stream being set up by `ServerApi::websocket` or
+/// `ServerApi::event_source`.
+///
+/// Frames carry JSON. An SSE frame is named by its `event` field, a
+/// WebSocket frame by the `type` field of the JSON object it holds; frames
+/// without a name are named `"message"`. Frames with a name no message type
+/// was registered for are ignored.
+pub struct StreamBuilder {
+    api: ServerApi,
+    kind: Kind,
+    url: String,
+    decoders: Vec<(String, Decoder)>,
+    reconnect: RetryPolicy,
+    cancel: CancelToken,
+}
+
+impl std::fmt::Debug for StreamBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StreamBuilder")
+            .field("kind", &self.kind)
+            .field("url", &self.url)
+            .field(
+                "messages",
+                &self.decoders.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            )
+            .field("reconnect", &self.reconnect)
+            .finish()
+    }
+}
+
+impl StreamBuilder {
+    /// Decode frames named `name` as `T` and post them on the event bus.
+    pub fn message<T>(mut self, name: &str) -> Self
+    where
+        T: Message + serde::de::DeserializeOwned,
+    {
+        let decode = |data: &str| {
+            let message: T = serde_json::from_str(data)?;
+            event_bus().post(message);
+            Ok(())
+        };
+        self.decoders.push((name.to_string(), Box::new(decode)));
+        self
+    }
+
+    /// When and how often to re-open the connection. Connection errors,
+    /// timeouts and the policy's statuses are retried; a connection that
+    /// drops after it was open is always re-opened. The default retries
+    /// forever, backing off from 500ms to 30s.
+    pub fn reconnect(mut self, policy: RetryPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Close the stream when `token` is cancelled.
+    pub fn cancel_with(mut self, token: &CancelToken) -> Self {
+        self.cancel = token.clone();
+        self
+    }
+
+    /// Open the connection in the background.
+    pub fn connect(self) -> LiveStream {
+        let status = crate::state::State::new(ConnectionStatus::Connecting);
+        let link = Link {
+            api: self.api,
+            kind: self.kind,
+            url: self.url.clone(),
+            decoders: self.decoders,
+            reconnect: self.reconnect,
+            cancel: self.cancel.clone(),
+            status: status.clone(),
+        };
+        crate::runtime::spawn(move || link.run());
+        LiveStream {
+            url: self.url,
+            status,
+            cancel: self.cancel,
+        }
+    }
+}
+
+/// An open WebSocket or SSE connection, closed when dropped.
+///
+/// ```ignore
+/// let prices = api
+///     .event_source("/prices")
+///     .message::<PriceChanged>("price")
+///     .connect();
+/// // Bind `prices.status` in the UI; handle `PriceChanged` messages.
+/// ```
+#[derive(Debug)]
+pub struct LiveStream {
+    url: String,
+    /// The connection's current state.
+    pub status: crate::state::State<ConnectionStatus>,
+    cancel: CancelToken,
+}
+
+impl LiveStream {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Close the connection and stop reconnecting.
+    pub fn close(&self) {
+        self.cancel.cancel();
+        self.status.set(ConnectionStatus::Closed);
+    }
+}
+
+impl Drop for LiveStream {
+    fn drop(&mut self) {
+        self.cancel.cancel();
+    }
+}
+
+impl ServerApi {
+    /// Start setting up a WebSocket connection to `path`. `http` endpoints
+    /// connect with `ws://`, `https` endpoints with `wss://`; `path` may also
+    /// be an absolute `ws://` or `wss://` URL.
+    pub fn websocket(&self, path: &str) -> StreamBuilder {
+        let url = if path.starts_with("ws://") || path.starts_with("wss://") {
+            path.to_string()
+        } else {
+            let url = self.url(path);
+            match url.strip_prefix("http") {
+                Some(rest) => format!("ws{rest}"),
+                None => url,
+            }
+        };
+        self.stream(Kind::WebSocket, url)
+    }
+
+    /// Start setting up a server-sent event stream from `path`.
+    pub fn event_source(&self, path: &str) -> StreamBuilder {
+        self.stream(Kind::EventSource, self.url(path))
+    }
+
+    fn stream(&self, kind: Kind, url: String) -> StreamBuilder {
+        StreamBuilder {
+            api: self.clone(),
+            kind,
+            url,
+            decoders: vec![],
+            reconnect: RetryPolicy {
+                max_retries: u32::MAX,
+                base_delay: Duration::from_millis(500),
+                max_delay: Duration::from_secs(30),
+                ..RetryPolicy::default()
+            },
+            cancel: CancelToken::new(),
+        }
+    }
+}
+
+// ── Connection loop ──────────────────────────────────────────────────────────
+
+/// How a connection that was opened came to an end.
+enum Ended {
+    /// It dropped; open it again.
+    Dropped,
+    /// The server asked not to reconnect.
+    Stopped,
+}
+
+/// Everything the background task needs.
+struct Link {
+    api: ServerApi,
+    kind: Kind,
+    url: String,
+    decoders: Vec<(String, Decoder)>,
+    reconnect: RetryPolicy,
+    cancel: CancelToken,
+    status: crate::state::State<ConnectionStatus>,
+}
+
+/// SSE state carried across reconnects.
+#[derive(Debug, Default)]
+struct Resume {
+    last_event_id: Option<String>,
+    /// Reconnect delay requested by the server with `retry:`.
+    retry: Option<Duration>,
+}
+
+impl Link {
+    async fn run(self) {
+        let run = std::pin::pin!(self.connect_loop());
+        let stop = std::pin::pin!(self.cancel.cancelled());
+        let status = match futures::future::select(run, stop).await {
+            futures::future::Either::Left((status, _)) => status,
+            futures::future::Either::Right(_) => ConnectionStatus::Closed,
+        };
+        self.set_status(status);
+    }
+
+    /// Connect, and reconnect, until the stream stops or fails. Returns the
+    /// final status.
+    async fn connect_loop(&self) -> ConnectionStatus {
+        let mut resume = Resume::default();
+        let mut attempt = 0;
+        loop {
+            let mut connected = false;
+            let result = match self.kind {
+                Kind::WebSocket => self.websocket(&mut connected).await,
+                Kind::EventSource => self.event_source(&mut connected, &mut resume).await,
+            };
+            match result {
+                Ok(Ended::Stopped) => return ConnectionStatus::Closed,
+                _ if connected => attempt = 0,
+                Ok(Ended::Dropped) => {}
+                Err(e) => {
+                    if attempt >= self.reconnect.max_retries
+                        || !self.reconnect.should_retry(Method::Get, &e)
+                    {
+                        return ConnectionStatus::Failed(format!("{e:#}"));
+                    }
+                }
+            }
+            self.set_status(ConnectionStatus::Reconnecting {
+                attempt: attempt + 1,
+            });
+            let delay = resume
+                .retry
+                .unwrap_or_else(|| self.reconnect.delay(attempt));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    fn set_status(&self, status: ConnectionStatus) {
+        // Once closed, stay closed.
+        let status = if self.cancel.is_cancelled() {
+            ConnectionStatus::Closed
+        } else {
+            status
+        };
+        if self.status.get() == status && status != ConnectionStatus::Closed {
+            return;
+        }
+        self.status.set(status.clone());
+        event_bus().post(ConnectionChanged {
+            url: self.url.clone(),
+            status,
+        });
+    }
+
+    /// Decode frame `name` with the decoders registered for it.
+    fn dispatch(&self, name: &str, data: &str) {
+        for (_, decode) in self.decoders.iter().filter(|(n, _)| n == name) {
+            if let Err(e) = decode(data) {
+                event_bus().post(FrameRejected {
+                    url: self.url.clone(),
+                    name: name.to_string(),
+                    error: e.to_string(),
+                });
+            }
+        }
+    }
+
+    /// The request that opens the connection, with the API's headers,
+    /// interceptors and auth applied. Also returns the auth token used.
+    async fn request(&self) -> anyhow::Result<(Request, Option<String>)> {
+        let mut request = self.api.get("").build()?;
+        request.url = self.url.clone();
+        if self.kind == Kind::EventSource {
+            request.set_header("Accept", "text/event-stream");
+            request.set_header("Cache-Control", "no-cache");
+        }
+        let token = self.api.prepare(&mut request).await?;
+        Ok((request, token))
+    }
+
+    /// Open the connection within the request's timeout, refreshing a
+    /// rejected auth token once.
+    async fn open<T, F, Fut>(
+        &self,
+        mut request: Request,
+        token: Option<String>,
+        open: F,
+    ) -> anyhow::Result<T>
+    where
+        F: Fn(Request) -> Fut,
+        Fut: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        let within_timeout = |request: Request| {
+            let timeout = Timeout {
+                method: request.method,
+                url: request.url.clone(),
+                after: request.timeout,
+            };
+            let after = request.timeout;
+            let opened = open(request);
+            async move {
+                tokio::time::timeout(after, opened)
+                    .await
+                    .map_err(|_| timeout)?
+            }
+        };
+        let result = within_timeout(request.clone()).await;
+        if let Err(e) = &result
+            && e.downcast_ref::<HttpError>()
+                .is_some_and(|e| e.status == 401)
+            && let Some(rejected) = token
+            && self.api.refresh_token(&mut request, &rejected).await?
+        {
+            return within_timeout(request).await;
+        }
+        result
+    }
+
+    // ── WebSocket ────────────────────────────────────────────────────────────
+
+    async fn websocket(&self, connected: &mut bool) -> anyhow::Result<Ended> {
+        let (request, token) = self.request().await?;
+        let mut socket = self.open(request, token, ws_handshake).await?;
+        *connected = true;
+        self.set_status(ConnectionStatus::Connected);
+
+        while let Some(frame) = socket.next().await {
+            let text = match frame {
+                Ok(tungstenite::Message::Text(text)) => text.to_string(),
+                Ok(tungstenite::Message::Binary(data)) => match String::from_utf8(data.to_vec()) {
+                    Ok(text) => text,
+                    Err(_) => continue,
+                },
+                Ok(tungstenite::Message::Close(_)) | Err(_) => break,
+                Ok(_) => continue,
+            };
+            let name = serde_json::from_str::<serde_json::Value>(&text)
+                .ok()
+                .and_then(|v| Some(v.get("type")?.as_str()?.to_string()))
+                .unwrap_or_else(|| "message".to_string());
+            self.dispatch(&name, &text);
+        }
+        Ok(Ended::Dropped)
+    }
+
+    // ── Server-sent events ───────────────────────────────────────────────────
+
+    async fn event_source(
+        &self,
+        connected: &mut bool,
+        resume: &mut Resume,
+    ) -> anyhow::Result<Ended> {
+        let (mut request, token) = self.request().await?;
+        if let Some(id) = &resume.last_event_id {
+            request.set_header("Last-Event-ID", id);
+        }
+        let Some((mut conn, head, mut pending)) = self.open(request, token, sse_open).await? else {
+            return Ok(Ended::Stopped);
+        };
+        *connected = true;
+        self.set_status(ConnectionStatus::Connected);
+
+        let chunked = is_chunked(&head.headers);
+        let mut remaining = head
+            .header("Content-Length")
+            .and_then(|l| l.parse::<usize>().ok());
+        let mut parser = EventParser {
+            last_event_id: resume.last_event_id.clone(),
+            ..EventParser::default()
+        };
+        let mut chunk = [0u8; 8192];
+        loop {
+            let (data, done) = if chunked {
+                take_chunks(&mut pending)?
+            } else {
+                let take = remaining.map_or(pending.len(), |r| r.min(pending.len()));
+                remaining = remaining.map(|r| r - take);
+                (pending.drain(..take).collect(), remaining == Some(0))
+            };
+            for event in parser.feed(&data) {
+                self.dispatch(&event.name, &event.data);
+            }
+            resume.last_event_id.clone_from(&parser.last_event_id);
+            if parser.retry.is_some() {
+                resume.retry = parser.retry;
+            }
+            if done {
+                return Ok(Ended::Dropped);
+            }
+            // A read error drops the connection just like EOF.
+            let n = conn.read(&mut chunk).await.unwrap_or(0);
+            if n == 0 {
+                return Ok(Ended::Dropped);
+            }
+            pending.extend_from_slice(&chunk[..n]);
+        }
+    }
+}
+
+type SseConnection = (
+    Box<dyn crate::server_api::Connection>,
+    crate::server_api::Response,
+    Vec<u8>,
+);
+
+/// Send the SSE request and read the response head. `None` if the server
+/// answered 204, which means "do not reconnect".
+async fn sse_open(request: Request) -> anyhow::Result<Option<SseConnection>> {
+    let url = Url::parse(&request.url)?;
+    let mut conn = connect(&url).await?;
+    write_request(&mut conn, &url, &request).await?;
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let (head, len) = loop {
+        if let Some(head) = parse_head(&buf)? {
+            break head;
+        }
+        let n = conn.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof))
+                .context("connection closed before the response head");
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    if head.status == 204 {
+        return Ok(None);
+    }
+    if !head.is_success() {
+        return Err(HttpError {
+            method: request.method,
+            url: request.url,
+            status: head.status,
+            reason: head.reason,
+            body: String::from_utf8_lossy(&buf[len..]).into_owned(),
+        }
+        .into());
+    }
+    let content_type = head.header("Content-Type").unwrap_or_default();
+    anyhow::ensure!(
+        content_type.starts_with("text/event-stream"),
+        "{} is not an event stream: expected Content-Type text/event-stream, got `{content_type}`",
+        request.url
+    );
+    buf.drain(..len);
+    Ok(Some((conn, head, buf)))
+}
+
+type WebSocket = tokio_tungstenite::WebSocketStream<Box<dyn crate::server_api::Connection>>;
+
+/// Open a WebSocket connection for `request`.
+async fn ws_handshake(request: Request) -> anyhow::Result<WebSocket> {
+    use tungstenite::client::IntoClientRequest as _;
+
+    let url = Url::parse(&request.url)?;
+    let mut handshake = request.url.as_str().into_client_request()?;
+    for (name, value) in &request.headers {
+        handshake.headers_mut().insert(
+            tungstenite::http::HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("invalid header `{name}`"))?,
+            tungstenite::http::HeaderValue::from_str(value)
+                .with_context(|| format!("invalid header `{name}`"))?,
+        );
+    }
+    let conn = connect(&url).await?;
+    match tokio_tungstenite::client_async(handshake, conn).await {
+        Ok((socket, _)) => Ok(socket),
+        Err(tungstenite::Error::Http(response)) => {
+            let status = response.status().as_u16();
+            Err(HttpError {
+                method: request.method,
+                url: request.url,
+                status,
+                reason: reason_phrase(status).to_string(),
+                body: String::from_utf8_lossy(response.body().as_deref().unwrap_or_default())
+                    .into_owned(),
+            }
+            .into())
+        }
+        Err(tungstenite::Error::Io(e)) => Err(e.into()),
+        Err(e) => Err(e).context("WebSocket handshake failed"),
+    }
+}
+
+/// A dispatched server-sent event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Event {
+    name: String,
+    data: String,
+}
+
+/// Parses the `text/event-stream` format, fed in arbitrary pieces.
+#[derive(Debug, Default)]
+struct EventParser {
+    line: Vec<u8>,
+    name: String,
+    data: Vec<String>,
+    last_event_id: Option<String>,
+    retry: Option<Duration>,
+}
+
+impl EventParser {
+    /// Feed more of the stream; returns the events it completed.
+    fn feed(&mut self, bytes: &[u8]) -> Vec<Event> {
+        let mut events = vec![];
+        for &b in bytes {
+            if b != b'\n' {
+                self.line.push(b);
+                continue;
+            }
+            let line = std::mem::take(&mut self.line);
+            let line = String::from_utf8_lossy(&line);
+            let line = line.strip_suffix('\r').unwrap_or(&line);
+            if line.is_empty() {
+                let name = std::mem::take(&mut self.name);
+                let data = std::mem::take(&mut self.data);
+                if !data.is_empty() {
+                    events.push(Event {
+                        name: if name.is_empty() {
+                            "message".into()
+                        } else {
+                            name
+                        },
+                        data: data.join("\n"),
+                    });
+                }
+                continue;
+            }
+            // Lines starting with `:` are comments, e.g. keep-alives.
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.name = value.to_string(),
+                "data" => self.data.push(value.to_string()),
+                "id" if !value.contains('\0') => self.last_event_id = Some(value.to_string()),
+                "retry" => {
+                    if let Ok(ms) = value.parse() {
+                        self.retry = Some(Duration::from_millis(ms));
+                    }
+                }
+                _ => {}
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,828 @@
+// ============================================================================
+// Style sheets
+// A `StyleSheet` is a list of CSS-like rules that style nodes picked out by
+// element type, class, key and state:
+//
+//     Button.primary { background: #2563eb; color: white; }
+//     Form#login TextInput { border-color: #888; font-size: 16; }
+//     Button:hover, Button:focus { border: 2 rgb(0, 0, 200); }
+//
+// Rules cascade as in CSS: the more specific selector wins, and of equally
+// specific ones the later rule. Box properties restyle what
+// `World::box_style` returns; text properties are inherited by everything
+// inside the node, like its own text style, which still wins over the sheet.
+// ============================================================================
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::elements::Element;
+use crate::object::{Node, NodePath, Object, World};
+use crate::text::{FontWeight, TextStyle, WrapMode};
+use crate::theme::BoxStyle;
+use crate::types::{Color, HAlign};
+
+/// A set of style rules, in cascade order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StyleSheet {
+    rules: Vec<StyleRule>,
+    /// The file the sheet was loaded from, for `reload`.
+    path: Option<PathBuf>,
+}
+
+/// A rule: the style given to nodes matching any of its selectors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyleRule {
+    pub selectors: Vec<Selector>,
+    pub style: Style,
+}
+
+/// Properties set by style rules. Unset properties are left to less specific
+/// rules, and then to the theme.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Style {
+    pub background: Option<Color>,
+    pub border: Option<Color>,
+    pub border_width: Option<f32>,
+    pub radius: Option<f32>,
+    pub elevation: Option<f32>,
+    pub padding: Option<f32>,
+    /// Text properties, inherited by everything inside. Its color is also
+    /// the foreground of the node's box.
+    pub text: TextStyle,
+}
+
+/// Returned when a style sheet cannot be read or parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyleSheetError {
+    pub path: Option<PathBuf>,
+    /// The line the error is on, counting from 1; 0 if the file could not be
+    /// read.
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for StyleSheetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.path, self.line) {
+            (Some(path), 0) => write!(f, "{}: {}", path.display(), self.message),
+            (Some(path), line) => write!(f, "{}:{line}: {}", path.display(), self.message),
+            (None, line) => write!(f, "line {line}: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for StyleSheetError {}
+
+impl StyleSheet {
+    /// Parse a style sheet.
+    pub fn parse(source: &str) -> Result<Self, StyleSheetError> {
+        Ok(StyleSheet {
+            rules: parse_rules(source)?,
+            path: None,
+        })
+    }
+
+    /// Read and parse the style sheet in the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, StyleSheetError> {
+        let path = path.as_ref().to_path_buf();
+        let rules = read_rules(&path)?;
+        Ok(StyleSheet {
+            rules,
+            path: Some(path),
+        })
+    }
+
+    /// Read the file the sheet was loaded from again, so that edits show
+    /// without a restart. Returns `true` if the rules changed. On error the
+    /// sheet is left as it was.
+    pub fn reload(&mut self) -> Result<bool, StyleSheetError> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        let rules = read_rules(path)?;
+        if rules == self.rules {
+            return Ok(false);
+        }
+        self.rules = rules;
+        Ok(true)
+    }
+
+    pub fn rules(&self) -> &[StyleRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The rules of `other` after those of this sheet, so that they win
+    /// between equally specific selectors.
+    pub fn extend(&mut self, other: StyleSheet) {
+        self.rules.extend(other.rules);
+    }
+
+    /// The style of the last node of `chain`, the nodes from the root down.
+    fn cascade(&self, chain: &[Subject]) -> Style {
+        let mut matched: Vec<(Specificity, usize, &Style)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, rule)| {
+                let specificity = rule
+                    .selectors
+                    .iter()
+                    .filter(|s| s.matches(chain))
+                    .map(Selector::specificity)
+                    .max()?;
+                Some((specificity, i, &rule.style))
+            })
+            .collect();
+        matched.sort_by_key(|&(specificity, i, _)| (specificity, i));
+        let mut style = Style::default();
+        for (_, _, over) in matched {
+            style.merge(over);
+        }
+        style
+    }
+}
+
+impl Style {
+    /// Set every property `over` sets.
+    fn merge(&mut self, over: &Style) {
+        self.background = over.background.or(self.background);
+        self.border = over.border.or(self.border);
+        self.border_width = over.border_width.or(self.border_width);
+        self.radius = over.radius.or(self.radius);
+        self.elevation = over.elevation.or(self.elevation);
+        self.padding = over.padding.or(self.padding);
+        self.text = over.text.inherit(&self.text);
+    }
+
+    /// Whether any box property is set.
+    fn has_box(&self) -> bool {
+        self.background.is_some()
+            || self.border.is_some()
+            || self.border_width.is_some()
+            || self.radius.is_some()
+            || self.elevation.is_some()
+            || self.padding.is_some()
+            || self.text.color.is_some()
+    }
+
+    /// Restyle `b` with the properties set.
+    fn apply(&self, b: &mut BoxStyle) {
+        if let Some(c) = self.background {
+            b.background = c;
+        }
+        if let Some(c) = self.text.color {
+            b.foreground = c;
+        }
+        if let Some(c) = self.border {
+            b.border = c;
+        }
+        if let Some(w) = self.border_width {
+            b.border_width = w;
+        }
+        if let Some(r) = self.radius {
+            b.radius = r;
+        }
+        if let Some(e) = self.elevation {
+            b.elevation = e;
+        }
+        if let Some(p) = self.padding {
+            b.padding = p;
+        }
+    }
+}
+
+// ── Selectors ────────────────────────────────────────────────────────────────
+
+/// A node state a selector can require, as in `Button:hover`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeState {
+    /// The pointer is over the node or something inside it.
+    Hover,
+    /// The pointer went down on the node and has not come up yet.
+    Pressed,
+    /// The node has keyboard focus.
+    Focus,
+    /// A disabled button.
+    Disabled,
+    /// A form field with a validation error.
+    Invalid,
+}
+
+/// `(keys, classes and states, element types)`, compared in that order.
+type Specificity = (usize, usize, usize);
+
+/// A chain of compound selectors such as `Form#login > .row TextInput`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    /// From the outermost to the node being styled.
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Part {
+    /// How this part relates to the one before it.
+    combinator: Combinator,
+    /// `None` for `*` or no type at all.
+    element: Option<&'static str>,
+    key: Option<String>,
+    classes: Vec<String>,
+    states: Vec<NodeState>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    /// Whitespace: anywhere inside.
+    Descendant,
+    /// `>`: directly inside.
+    Child,
+}
+
+/// The names selectors use for element types.
+const ELEMENT_TYPES: &[&str] = &[
+    "Board",
+    "Card",
+    "Column",
+    "Row",
+    "Girl",
+    "Text",
+    "RichText",
+    "TextClock",
+    "Button",
+    "Form",
+    "TextInput",
+    "TextArea",
+    "Checkbox",
+    "RadioGroup",
+    "Select",
+    "Slider",
+    "NumberInput",
+    "Switch",
+    "Query",
+    "Markdown",
+];
+
+impl Selector {
+    /// Parse a single selector (no commas).
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut parts: Vec<Part> = Vec::new();
+        let mut combinator = Combinator::Descendant;
+        let mut chars = source.trim().chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            if c == '>' {
+                if parts.is_empty() || combinator == Combinator::Child {
+                    return Err(format!("misplaced `>` in `{source}`"));
+                }
+                combinator = Combinator::Child;
+                chars.next();
+                continue;
+            }
+            let mut part = Part {
+                combinator,
+                element: None,
+                key: None,
+                classes: Vec::new(),
+                states: Vec::new(),
+            };
+            if c == '*' {
+                chars.next();
+            } else if is_ident(c) {
+                let name = ident(&mut chars);
+                part.element = Some(
+                    ELEMENT_TYPES
+                        .iter()
+                        .find(|&&t| t == name)
+                        .ok_or_else(|| format!("unknown element type `{name}`"))?,
+                );
+            }
+            while let Some(&c) = chars.peek()
+                && matches!(c, '.' | '#' | ':')
+            {
+                chars.next();
+                let name = ident(&mut chars);
+                if name.is_empty() {
+                    return Err(format!("expected a name after `{c}` in `{source}`"));
+                }
+                match c {
+                    '.' => part.classes.push(name),
+                    '#' => part.key = Some(name),
+                    _ => part.states.push(match name.as_str() {
+                        "hover" => NodeState::Hover,
+                        "pressed" | "active" => NodeState::Pressed,
+                        "focus" => NodeState::Focus,
+                        "disabled" => NodeState::Disabled,
+                        "invalid" => NodeState::Invalid,
+                        _ => return Err(format!("unknown state `:{name}`")),
+                    }),
+                }
+            }
+            match chars.peek() {
+                Some(&c) if !c.is_whitespace() && c != '>' => {
+                    return Err(format!("unexpected `{c}` in `{source}`"));
+                }
+                _ => {}
+            }
+            parts.push(part);
+            combinator = Combinator::Descendant;
+        }
+        if parts.is_empty() || combinator == Combinator::Child {
+            return Err(format!("incomplete selector `{source}`"));
+        }
+        Ok(Selector { parts })
+    }
+
+    pub fn specificity(&self) -> Specificity {
+        self.parts.iter().fold((0, 0, 0), |(k, c, t), p| {
+            (
+                k + p.key.is_some() as usize,
+                c + p.classes.len() + p.states.len(),
+                t + p.element.is_some() as usize,
+            )
+        })
+    }
+
+    /// Whether the selector matches the last node of `chain`, the nodes from
+    /// the root down.
+    fn matches(&self, chain: &[Subject]) -> bool {
+        matches_parts(&self.parts, chain)
+    }
+}
+
+fn matches_parts(parts: &[Part], chain: &[Subject]) -> bool {
+    let (Some((part, rest)), Some((subject, ancestors))) = (parts.split_last(), chain.split_last())
+    else {
+        return false;
+    };
+    if !part.matches(subject) {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+    match part.combinator {
+        Combinator::Child => matches_parts(rest, ancestors),
+        Combinator::Descendant => {
+            (1..=ancestors.len()).any(|n| matches_parts(rest, &ancestors[..n]))
+        }
+    }
+}
+
+impl Part {
+    fn matches(&self, subject: &Subject) -> bool {
+        self.element.is_none_or(|e| e == subject.element)
+            && self.key.as_deref().is_none_or(|k| k == subject.key)
+            && self
+                .classes
+                .iter()
+                .all(|c| subject.class.split_whitespace().any(|s| s == c))
+            && self.states.iter().all(|s| subject.states.contains(s))
+    }
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn ident(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut name = String::new();
+    while let Some(&c) = chars.peek()
+        && is_ident(c)
+    {
+        name.push(c);
+        chars.next();
+    }
+    name
+}
+
+// ── Parsing ──────────────────────────────────────────────────────────────────
+
+fn read_rules(path: &Path) -> Result<Vec<StyleRule>, StyleSheetError> {
+    let source = std::fs::read_to_string(path).map_err(|e| StyleSheetError {
+        path: Some(path.to_path_buf()),
+        line: 0,
+        message: e.to_string(),
+    })?;
+    parse_rules(&source).map_err(|e| StyleSheetError {
+        path: Some(path.to_path_buf()),
+        ..e
+    })
+}
+
+fn parse_rules(source: &str) -> Result<Vec<StyleRule>, StyleSheetError> {
+    let source = strip_comments(source);
+    let line_at = |offset: usize| source[..offset].matches('\n').count() + 1;
+    let error = |offset: usize, message: String| StyleSheetError {
+        path: None,
+        line: line_at(offset),
+        message,
+    };
+    let mut rules = Vec::new();
+    let mut rest = 0;
+    while let Some(skip) = source[rest..].find(|c: char| !c.is_whitespace()) {
+        let start = rest + skip;
+        let open = match source[start..].find(['{', '}']) {
+            Some(i) if source[start + i..].starts_with('{') => start + i,
+            _ => return Err(error(start, "expected `{` after the selectors".into())),
+        };
+        let close = match source[open + 1..].find(['{', '}']) {
+            Some(i) if source[open + 1 + i..].starts_with('}') => open + 1 + i,
+            _ => return Err(error(open, "expected `}` to end the rule".into())),
+        };
+        let selectors = source[start..open]
+            .split(',')
+            .map(Selector::parse)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|message| error(start, message))?;
+        let mut style = Style::default();
+        let mut offset = open + 1;
+        for declaration in source[open + 1..close].split(';') {
+            let at = offset + declaration.len() - declaration.trim_start().len();
+            offset += declaration.len() + 1;
+            if declaration.trim().is_empty() {
+                continue;
+            }
+            let Some((name, value)) = declaration.split_once(':') else {
+                return Err(error(
+                    at,
+                    format!("expected `name: value`, got `{}`", declaration.trim()),
+                ));
+            };
+            set_property(&mut style, name.trim(), value.trim()).map_err(|m| error(at, m))?;
+        }
+        rules.push(StyleRule { selectors, style });
+        rest = close + 1;
+    }
+    Ok(rules)
+}
+
+/// `source` with `/* comments */` blanked out, keeping line breaks so that
+/// line numbers stay right.
+fn strip_comments(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut rest = source;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find("*/")
+            .map_or(rest.len(), |i| start + i + 2);
+        out.extend(
+            rest[start..end]
+                .chars()
+                .map(|c| if c == '\n' { '\n' } else { ' ' }),
+        );
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn set_property(style: &mut Style, name: &str, value: &str) -> Result<(), String> {
+    let t = &mut style.text;
+    match name {
+        "background" | "background-color" => style.background = Some(color(value)?),
+        "color" => t.color = Some(color(value)?),
+        "border-color" => style.border = Some(color(value)?),
+        "border-width" => style.border_width = Some(length(value)?),
+        // A width and a color, in either order.
+        "border" => {
+            for word in words(value) {
+                match length(word) {
+                    Ok(w) => style.border_width = Some(w),
+                    Err(_) => style.border = Some(color(word)?),
+                }
+            }
+        }
+        "border-radius" | "radius" => style.radius = Some(length(value)?),
+        "elevation" => style.elevation = Some(length(value)?),
+        "padding" => style.padding = Some(length(value)?),
+        "font-family" => t.family = Some(value.trim_matches(['"', '\'']).to_string().into()),
+        "font-size" => t.size = Some(length(value)?),
+        "font-weight" => {
+            t.weight = Some(match value {
+                "normal" => FontWeight::NORMAL,
+                "bold" => FontWeight::BOLD,
+                _ => FontWeight(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid font weight `{value}`"))?,
+                ),
+            })
+        }
+        "font-style" => {
+            t.italic = Some(match value {
+                "normal" => false,
+                "italic" | "oblique" => true,
+                _ => return Err(format!("invalid font style `{value}`")),
+            })
+        }
+        "text-decoration" => {
+            t.underline = Some(false);
+            t.strikethrough = Some(false);
+            for word in words(value) {
+                match word {
+                    "none" => {}
+                    "underline" => t.underline = Some(true),
+                    "line-through" => t.strikethrough = Some(true),
+                    _ => return Err(format!("invalid text decoration `{word}`")),
+                }
+            }
+        }
+        "line-height" => t.line_height = Some(length(value)?),
+        "letter-spacing" => t.letter_spacing = Some(length(value)?),
+        "text-align" => {
+            t.align = Some(match value {
+                "left" => HAlign::Left,
+                "center" => HAlign::Center,
+                "right" => HAlign::Right,
+                _ => return Err(format!("invalid alignment `{value}`")),
+            })
+        }
+        "max-lines" => {
+            t.max_lines = Some(
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid line count `{value}`"))?,
+            )
+        }
+        "wrap" => {
+            t.wrap = Some(match value {
+                "word-or-char" => WrapMode::WordOrChar,
+                "word" => WrapMode::Word,
+                "char" => WrapMode::Char,
+                "none" => WrapMode::None,
+                _ => return Err(format!("invalid wrap mode `{value}`")),
+            })
+        }
+        _ => return Err(format!("unknown property `{name}`")),
+    }
+    Ok(())
+}
+
+/// Space-separated words, keeping `rgb(...)` whole.
+fn words(value: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut depth = 0;
+    let mut start = None;
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                if let Some(s) = start.take() {
+                    words.push(&value[s..i]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(i);
+    }
+    words.extend(start.map(|s| &value[s..]));
+    words
+}
+
+/// A length in pixels, written with or without `px`.
+fn length(value: &str) -> Result<f32, String> {
+    value
+        .strip_suffix("px")
+        .unwrap_or(value)
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid length `{value}`"))
+}
+
+/// `#rgb`, `#rrggbb`, `#rrggbbaa`, `rgb(r, g, b)`, `rgba(r, g, b, alpha)`
+/// with alpha from 0 to 1, or `black`, `white` or `transparent`.
+fn color(value: &str) -> Result<Color, String> {
+    let invalid = || format!("invalid color `{value}`");
+    match value {
+        "black" => return Ok(Color::BLACK),
+        "white" => return Ok(Color::WHITE),
+        "transparent" => return Ok(Color::TRANSPARENT),
+        _ => {}
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        return match digits[..] {
+            [r, g, b] => Ok(Color::rgb(r * 17, g * 17, b * 17)),
+            [r1, r2, g1, g2, b1, b2] => Ok(Color::rgb(r1 * 16 + r2, g1 * 16 + g2, b1 * 16 + b2)),
+            [r1, r2, g1, g2, b1, b2, a1, a2] => Ok(Color::rgba(
+                r1 * 16 + r2,
+                g1 * 16 + g2,
+                b1 * 16 + b2,
+                a1 * 16 + a2,
+            )),
+            _ => Err(invalid()),
+        };
+    }
+    let (args, alpha) = if let Some(args) = value.strip_prefix("rgba(") {
+        (args, true)
+    } else if let Some(args) = value.strip_prefix("rgb(") {
+        (args, false)
+    } else {
+        return Err(invalid());
+    };
+    let args: Vec<&str> = args
+        .strip_suffix(')')
+        .ok_or_else(invalid)?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    let channel = |s: &str| s.parse::<u8>().map_err(|_| invalid());
+    match (&args[..], alpha) {
+        ([r, g, b], false) => Ok(Color::rgb(channel(r)?, channel(g)?, channel(b)?)),
+        ([r, g, b, a], true) => {
+            let a: f32 = a.parse().map_err(|_| invalid())?;
+            Ok(Color::rgba(
+                channel(r)?,
+                channel(g)?,
+                channel(b)?,
+                (a.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+// ── Matching the tree ────────────────────────────────────────────────────────
+
+/// What selectors can see of a node.
+struct Subject<'a> {
+    element: &'static str,
+    class: &'a str,
+    key: &'a str,
+    states: Vec<NodeState>,
+}
+
+impl<'a> Node<'a> {
+    /// The element type name selectors use for this node, such as `"Button"`.
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Node::Button(_) => "Button",
+            Node::Object(obj) => match obj {
+                Object::Board(_) => "Board",
+                Object::Card(_) => "Card",
+                Object::Column(_) => "Column",
+                Object::Row(_) => "Row",
+                Object::Girl(_) => "Girl",
+                Object::Element(e) => match e {
+                    Element::Text(_) => "Text",
+                    Element::RichText(_) => "RichText",
+                    Element::TextClock(_) => "TextClock",
+                    Element::Button(_) => "Button",
+                    Element::Form(_) => "Form",
+                    Element::TextInput(_) => "TextInput",
+                    Element::TextArea(_) => "TextArea",
+                    Element::Checkbox(_) => "Checkbox",
+                    Element::RadioGroup(_) => "RadioGroup",
+                    Element::Select(_) => "Select",
+                    Element::Slider(_) => "Slider",
+                    Element::NumberInput(_) => "NumberInput",
+                    Element::Switch(_) => "Switch",
+                    Element::Query(_) => "Query",
+                    Element::Markdown(_) => "Markdown",
+                },
+            },
+        }
+    }
+
+    /// The node's style sheet classes and key; empty for nodes without them.
+    pub fn class_and_key(&self) -> (&'a str, &'a str) {
+        match *self {
+            Node::Button(b) => (&b.class, &b.key),
+            Node::Object(obj) => match obj {
+                Object::Board(b) => (&b.class, &b.key),
+                Object::Card(c) => (&c.class, &c.key),
+                Object::Column(c) => (&c.class, &c.key),
+                Object::Row(r) => (&r.class, &r.key),
+                Object::Element(e) => match e {
+                    Element::Text(t) => (&t.class, &t.key),
+                    Element::RichText(t) => (&t.class, &t.key),
+                    Element::Button(b) => (&b.class, &b.key),
+                    Element::Form(f) => (&f.class, &f.key),
+                    Element::TextInput(t) => (&t.class, &t.key),
+                    Element::TextArea(t) => (&t.class, &t.key),
+                    Element::Checkbox(c) => (&c.class, &c.key),
+                    Element::RadioGroup(r) => (&r.class, &r.key),
+                    Element::Select(s) => (&s.class, &s.key),
+                    Element::Slider(s) => (&s.class, &s.key),
+                    Element::NumberInput(n) => (&n.class, &n.key),
+                    Element::Markdown(m) => (&m.class, &m.key),
+                    _ => ("", ""),
+                },
+                Object::Girl(_) => ("", ""),
+            },
+        }
+    }
+}
+
+impl World {
+    /// What the style sheet gives the node at `path`, with every rule that
+    /// matches it in its current state cascaded.
+    pub fn sheet_style(&self, path: &[usize]) -> Style {
+        if self.styles.is_empty() {
+            return Style::default();
+        }
+        let chain: Option<Vec<Subject>> = (0..=path.len())
+            .map(|n| Some(self.subject(self.root.node(&path[..n])?, &path[..n])))
+            .collect();
+        chain.map_or_else(Style::default, |chain| self.styles.cascade(&chain))
+    }
+
+    /// Styles the style sheet gives nodes, by path, for layout.
+    pub(crate) fn sheet_styles(&self) -> HashMap<NodePath, Style> {
+        let mut styles = HashMap::new();
+        if !self.styles.is_empty() {
+            let mut chain = Vec::new();
+            self.collect_sheet_styles(
+                Node::Object(&self.root),
+                &mut vec![],
+                &mut chain,
+                &mut styles,
+            );
+        }
+        styles
+    }
+
+    fn collect_sheet_styles<'a>(
+        &'a self,
+        node: Node<'a>,
+        path: &mut NodePath,
+        chain: &mut Vec<Subject<'a>>,
+        styles: &mut HashMap<NodePath, Style>,
+    ) {
+        chain.push(self.subject(node, path));
+        let style = self.styles.cascade(chain);
+        if style != Style::default() {
+            styles.insert(path.clone(), style);
+        }
+        if let Node::Object(obj) = node {
+            let mut children: Vec<Node> = obj.children().iter().map(Node::Object).collect();
+            if let Object::Element(Element::Form(f)) = obj {
+                children.extend([
+                    Node::Button(&f.submit_button),
+                    Node::Button(&f.reset_button),
+                ]);
+            }
+            for (i, child) in children.into_iter().enumerate() {
+                path.push(i);
+                self.collect_sheet_styles(child, path, chain, styles);
+                path.pop();
+            }
+        }
+        chain.pop();
+    }
+
+    fn subject<'a>(&self, node: Node<'a>, path: &[usize]) -> Subject<'a> {
+        let (class, key) = node.class_and_key();
+        let mut states = Vec::new();
+        if self.pointer.hovered().iter().any(|h| h == path) {
+            states.push(NodeState::Hover);
+        }
+        if self.pointer.pressed().is_some_and(|p| p == path) {
+            states.push(NodeState::Pressed);
+        }
+        if self.focused().is_some_and(|f| f == path) {
+            states.push(NodeState::Focus);
+        }
+        if node.as_button().is_some_and(|b| b.disabled) {
+            states.push(NodeState::Disabled);
+        }
+        if let Node::Object(Object::Element(e)) = node
+            && crate::form::as_field(e).is_some_and(|f| f.error().is_some())
+        {
+            states.push(NodeState::Invalid);
+        }
+        Subject {
+            element: node.type_name(),
+            class,
+            key,
+            states,
+        }
+    }
+
+    /// `style` with the style sheet's box properties for the node at `path`
+    /// on top. Nodes the theme draws no box for get one if the sheet sets
+    /// any box property.
+    pub(crate) fn restyle_box(&self, path: &[usize], style: Option<BoxStyle>) -> Option<BoxStyle> {
+        let sheet = self.sheet_style(path);
+        if !sheet.has_box() {
+            return style;
+        }
+        let mut style = style.unwrap_or_else(|| BoxStyle::plain(&self.theme));
+        sheet.apply(&mut style);
+        Some(style)
+    }
+}
@@ -0,0 +1,582 @@
+// ============================================================================
+// Text shaping
+// Text is shaped with cosmic-text against fonts bundled with the crate, so it
+// measures the same on every machine and needs no system fonts. The shaper
+// handles font fallback, bidirectional text and line breaking; layout asks it
+// for the size of `Text`, `Button` and `TextInput` content. How text is set
+// is described by a `TextStyle`, which containers pass down to the text
+// inside them.
+// ============================================================================
+
+use std::ops::Range;
+
+use cosmic_text::{
+    Attrs, Buffer, Ellipsize, EllipsizeHeightLimit, Family, FontSystem, LineIter, Metrics, Shaping,
+    Style, Weight, Wrap, fontdb,
+};
+
+use crate::layout::LINE_HEIGHT;
+use crate::shared_str::SharedStr;
+use crate::types::{Color, HAlign};
+
+/// Font size of body text, in pixels.
+pub const FONT_SIZE: f32 = 14.0;
+
+/// The family text is set in unless it asks for another.
+pub const DEFAULT_FAMILY: &str = "DejaVu Sans";
+/// The family used for monospaced text.
+pub const MONOSPACE_FAMILY: &str = "DejaVu Sans Mono";
+
+/// Fonts compiled into the crate (see `fonts/LICENSE`).
+static BUNDLED_FONTS: [&[u8]; 4] = [
+    include_bytes!("../fonts/DejaVuSans.ttf"),
+    include_bytes!("../fonts/DejaVuSans-Bold.ttf"),
+    include_bytes!("../fonts/DejaVuSans-Oblique.ttf"),
+    include_bytes!("../fonts/DejaVuSansMono.ttf"),
+];
+
+/// Measured layouts are kept until there are this many.
+const CACHE_LIMIT: usize = 4096;
+
+// ── Style ────────────────────────────────────────────────────────────────────
+
+/// How heavy a font is, from 100 (thin) to 900 (black).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FontWeight(pub u16);
+
+impl FontWeight {
+    pub const THIN: FontWeight = FontWeight(100);
+    pub const LIGHT: FontWeight = FontWeight(300);
+    pub const NORMAL: FontWeight = FontWeight(400);
+    pub const MEDIUM: FontWeight = FontWeight(500);
+    pub const SEMIBOLD: FontWeight = FontWeight(600);
+    pub const BOLD: FontWeight = FontWeight(700);
+    pub const BLACK: FontWeight = FontWeight(900);
+}
+
+impl Default for FontWeight {
+    fn default() -> Self {
+        FontWeight::NORMAL
+    }
+}
+
+/// Where lines of text that do not fit their width are broken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WrapMode {
+    /// Between words, or inside a word too long for a line of its own.
+    #[default]
+    WordOrChar,
+    /// Only between words; long words overflow.
+    Word,
+    /// Between any two characters.
+    Char,
+    /// Never: lines only end at line breaks.
+    None,
+}
+
+/// What a text is, for the theme to style it (see `Theme::role_style`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextRole {
+    /// A heading of level 1 to 6.
+    Heading(u8),
+    /// Secondary text, e.g. quotes.
+    Muted,
+}
+
+/// How text is set. Unset properties are inherited from the nearest
+/// container that sets them, and default to the bundled sans-serif font at
+/// `FONT_SIZE`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextStyle {
+    /// A loaded family (see `font_families`), or one of `"sans-serif"`,
+    /// `"serif"` and `"monospace"`.
+    pub family: Option<SharedStr>,
+    /// Font size in pixels.
+    pub size: Option<f32>,
+    pub weight: Option<FontWeight>,
+    pub italic: Option<bool>,
+    pub underline: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub color: Option<Color>,
+    /// Distance between baselines in pixels; defaults to the same
+    /// proportion of the font size as `LINE_HEIGHT` is of `FONT_SIZE`.
+    pub line_height: Option<f32>,
+    /// Extra space after each character, in pixels.
+    pub letter_spacing: Option<f32>,
+    /// Where lines shorter than the widest one go. Unset, they start on
+    /// the left, or on the right in right-to-left paragraphs.
+    pub align: Option<HAlign>,
+    /// Lines shown at most; the last one ends in an ellipsis if the text
+    /// is cut short.
+    pub max_lines: Option<usize>,
+    pub wrap: Option<WrapMode>,
+    /// Color of spans with a link that set no color of their own.
+    pub link_color: Option<Color>,
+    /// What the text is. The theme's style for it goes between what the
+    /// node inherits and its own style; unlike the other properties, it is
+    /// not inherited.
+    pub role: Option<TextRole>,
+}
+
+impl TextStyle {
+    /// This style with every unset property taken from `parent`.
+    pub fn inherit(&self, parent: &TextStyle) -> TextStyle {
+        TextStyle {
+            family: self.family.clone().or_else(|| parent.family.clone()),
+            size: self.size.or(parent.size),
+            weight: self.weight.or(parent.weight),
+            italic: self.italic.or(parent.italic),
+            underline: self.underline.or(parent.underline),
+            strikethrough: self.strikethrough.or(parent.strikethrough),
+            color: self.color.or(parent.color),
+            line_height: self.line_height.or(parent.line_height),
+            letter_spacing: self.letter_spacing.or(parent.letter_spacing),
+            align: self.align.or(parent.align),
+            max_lines: self.max_lines.or(parent.max_lines),
+            wrap: self.wrap.or(parent.wrap),
+            link_color: self.link_color.or(parent.link_color),
+            role: self.role,
+        }
+    }
+
+    /// The font size in pixels.
+    pub fn font_size(&self) -> f32 {
+        self.size.unwrap_or(FONT_SIZE)
+    }
+
+    /// The line height in pixels.
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+            .unwrap_or(self.font_size() * LINE_HEIGHT / FONT_SIZE)
+    }
+
+    /// The color text is drawn in.
+    pub fn color(&self) -> Color {
+        self.color.unwrap_or(Color::BLACK)
+    }
+
+    /// The style `span` is set in within text set in this style. Links
+    /// without a color of their own take the link color.
+    pub fn span_style(&self, span: &TextSpan) -> TextStyle {
+        let mut style = span.style.inherit(self);
+        if span.link.is_some() && span.style.color.is_none() {
+            style.color = self.link_color.or(style.color);
+        }
+        style
+    }
+
+    /// Whether text is underlined.
+    pub fn is_underlined(&self) -> bool {
+        self.underline == Some(true)
+    }
+
+    /// Whether text is struck through.
+    pub fn is_struck_through(&self) -> bool {
+        self.strikethrough == Some(true)
+    }
+
+    fn attrs(&self) -> Attrs<'_> {
+        let family = match self.family.as_deref() {
+            None | Some("sans-serif") => Family::SansSerif,
+            Some("serif") => Family::Serif,
+            Some("monospace") => Family::Monospace,
+            Some(name) => Family::Name(name),
+        };
+        let mut attrs = Attrs::new()
+            .family(family)
+            .weight(Weight(self.weight.unwrap_or_default().0))
+            .style(if self.italic == Some(true) {
+                Style::Italic
+            } else {
+                Style::Normal
+            });
+        if let Some(spacing) = self.letter_spacing {
+            // cosmic-text takes the spacing in ems.
+            attrs = attrs.letter_spacing(spacing / self.font_size());
+        }
+        attrs
+    }
+}
+
+/// A run of text set in its own style within a `RichText`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextSpan {
+    pub text: SharedStr,
+    /// Set on top of the style of the text the span is in.
+    pub style: TextStyle,
+    /// Where the span links to. Clicking it sends `LinkClicked`.
+    pub link: Option<SharedStr>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<SharedStr>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.style.weight = Some(FontWeight::BOLD);
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.style.italic = Some(true);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.style.color = Some(color);
+        self
+    }
+
+    /// Font size in pixels.
+    pub fn size(mut self, size: f32) -> Self {
+        self.style.size = Some(size);
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.style.underline = Some(true);
+        self
+    }
+
+    pub fn strikethrough(mut self) -> Self {
+        self.style.strikethrough = Some(true);
+        self
+    }
+
+    /// Make the span a link to `target`.
+    pub fn link(mut self, target: impl Into<SharedStr>) -> Self {
+        self.link = Some(target.into());
+        self
+    }
+}
+
+impl From<&'static str> for TextSpan {
+    fn from(text: &'static str) -> Self {
+        TextSpan::new(text)
+    }
+}
+
+impl From<String> for TextSpan {
+    fn from(text: String) -> Self {
+        TextSpan::new(text)
+    }
+}
+
+/// The shaped layout of a piece of text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    /// Width of the widest line.
+    pub width: f32,
+    pub height: f32,
+    /// Visual lines, top to bottom. Text that is wrapped or contains line
+    /// breaks has several.
+    pub lines: Vec<TextLine>,
+    /// Whether `TextStyle::max_lines` cut the text short.
+    pub truncated: bool,
+}
+
+impl TextLayout {
+    /// The glyph at `(x, y)`, relative to the top left of the text.
+    pub fn glyph_at(&self, x: f32, y: f32) -> Option<&Glyph> {
+        let line = self
+            .lines
+            .iter()
+            .find(|l| y >= l.top && y < l.top + l.height)?;
+        line.glyphs.iter().find(|g| x >= g.x && x < g.x + g.width)
+    }
+}
+
+/// One visual line of a `TextLayout`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// Byte range of the text on this line.
+    pub range: Range<usize>,
+    /// Offset of the start of the line from the left of the text, as set by
+    /// the alignment.
+    pub x: f32,
+    /// Offset of the top of the line from the top of the text.
+    pub top: f32,
+    /// The tallest line height of the text on the line.
+    pub height: f32,
+    pub width: f32,
+    /// Whether the paragraph this line belongs to runs right to left.
+    pub rtl: bool,
+    /// Glyphs in the order of the text; `x` places them on the line, so in
+    /// a right-to-left paragraph the first glyph is on the right.
+    pub glyphs: Vec<Glyph>,
+}
+
+/// A shaped glyph; ligatures and clusters cover several characters. The
+/// ellipsis ending a truncated line covers none: its range is empty, at
+/// the point where the text was cut.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glyph {
+    /// Byte range of the characters the glyph draws.
+    pub range: Range<usize>,
+    /// Offset from the left of the text.
+    pub x: f32,
+    pub width: f32,
+    /// Index of the `TextSpan` the glyph belongs to; `0` in plain text.
+    pub span: usize,
+}
+
+/// Shape `text` in the default style, breaking lines at `max_width` if
+/// given. Empty text still takes up one line.
+pub fn measure_text(text: &str, max_width: Option<f32>) -> TextLayout {
+    measure_styled(text, &TextStyle::default(), max_width)
+}
+
+/// Shape `text` set in `style`, breaking lines at `max_width` if given.
+pub fn measure_styled(text: &str, style: &TextStyle, max_width: Option<f32>) -> TextLayout {
+    let runs = [(0..text.len(), style.clone())];
+    measure_runs(text, style, &runs, max_width)
+}
+
+/// Shape `spans` as one paragraph set in `style`, each span on top of it,
+/// breaking lines at `max_width` if given (see `TextStyle::span_style`).
+/// Byte ranges in the layout are into the spans' text joined together.
+pub fn measure_rich(spans: &[TextSpan], style: &TextStyle, max_width: Option<f32>) -> TextLayout {
+    let mut text = String::new();
+    let runs: Vec<(Range<usize>, TextStyle)> = spans
+        .iter()
+        .map(|span| {
+            let start = text.len();
+            text.push_str(&span.text);
+            (start..text.len(), style.span_style(span))
+        })
+        .collect();
+    measure_runs(&text, style, &runs, max_width)
+}
+
+/// Shape `text` with each of `runs` set in its own style.
+fn measure_runs(
+    text: &str,
+    style: &TextStyle,
+    runs: &[(Range<usize>, TextStyle)],
+    max_width: Option<f32>,
+) -> TextLayout {
+    let key = (
+        text.to_string(),
+        max_width.map(f32::to_bits),
+        format!("{style:?}{runs:?}"),
+    );
+    let mut shaper = shaper().lock().unwrap();
+    if let Some(layout) = shaper.cache.get(&key) {
+        return layout.clone();
+    }
+    let layout = shaper.shape(text, style, runs, max_width);
+    if shaper.cache.len() >= CACHE_LIMIT {
+        shaper.cache.clear();
+    }
+    shaper.cache.insert(key, layout.clone());
+    layout
+}
+
+/// Add the fonts in `data` (a TrueType or OpenType file or collection) to
+/// those text is shaped with, for fallback and for styles that name them.
+/// Returns the families it contained.
+pub fn load_font(data: Vec<u8>) -> Vec<String> {
+    let mut shaper = shaper().lock().unwrap();
+    let db = shaper.fonts.db_mut();
+    let before: std::collections::HashSet<_> = db.faces().map(|f| f.id).collect();
+    db.load_font_data(data);
+    let mut families: Vec<String> = db
+        .faces()
+        .filter(|f| !before.contains(&f.id))
+        .flat_map(|f| f.families.iter().map(|(name, _)| name.clone()))
+        .collect();
+    families.dedup();
+    shaper.cache.clear();
+    families
+}
+
+/// The families of all loaded fonts, sorted.
+pub fn font_families() -> Vec<String> {
+    let shaper = shaper().lock().unwrap();
+    let mut families: Vec<String> = shaper
+        .fonts
+        .db()
+        .faces()
+        .flat_map(|f| f.families.iter().map(|(name, _)| name.clone()))
+        .collect();
+    families.sort();
+    families.dedup();
+    families
+}
+
+struct Shaper {
+    fonts: FontSystem,
+    cache: std::collections::HashMap<(String, Option<u32>, String), TextLayout>,
+}
+
+fn shaper() -> &'static std::sync::Mutex<Shaper> {
+    static SHAPER: std::sync::OnceLock<std::sync::Mutex<Shaper>> = std::sync::OnceLock::new();
+    SHAPER.get_or_init(|| {
+        let mut db = fontdb::Database::new();
+        for font in BUNDLED_FONTS {
+            db.load_font_source(fontdb::Source::Binary(std::sync::Arc::new(font)));
+        }
+        db.set_sans_serif_family(DEFAULT_FAMILY);
+        db.set_serif_family(DEFAULT_FAMILY);
+        db.set_monospace_family(MONOSPACE_FAMILY);
+        // A fixed locale keeps fallback choices the same everywhere.
+        let fonts = FontSystem::new_with_locale_and_db("en-US".to_string(), db);
+        std::sync::Mutex::new(Shaper {
+            fonts,
+            cache: Default::default(),
+        })
+    })
+}
+
+impl Shaper {
+    fn shape(
+        &mut self,
+        text: &str,
+        style: &TextStyle,
+        runs: &[(Range<usize>, TextStyle)],
+        max_width: Option<f32>,
+    ) -> TextLayout {
+        let line_height = style.line_height();
+        let mut lines = self.lines(text, style, runs, max_width);
+
+        // cosmic-text cuts each paragraph on its own, but the limit is for the
+        // whole text: past it, end the last line shown with an ellipsis. The
+        // ellipsis may not fit after the cut, so take back one grapheme at a
+        // time until it lands on the last line shown.
+        let limit = style.max_lines.unwrap_or(usize::MAX).max(1);
+        let mut truncated = false;
+        if lines.len() > limit && !lines[limit - 1].glyphs.iter().any(is_ellipsis) {
+            let start = lines[limit - 1].range.start;
+            let mut cut = lines[limit - 1].range.end;
+            lines = loop {
+                let text = format!("{}…", &text[..cut]);
+                // The ellipsis goes in the span the text is cut in.
+                let mut runs: Vec<_> = runs
+                    .iter()
+                    .filter(|(range, _)| range.start < cut || range.start == 0)
+                    .cloned()
+                    .collect();
+                if let Some((range, _)) = runs.last_mut() {
+                    range.end = text.len();
+                }
+                let shorter = self.lines(&text, style, &runs, max_width);
+                let fits = shorter
+                    .get(limit - 1)
+                    .is_some_and(|line| line.glyphs.iter().any(|g| g.range.start >= cut));
+                if fits || cut <= start {
+                    break shorter;
+                }
+                cut = crate::text_edit::prev_boundary(&text, cut);
+            };
+            for line in &mut lines {
+                for glyph in &mut line.glyphs {
+                    if glyph.range.start >= cut {
+                        glyph.range = cut..cut;
+                    }
+                }
+                line.range.end = line.range.end.min(cut);
+            }
+            truncated = true;
+        }
+        lines.truncate(limit);
+        truncated |= lines.iter().flat_map(|l| &l.glyphs).any(is_ellipsis);
+
+        let width = lines.iter().map(|l| l.width).fold(0.0, f32::max);
+        for line in &mut lines {
+            let room = width - line.width;
+            line.x = match style.align {
+                Some(HAlign::Left) => 0.0,
+                Some(HAlign::Center) => room / 2.0,
+                Some(HAlign::Right) => room,
+                None if line.rtl => room,
+                None => 0.0,
+            };
+            for glyph in &mut line.glyphs {
+                glyph.x += line.x;
+            }
+        }
+        TextLayout {
+            width,
+            height: lines.last().map_or(line_height, |l| l.top + l.height),
+            lines,
+            truncated,
+        }
+    }
+
+    /// Lays `text` out into lines, without the `max_lines` limit for the
+    /// whole text or alignment.
+    fn lines(
+        &mut self,
+        text: &str,
+        style: &TextStyle,
+        runs: &[(Range<usize>, TextStyle)],
+        max_width: Option<f32>,
+    ) -> Vec<TextLine> {
+        let mut buffer = Buffer::new(
+            &mut self.fonts,
+            Metrics::new(style.font_size(), style.line_height()),
+        );
+        buffer.set_wrap(match style.wrap.unwrap_or_default() {
+            WrapMode::WordOrChar => Wrap::WordOrGlyph,
+            WrapMode::Word => Wrap::Word,
+            WrapMode::Char => Wrap::Glyph,
+            WrapMode::None => Wrap::None,
+        });
+        if let Some(lines) = style.max_lines {
+            buffer.set_ellipsize(Ellipsize::End(EllipsizeHeightLimit::Lines(lines)));
+        }
+        buffer.set_size(max_width, None);
+        let spans = runs.iter().enumerate().map(|(i, (range, run))| {
+            let metrics = Metrics::new(run.font_size(), run.line_height());
+            (
+                &text[range.clone()],
+                run.attrs().metrics(metrics).metadata(i),
+            )
+        });
+        buffer.set_rich_text(spans, &style.attrs(), Shaping::Advanced, None);
+        buffer.shape_until_scroll(&mut self.fonts, false);
+
+        // Glyph ranges are relative to the paragraph they are in.
+        let starts: Vec<usize> = LineIter::new(text).map(|(range, _)| range.start).collect();
+        buffer
+            .layout_runs()
+            .map(|run| {
+                let start = starts.get(run.line_i).copied().unwrap_or(0);
+                let glyphs: Vec<Glyph> = run
+                    .glyphs
+                    .iter()
+                    .map(|g| Glyph {
+                        range: start + g.start..start + g.end,
+                        x: g.x,
+                        width: g.w,
+                        span: g.metadata,
+                    })
+                    .collect();
+                let range = match (
+                    glyphs.iter().map(|g| g.range.start).min(),
+                    glyphs.iter().map(|g| g.range.end).max(),
+                ) {
+                    (Some(first), Some(last)) => first..last,
+                    _ => start..start,
+                };
+                TextLine {
+                    range,
+                    x: 0.0,
+                    top: run.line_top,
+                    height: run.line_height,
+                    width: run.line_w,
+                    rtl: run.rtl,
+                    glyphs,
+                }
+            })
+            .collect()
+    }
+}
+
+fn is_ellipsis(glyph: &Glyph) -> bool {
+    glyph.range.is_empty()
+}
@@ -0,0 +1,408 @@
+// ============================================================================
+// Text editing
+// Grapheme-aware editing shared by the text elements. Offsets are byte offsets
+// into the value and always sit on grapheme cluster boundaries, so a caret can
+// never split an emoji or a base character from its combining marks.
+// ============================================================================
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::keyboard::{Key, KeyEvent};
+use crate::object::NodePath;
+use crate::shared_str::SharedStr;
+use crate::traits::Message;
+
+/// Caret and selection of an editable text value.
+///
+/// The selection spans from `anchor` to `caret`; when both are equal it is
+/// empty and only the caret is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextSelection {
+    /// Where the selection started, as a byte offset.
+    pub anchor: usize,
+    /// Where the caret is, as a byte offset.
+    pub caret: usize,
+}
+
+impl TextSelection {
+    /// An empty selection with the caret at `offset`.
+    pub fn caret_at(offset: usize) -> Self {
+        Self {
+            anchor: offset,
+            caret: offset,
+        }
+    }
+
+    pub fn new(anchor: usize, caret: usize) -> Self {
+        Self { anchor, caret }
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.anchor == self.caret
+    }
+
+    /// The selected byte range, in ascending order.
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.anchor.min(self.caret)..self.anchor.max(self.caret)
+    }
+}
+
+/// Where a caret movement or deletion goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// One grapheme to the left.
+    Left,
+    /// One grapheme to the right.
+    Right,
+    /// To the start of the current or previous word.
+    WordLeft,
+    /// To the end of the current or next word.
+    WordRight,
+    /// To the start of the value.
+    Home,
+    /// To the end of the value.
+    End,
+    /// To the start of the current visual line.
+    LineStart,
+    /// To the end of the current visual line.
+    LineEnd,
+    /// To the same column on the visual line above.
+    Up,
+    /// To the same column on the visual line below.
+    Down,
+}
+
+/// An editing operation on a text value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditCommand {
+    /// Replace the selection with the given text.
+    Insert(String),
+    /// Delete the selection or, if it is empty, from the caret to `Motion`.
+    Delete(Motion),
+    /// Move the caret, extending the selection when `extend` is set.
+    Move {
+        motion: Motion,
+        extend: bool,
+    },
+    SelectAll,
+}
+
+impl EditCommand {
+    /// The command a key press maps to in a text field, if any.
+    pub fn from_key(event: &KeyEvent) -> Option<Self> {
+        let m = event.modifiers;
+        // Word-wise with Ctrl (Alt on macOS); Meta is left to the platform.
+        let word = m.ctrl || m.alt;
+        let extend = m.shift;
+        let motion = |plain, by_word| if word { by_word } else { plain };
+        Some(match event.key {
+            Key::Char('a' | 'A') if m.ctrl || m.meta => EditCommand::SelectAll,
+            Key::Char(_) if m.ctrl || m.alt || m.meta => return None,
+            Key::Char(c) => EditCommand::Insert(c.to_string()),
+            Key::Backspace => EditCommand::Delete(motion(Motion::Left, Motion::WordLeft)),
+            Key::Delete => EditCommand::Delete(motion(Motion::Right, Motion::WordRight)),
+            Key::Left => EditCommand::Move {
+                motion: motion(Motion::Left, Motion::WordLeft),
+                extend,
+            },
+            Key::Right => EditCommand::Move {
+                motion: motion(Motion::Right, Motion::WordRight),
+                extend,
+            },
+            Key::Home => EditCommand::Move {
+                motion: if m.ctrl {
+                    Motion::Home
+                } else {
+                    Motion::LineStart
+                },
+                extend,
+            },
+            Key::End => EditCommand::Move {
+                motion: if m.ctrl { Motion::End } else { Motion::LineEnd },
+                extend,
+            },
+            Key::Up => EditCommand::Move {
+                motion: Motion::Up,
+                extend,
+            },
+            Key::Down => EditCommand::Move {
+                motion: Motion::Down,
+                extend,
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// Constraints a text element puts on its value.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct EditRules {
+    /// Maximum length in graphemes; `0` means unlimited.
+    pub max_len: u32,
+    /// Drop line breaks and tabs, not only other control characters, from
+    /// inserted text.
+    pub single_line: bool,
+    /// Soft wrap width of a multi-line value, in graphemes; `0` only breaks
+    /// at newlines.
+    pub wrap_cols: usize,
+    /// Word motions jump to the ends of the value, so word boundaries of a
+    /// masked value are not revealed.
+    pub masked: bool,
+}
+
+/// Apply `cmd` to `text`. Returns `true` if the value changed.
+pub(crate) fn apply(
+    text: &mut String,
+    sel: &mut TextSelection,
+    cmd: &EditCommand,
+    rules: EditRules,
+) -> bool {
+    clamp(text, sel);
+    match cmd {
+        EditCommand::Insert(s) => insert(text, sel, s, rules),
+        EditCommand::Delete(motion) => {
+            let range = if sel.is_collapsed() {
+                let target = motion_target(text, sel.caret, *motion, rules);
+                sel.caret.min(target)..sel.caret.max(target)
+            } else {
+                sel.range()
+            };
+            if range.is_empty() {
+                *sel = TextSelection::caret_at(range.start);
+                return false;
+            }
+            text.replace_range(range.clone(), "");
+            *sel = TextSelection::caret_at(range.start);
+            true
+        }
+        EditCommand::Move { motion, extend } => {
+            let caret = if !*extend && !sel.is_collapsed() {
+                // Collapse an existing selection towards the motion.
+                match motion {
+                    Motion::Left | Motion::WordLeft => sel.range().start,
+                    Motion::Right | Motion::WordRight => sel.range().end,
+                    _ => motion_target(text, sel.caret, *motion, rules),
+                }
+            } else {
+                motion_target(text, sel.caret, *motion, rules)
+            };
+            sel.caret = caret;
+            if !*extend {
+                sel.anchor = caret;
+            }
+            false
+        }
+        EditCommand::SelectAll => {
+            *sel = TextSelection::new(0, text.len());
+            false
+        }
+    }
+}
+
+fn insert(text: &mut String, sel: &mut TextSelection, s: &str, rules: EditRules) -> bool {
+    let mut s: String = if rules.single_line {
+        s.chars().filter(|c| !c.is_control()).collect()
+    } else {
+        s.chars()
+            .filter(|c| matches!(c, '\n' | '\t') || !c.is_control())
+            .collect()
+    };
+    let range = sel.range();
+    if rules.max_len > 0 {
+        let kept = grapheme_len(text) - grapheme_len(&text[range.clone()]);
+        let room = (rules.max_len as usize).saturating_sub(kept);
+        if let Some((end, _)) = s.grapheme_indices(true).nth(room) {
+            s.truncate(end);
+        }
+    }
+    if s.is_empty() && range.is_empty() {
+        return false;
+    }
+    text.replace_range(range.clone(), &s);
+    *sel = TextSelection::caret_at(range.start + s.len());
+    true
+}
+
+/// Number of grapheme clusters in `text`.
+pub fn grapheme_len(text: &str) -> usize {
+    text.graphemes(true).count()
+}
+
+/// Snap both ends of `sel` into `text` and onto grapheme boundaries.
+pub(crate) fn clamp(text: &str, sel: &mut TextSelection) {
+    sel.anchor = floor_boundary(text, sel.anchor);
+    sel.caret = floor_boundary(text, sel.caret);
+}
+
+fn floor_boundary(text: &str, offset: usize) -> usize {
+    if offset >= text.len() {
+        return text.len();
+    }
+    text.grapheme_indices(true)
+        .map(|(i, _)| i)
+        .take_while(|&i| i <= offset)
+        .last()
+        .unwrap_or(0)
+}
+
+pub(crate) fn prev_boundary(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+fn next_boundary(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .graphemes(true)
+        .next()
+        .map_or(offset, |g| offset + g.len())
+}
+
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+fn motion_target(text: &str, caret: usize, motion: Motion, rules: EditRules) -> usize {
+    match motion {
+        Motion::Left => prev_boundary(text, caret),
+        Motion::Right => next_boundary(text, caret),
+        Motion::WordLeft if rules.masked => 0,
+        Motion::WordRight if rules.masked => text.len(),
+        Motion::WordLeft => text[..caret]
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, w)| is_word(w))
+            .map_or(0, |(i, _)| i),
+        Motion::WordRight => text[caret..]
+            .split_word_bound_indices()
+            .find(|(_, w)| is_word(w))
+            .map_or(text.len(), |(i, w)| caret + i + w.len()),
+        Motion::Home => 0,
+        Motion::End => text.len(),
+        Motion::LineStart | Motion::Up if rules.single_line => 0,
+        Motion::LineEnd | Motion::Down if rules.single_line => text.len(),
+        Motion::LineStart | Motion::LineEnd | Motion::Up | Motion::Down => {
+            let lines = wrap(text, rules.wrap_cols);
+            let line = line_of(&lines, caret);
+            let col = grapheme_len(&text[lines[line].start..caret]);
+            match motion {
+                Motion::LineStart => lines[line].start,
+                Motion::LineEnd => line_end(text, &lines, line),
+                Motion::Up if line == 0 => 0,
+                Motion::Down if line + 1 == lines.len() => text.len(),
+                _ => {
+                    let target = if motion == Motion::Up {
+                        line - 1
+                    } else {
+                        line + 1
+                    };
+                    let start = lines[target].start;
+                    text[start..line_end(text, &lines, target)]
+                        .grapheme_indices(true)
+                        .nth(col)
+                        .map_or(line_end(text, &lines, target), |(i, _)| start + i)
+                }
+            }
+        }
+    }
+}
+
+/// Where the caret goes at the end of visual line `line`. A soft-wrapped line
+/// ends at the start of the next one, which would show the caret there, so
+/// the caret stops before its last grapheme instead.
+fn line_end(text: &str, lines: &[std::ops::Range<usize>], line: usize) -> usize {
+    let end = lines[line].end;
+    match lines.get(line + 1) {
+        Some(next) if next.start == end => prev_boundary(text, end).max(lines[line].start),
+        _ => end,
+    }
+}
+
+/// Split `text` into visual lines of at most `cols` graphemes, breaking after
+/// whitespace where possible and always at `'\n'`. Ranges exclude the
+/// newlines themselves. `cols == 0` disables soft wrapping.
+pub fn wrap(text: &str, cols: usize) -> Vec<std::ops::Range<usize>> {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    for hard in text.split('\n') {
+        let line_end = line_start + hard.len();
+        let mut start = line_start;
+        let mut count = 0;
+        let mut last_break = None;
+        for (i, g) in hard.grapheme_indices(true) {
+            let at = line_start + i;
+            if cols > 0 && count == cols {
+                let brk = last_break.filter(|&b| b > start).unwrap_or(at);
+                lines.push(start..brk);
+                count = grapheme_len(&text[brk..at]);
+                start = brk;
+                last_break = None;
+            }
+            count += 1;
+            if g.chars().all(char::is_whitespace) {
+                last_break = Some(at + g.len());
+            }
+        }
+        lines.push(start..line_end);
+        line_start = line_end + 1;
+    }
+    lines
+}
+
+/// Index of the visual line in `lines` (as returned by `wrap`) that shows a
+/// caret at `offset`.
+pub fn line_of(lines: &[std::ops::Range<usize>], offset: usize) -> usize {
+    lines.iter().rposition(|l| l.start <= offset).unwrap_or(0)
+}
+
+/// `text` with every grapheme replaced by a bullet.
+pub fn mask(text: &str) -> String {
+    "\u{2022}".repeat(grapheme_len(text))
+}
+
+// ── Messages ─────────────────────────────────────────────────────────────────
+
+/// Sent on the event bus after each edit that changes a text field's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdited {
+    pub path: NodePath,
+    pub name: SharedStr,
+    pub value: String,
+}
+
+impl Message for TextEdited {}
+
+/// Sent on the event bus when an edited text field is committed, by pressing
+/// `Enter` or by moving focus away from it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextCommitted {
+    pub path: NodePath,
+    pub name: SharedStr,
+    pub value: String,
+}
+
+impl Message for TextCommitted {}
+
+// ── Display ──────────────────────────────────────────────────────────────────
+
+/// How a run of displayed text is underlined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Underline {
+    /// Text being composed by an input method.
+    Thin,
+    /// The clause of the composition the input method is working on.
+    Thick,
+}
+
+/// What a text element draws: its (possibly masked) value with any input
+/// method composition spliced in. Offsets are byte offsets into `text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayText {
+    pub text: String,
+    /// Where to draw the caret; `None` hides it.
+    pub caret: Option<usize>,
+    /// The highlighted selection, empty while composing.
+    pub selection: std::ops::Range<usize>,
+    pub underlines: Vec<(std::ops::Range<usize>, Underline)>,
+}
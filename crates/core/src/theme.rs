@@ -0,0 +1,502 @@
+// ============================================================================
+// Theming
+// A `Theme` is a set of design tokens: a palette, a typography scale, a
+// spacing scale, corner radii and elevations. The `World` holds one; its
+// typography is the text style every node inherits, and `World::box_style`
+// derives the look of buttons, cards and fields from its tokens. Layout
+// takes gaps from its spacing scale and padding from its box styles. Sending
+// `SetTheme` switches the theme of every world on its next `update`.
+// ============================================================================
+
+use crate::elements::Element;
+use crate::event_bus::event_bus;
+use crate::object::{Node, Object, World};
+use crate::shared_str::SharedStr;
+use crate::text::{FONT_SIZE, FontWeight, TextRole, TextStyle};
+use crate::traits::{Message, MessageContext, MessageHandler};
+use crate::types::Color;
+
+/// Which family of colors a theme uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ThemeMode {
+    #[default]
+    Light,
+    Dark,
+    HighContrast,
+}
+
+/// The colors of a theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    /// Behind everything.
+    pub background: Color,
+    /// Cards and fields.
+    pub surface: Color,
+    pub text: Color,
+    /// Secondary text: hints, quotes, disabled labels.
+    pub muted: Color,
+    /// Buttons and other accents.
+    pub primary: Color,
+    /// Text on `primary`.
+    pub on_primary: Color,
+    /// `primary` under the pointer.
+    pub primary_hover: Color,
+    pub border: Color,
+    /// The ring around the focused node.
+    pub focus: Color,
+    pub error: Color,
+    /// Disabled buttons.
+    pub disabled: Color,
+    pub link: Color,
+}
+
+/// Font family and sizes of a theme.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typography {
+    /// A loaded family, or `"sans-serif"`, `"serif"` or `"monospace"`.
+    pub family: SharedStr,
+    /// Body text size in pixels.
+    pub body: f32,
+    /// Size of captions and hints.
+    pub small: f32,
+    /// Heading sizes, from level 1 to level 6.
+    pub headings: [f32; 6],
+}
+
+/// Distances between and inside elements, smallest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpacingScale {
+    pub xs: f32,
+    pub sm: f32,
+    pub md: f32,
+    pub lg: f32,
+    pub xl: f32,
+}
+
+/// Corner radii.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Radii {
+    /// Fields and checkboxes.
+    pub small: f32,
+    /// Buttons.
+    pub medium: f32,
+    /// Cards.
+    pub large: f32,
+}
+
+/// How far above the background things appear, as shadow depth in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elevation {
+    pub flat: f32,
+    /// Buttons and cards.
+    pub raised: f32,
+    /// Open dropdowns and other overlays.
+    pub overlay: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: SharedStr,
+    pub mode: ThemeMode,
+    pub palette: Palette,
+    pub typography: Typography,
+    pub spacing: SpacingScale,
+    pub radii: Radii,
+    pub elevation: Elevation,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::light()
+    }
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Theme {
+            name: "light".into(),
+            mode: ThemeMode::Light,
+            palette: Palette {
+                background: Color::rgb(245, 246, 248),
+                surface: Color::WHITE,
+                text: Color::rgb(24, 26, 31),
+                muted: Color::rgb(100, 104, 112),
+                primary: Color::rgb(37, 99, 235),
+                on_primary: Color::WHITE,
+                primary_hover: Color::rgb(29, 78, 216),
+                border: Color::rgb(208, 212, 220),
+                focus: Color::rgb(59, 130, 246),
+                error: Color::rgb(200, 30, 30),
+                disabled: Color::rgb(226, 228, 233),
+                link: Color::rgb(0, 90, 200),
+            },
+            typography: Typography::default(),
+            spacing: SpacingScale::default(),
+            radii: Radii::default(),
+            elevation: Elevation::default(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Theme {
+            name: "dark".into(),
+            mode: ThemeMode::Dark,
+            palette: Palette {
+                background: Color::rgb(18, 19, 23),
+                surface: Color::rgb(30, 32, 38),
+                text: Color::rgb(230, 232, 236),
+                muted: Color::rgb(150, 155, 165),
+                primary: Color::rgb(96, 150, 250),
+                on_primary: Color::rgb(10, 12, 16),
+                primary_hover: Color::rgb(130, 172, 252),
+                border: Color::rgb(62, 66, 76),
+                focus: Color::rgb(130, 172, 252),
+                error: Color::rgb(248, 113, 113),
+                disabled: Color::rgb(48, 51, 60),
+                link: Color::rgb(120, 170, 255),
+            },
+            ..Theme::light()
+        }
+    }
+
+    /// Black on white with strong borders, for low vision.
+    pub fn high_contrast() -> Self {
+        Theme {
+            name: "high-contrast".into(),
+            mode: ThemeMode::HighContrast,
+            palette: Palette {
+                background: Color::WHITE,
+                surface: Color::WHITE,
+                text: Color::BLACK,
+                muted: Color::BLACK,
+                primary: Color::BLACK,
+                on_primary: Color::WHITE,
+                primary_hover: Color::rgb(40, 40, 40),
+                border: Color::BLACK,
+                focus: Color::rgb(0, 0, 200),
+                error: Color::rgb(160, 0, 0),
+                disabled: Color::rgb(200, 200, 200),
+                link: Color::rgb(0, 0, 160),
+            },
+            // No shadows: everything is outlined instead.
+            elevation: Elevation {
+                flat: 0.0,
+                raised: 0.0,
+                overlay: 0.0,
+            },
+            ..Theme::light()
+        }
+    }
+
+    /// The text style everything inherits: body text in the theme's family
+    /// and text color.
+    pub fn text_style(&self) -> TextStyle {
+        TextStyle {
+            family: Some(self.typography.family.clone()),
+            size: Some(self.typography.body),
+            color: Some(self.palette.text),
+            link_color: Some(self.palette.link),
+            ..Default::default()
+        }
+    }
+
+    /// The style of a heading of `level` (1 to 6).
+    pub fn heading_style(&self, level: usize) -> TextStyle {
+        let sizes = &self.typography.headings;
+        TextStyle {
+            size: Some(sizes[level.clamp(1, sizes.len()) - 1]),
+            weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        }
+    }
+
+    /// The style of text with `role`.
+    pub fn role_style(&self, role: TextRole) -> TextStyle {
+        match role {
+            TextRole::Heading(level) => self.heading_style(level as usize),
+            TextRole::Muted => TextStyle {
+                color: Some(self.palette.muted),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Room the theme leaves inside `node`'s box: cards, buttons and fields
+    /// are padded, other nodes are not.
+    pub(crate) fn padding(&self, node: Node) -> f32 {
+        match node {
+            Node::Object(Object::Card(_)) => self.spacing.md,
+            Node::Object(Object::Element(Element::Button(_))) | Node::Button(_) => self.spacing.sm,
+            Node::Object(Object::Element(
+                Element::TextInput(_)
+                | Element::TextArea(_)
+                | Element::NumberInput(_)
+                | Element::Select(_)
+                | Element::Checkbox(_)
+                | Element::RadioGroup(_)
+                | Element::Slider(_),
+            )) => self.spacing.xs,
+            _ => 0.0,
+        }
+    }
+
+    /// Borders are drawn at least this wide.
+    fn border_width(&self) -> f32 {
+        match self.mode {
+            ThemeMode::HighContrast => 2.0,
+            _ => 1.0,
+        }
+    }
+}
+
+impl Default for Typography {
+    fn default() -> Self {
+        Typography {
+            family: "sans-serif".into(),
+            body: FONT_SIZE,
+            small: 12.0,
+            headings: [28.0, 24.0, 20.0, 17.0, 15.0, 14.0],
+        }
+    }
+}
+
+/// The default scale; `sm` is `layout::SPACING` and `md` is
+/// `layout::CARD_PADDING`.
+impl Default for SpacingScale {
+    fn default() -> Self {
+        SpacingScale {
+            xs: 4.0,
+            sm: 8.0,
+            md: 12.0,
+            lg: 16.0,
+            xl: 24.0,
+        }
+    }
+}
+
+impl Default for Radii {
+    fn default() -> Self {
+        Radii {
+            small: 4.0,
+            medium: 6.0,
+            large: 10.0,
+        }
+    }
+}
+
+impl Default for Elevation {
+    fn default() -> Self {
+        Elevation {
+            flat: 0.0,
+            raised: 2.0,
+            overlay: 8.0,
+        }
+    }
+}
+
+// ── Box styles ───────────────────────────────────────────────────────────────
+
+/// How the box of a node is drawn.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxStyle {
+    pub background: Color,
+    /// Text and icons inside the box.
+    pub foreground: Color,
+    pub border: Color,
+    pub border_width: f32,
+    pub radius: f32,
+    pub elevation: f32,
+    /// Room between the edge of the box and what is inside. Layout keeps it
+    /// around the children of containers and on either side of a button's
+    /// text; fields keep their own metrics.
+    pub padding: f32,
+}
+
+impl BoxStyle {
+    /// No background or border, and the theme's text color.
+    pub fn plain(theme: &Theme) -> Self {
+        BoxStyle {
+            background: Color::TRANSPARENT,
+            foreground: theme.palette.text,
+            border: Color::TRANSPARENT,
+            border_width: 0.0,
+            radius: 0.0,
+            elevation: theme.elevation.flat,
+            padding: 0.0,
+        }
+    }
+}
+
+impl World {
+    /// The box of the node at `path` as the current theme and style sheet
+    /// draw it, taking hover, press, focus, disabled and error states into
+    /// account. `None` for nodes without a box of their own, such as text and
+    /// rows, unless the style sheet gives them one.
+    pub fn box_style(&self, path: &[usize]) -> Option<BoxStyle> {
+        self.animate_box(path, self.restyle_box(path, self.theme_box_style(path)))
+    }
+
+    /// The box of the node at `path` from the theme's tokens alone.
+    pub(crate) fn theme_box_style(&self, path: &[usize]) -> Option<BoxStyle> {
+        let theme = &self.theme;
+        let p = &theme.palette;
+        let node = self.root.node(path)?;
+        let focused = self.focused().is_some_and(|f| f == path);
+        let hovered = self.pointer.hovered().last().is_some_and(|h| h == path);
+        let pressed = self.pointer.pressed().is_some_and(|h| h == path);
+        let plain = BoxStyle::plain(theme);
+        let mut style = match node {
+            Node::Object(Object::Board(_)) => BoxStyle {
+                background: p.background,
+                ..plain
+            },
+            Node::Object(Object::Card(_)) => BoxStyle {
+                background: p.surface,
+                border: p.border,
+                border_width: theme.border_width(),
+                radius: theme.radii.large,
+                elevation: theme.elevation.raised,
+                padding: theme.padding(node),
+                ..plain
+            },
+            Node::Object(Object::Element(Element::Button(b))) | Node::Button(b) => {
+                let (background, foreground) = match () {
+                    _ if b.disabled => (p.disabled, p.muted),
+                    _ if hovered || pressed => (p.primary_hover, p.on_primary),
+                    _ => (p.primary, p.on_primary),
+                };
+                BoxStyle {
+                    background,
+                    foreground,
+                    radius: theme.radii.medium,
+                    elevation: if b.disabled || pressed {
+                        theme.elevation.flat
+                    } else {
+                        theme.elevation.raised
+                    },
+                    padding: theme.padding(node),
+                    ..plain
+                }
+            }
+            Node::Object(Object::Element(
+                e @ (Element::TextInput(_)
+                | Element::TextArea(_)
+                | Element::NumberInput(_)
+                | Element::Select(_)
+                | Element::Checkbox(_)
+                | Element::RadioGroup(_)
+                | Element::Slider(_)),
+            )) => {
+                let error = crate::form::as_field(e).is_some_and(|f| f.error().is_some());
+                BoxStyle {
+                    background: p.surface,
+                    border: if error { p.error } else { p.border },
+                    border_width: theme.border_width(),
+                    radius: theme.radii.small,
+                    elevation: match e {
+                        Element::Select(s) if s.open => theme.elevation.overlay,
+                        _ => theme.elevation.flat,
+                    },
+                    padding: theme.padding(node),
+                    ..plain
+                }
+            }
+            _ => return None,
+        };
+        if focused {
+            style.border = p.focus;
+            style.border_width = 2.0 * theme.border_width();
+        }
+        Some(style)
+    }
+
+    /// The text style in effect at the node at `path`, starting from the
+    /// theme's, with the style sheet's between what nodes inherit and their
+    /// own. An animated foreground color replaces the node's text color.
+    pub fn text_style_at(&self, path: &[usize]) -> TextStyle {
+        let sheet = (0..=path.len())
+            .map(|n| (path[..n].to_vec(), self.sheet_style(&path[..n])))
+            .collect();
+        let mut style =
+            self.root
+                .text_style_styled(path, &self.theme.text_style(), &sheet, &self.theme);
+        if let Some(color) = self.animated_foreground(path) {
+            style.color = Some(color);
+        }
+        style
+    }
+
+    /// Adopt the theme last sent with `SetTheme`, if this world has not
+    /// adopted that switch yet and it differs from the current theme. A theme
+    /// assigned to `World::theme` directly stays until the next `SetTheme`.
+    /// Returns `true` if the theme changed.
+    pub(crate) fn update_theme(&mut self) -> bool {
+        let latest = latest_theme().lock().unwrap().clone();
+        let Some((switch, theme)) = latest else {
+            return false;
+        };
+        if switch == self.theme_switch {
+            return false;
+        }
+        self.theme_switch = switch;
+        if theme == self.theme {
+            return false;
+        }
+        self.theme = theme;
+        event_bus().send(ThemeChanged {
+            name: self.theme.name.clone(),
+            mode: self.theme.mode,
+        });
+        true
+    }
+}
+
+// ── Switching ────────────────────────────────────────────────────────────────
+
+/// Switch every world to this theme on its next `World::update`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetTheme(pub Theme);
+
+impl Message for SetTheme {}
+
+/// Counts the `SetTheme` messages sent, so each world adopts every switch
+/// once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThemeSwitch(u64);
+
+/// Sent on the event bus when a world has switched to another theme.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThemeChanged {
+    pub name: SharedStr,
+    pub mode: ThemeMode,
+}
+
+impl Message for ThemeChanged {}
+
+/// The theme sent last with `SetTheme`, and which switch that was.
+static LATEST: std::sync::OnceLock<std::sync::Mutex<Option<(ThemeSwitch, Theme)>>> =
+    std::sync::OnceLock::new();
+
+/// Registers the `SetTheme` handler the first time it is called. Worlds call
+/// it when they are created, so that themes sent before their first update
+/// are not missed.
+pub(crate) fn latest_theme() -> &'static std::sync::Mutex<Option<(ThemeSwitch, Theme)>> {
+    LATEST.get_or_init(|| {
+        event_bus().register_handler::<SetThemeHandler, SetTheme>(std::sync::Arc::new(
+            std::sync::Mutex::new(SetThemeHandler),
+        ));
+        Default::default()
+    })
+}
+
+struct SetThemeHandler;
+
+impl MessageHandler<SetTheme> for SetThemeHandler {
+    async fn handle(&mut self, msg: &SetTheme, _: &mut MessageContext) {
+        let mut latest = latest_theme().lock().unwrap();
+        let ThemeSwitch(n) = latest
+            .as_ref()
+            .map_or(ThemeSwitch(0), |(switch, _)| *switch);
+        *latest = Some((ThemeSwitch(n + 1), msg.0.clone()));
+    }
+}
@@ -5,12 +5,84 @@ use crate::object::Object;
 ///
 /// Note: this crate targets a single-threaded environment, so `Message` does not
 /// require `Send`/`Sync` — only `'static` is required for type-based storage.
-pub trait Message: 'static + Send + Sync {}
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a message",
+    note = "derive it with `#[derive(Message)]` or annotate it with `#[message]`"
+)]
+pub trait Message: 'static + Send + Sync {
+    /// Metadata describing how the bus treats this message type.
+    ///
+    /// Override it with `#[message(name = "...", priority = N, coalesce)]`.
+    fn meta() -> MessageMeta
+    where
+        Self: Sized,
+    {
+        MessageMeta::of::<Self>()
+    }
+
+    /// The variant of an enum message. A queued `coalesce` message is only
+    /// replaced by one of the same variant; messages without a variant
+    /// replace any of their type.
+    ///
+    /// Provided for enums by `#[derive(Message)]` and `#[message]`.
+    fn variant(&self) -> Option<std::mem::Discriminant<Self>>
+    where
+        Self: Sized,
+    {
+        None
+    }
+}
+
+/// Per-type message metadata, produced by `Message::meta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageMeta {
+    /// Human-readable name used in debug output.
+    pub name: &'static str,
+    /// Queued messages with a higher priority are delivered first.
+    pub priority: i32,
+    /// If `true`, posting this message while one of the same type is still
+    /// queued replaces the queued one instead of adding a duplicate.
+    pub coalesce: bool,
+}
+
+impl MessageMeta {
+    /// Default metadata for `T`: its type name, priority `0`, no coalescing.
+    pub fn of<T: ?Sized>() -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            priority: 0,
+            coalesce: false,
+        }
+    }
+}
 
 /// Context passed into `InnerMovement::update` allowing widgets to read timing information.
 #[derive(Debug, Clone)]
 pub struct UpdateContext {
+    /// When the current frame is.
     pub time: std::time::Instant,
+    /// Time since the previous frame; zero on the first.
+    pub delta: std::time::Duration,
+    /// Frames since the first, which is frame 0.
+    pub frame: u64,
+}
+
+impl UpdateContext {
+    /// The first frame, at `time`.
+    pub fn new(time: std::time::Instant) -> Self {
+        Self {
+            time,
+            delta: std::time::Duration::ZERO,
+            frame: 0,
+        }
+    }
+
+    /// Move on to the next frame, `delta` after this one.
+    pub fn advance(&mut self, delta: std::time::Duration) {
+        self.time += delta;
+        self.delta = delta;
+        self.frame += 1;
+    }
 }
 
 /// A trait for internal widgets that update over time.
@@ -26,6 +98,10 @@ pub trait InnerTicker {
 }
 
 /// A trait for widgets that handle clicks.
+///
+/// `on_click` runs in the background on a clone of the element, so the
+/// element is free to handle the messages it sends; keep state that must
+/// outlast the click in a `State`.
 #[allow(async_fn_in_trait)]
 pub trait ClickHandler {
     async fn on_click(&mut self);
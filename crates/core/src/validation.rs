@@ -0,0 +1,191 @@
+// ============================================================================
+// Field validation
+// Fields carry a list of declarative `Rule`s. A `Form` checks them, in order,
+// before its submit handler runs; the first failing rule of each field sets
+// that field's `error`, and submission is refused while any field has one.
+// ============================================================================
+
+use std::future::Future;
+use std::pin::Pin;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::shared_str::SharedStr;
+
+/// Boxed future returned by custom validators.
+pub type ValidatorFuture = Pin<Box<dyn Future<Output = Result<(), String>> + Send>>;
+
+/// A validation rule for a form field.
+///
+/// Rules other than `required` and `matches` accept an empty value, so
+/// optional fields can still carry e.g. an `email` rule.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    check: Check,
+    message: Option<SharedStr>,
+}
+
+#[derive(Clone)]
+enum Check {
+    Required,
+    MinLen(usize),
+    MaxLen(usize),
+    Pattern(regex::Regex),
+    Email,
+    Matches(SharedStr),
+    Custom(std::sync::Arc<dyn Fn(String) -> ValidatorFuture + Send + Sync>),
+}
+
+impl std::fmt::Debug for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Check::Required => f.write_str("Required"),
+            Check::MinLen(n) => write!(f, "MinLen({n})"),
+            Check::MaxLen(n) => write!(f, "MaxLen({n})"),
+            Check::Pattern(re) => write!(f, "Pattern({:?})", re.as_str()),
+            Check::Email => f.write_str("Email"),
+            Check::Matches(field) => write!(f, "Matches({field:?})"),
+            Check::Custom(_) => f.write_str("<validator>"),
+        }
+    }
+}
+
+impl Rule {
+    fn new(check: Check) -> Self {
+        Self {
+            check,
+            message: None,
+        }
+    }
+
+    /// The value must not be empty or only whitespace.
+    pub fn required() -> Self {
+        Self::new(Check::Required)
+    }
+
+    /// At least `n` characters (grapheme clusters).
+    pub fn min_len(n: usize) -> Self {
+        Self::new(Check::MinLen(n))
+    }
+
+    /// At most `n` characters (grapheme clusters).
+    pub fn max_len(n: usize) -> Self {
+        Self::new(Check::MaxLen(n))
+    }
+
+    /// The value must match the regular expression `pattern`.
+    ///
+    /// # Panics
+    ///
+    /// If `pattern` is not a valid regular expression.
+    pub fn pattern(pattern: &str) -> Self {
+        match regex::Regex::new(pattern) {
+            Ok(re) => Self::regex(re),
+            Err(e) => panic!("invalid validation pattern {pattern:?}: {e}"),
+        }
+    }
+
+    /// The value must match `re`.
+    pub fn regex(re: regex::Regex) -> Self {
+        Self::new(Check::Pattern(re))
+    }
+
+    /// The value must look like an e-mail address.
+    pub fn email() -> Self {
+        Self::new(Check::Email)
+    }
+
+    /// The value must equal the value of the field named `field` in the same
+    /// form, e.g. a password confirmation. An empty value only matches an
+    /// empty field.
+    pub fn matches(field: impl Into<SharedStr>) -> Self {
+        Self::new(Check::Matches(field.into()))
+    }
+
+    /// Run `f` on the value; `Err(message)` fails the rule. The check may be
+    /// asynchronous, e.g. asking the server whether a user name is taken.
+    pub fn custom<F, Fut>(f: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), String>> + Send + 'static,
+    {
+        Self::new(Check::Custom(std::sync::Arc::new(move |value| {
+            Box::pin(f(value))
+        })))
+    }
+
+    /// Replace the rule's error message.
+    pub fn message(mut self, message: impl Into<SharedStr>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Check `value`. `field` looks up the value of another field by name.
+    pub(crate) async fn check(
+        &self,
+        value: &str,
+        field: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), String> {
+        let failed = match &self.check {
+            Check::Required => value.trim().is_empty(),
+            Check::Matches(other) => field(other).unwrap_or_default() != value,
+            _ if value.is_empty() => false,
+            Check::MinLen(n) => value.graphemes(true).count() < *n,
+            Check::MaxLen(n) => value.graphemes(true).count() > *n,
+            Check::Pattern(re) => !re.is_match(value),
+            Check::Email => !is_email(value),
+            Check::Custom(f) => {
+                return f(value.to_string())
+                    .await
+                    .map_err(|e| self.message.as_ref().map_or(e, |m| m.to_string()));
+            }
+        };
+        if !failed {
+            return Ok(());
+        }
+        Err(match &self.message {
+            Some(m) => m.to_string(),
+            None => self.default_message(),
+        })
+    }
+
+    fn default_message(&self) -> String {
+        match &self.check {
+            Check::Required => "This field is required".to_string(),
+            Check::MinLen(n) => format!("Must be at least {n} characters"),
+            Check::MaxLen(n) => format!("Must be at most {n} characters"),
+            Check::Pattern(_) => "Invalid format".to_string(),
+            Check::Email => "Enter a valid e-mail address".to_string(),
+            Check::Matches(other) => format!("Must match {other}"),
+            Check::Custom(_) => "Invalid value".to_string(),
+        }
+    }
+}
+
+/// `local@domain.tld`, without whitespace and with a single `@`.
+fn is_email(value: &str) -> bool {
+    static EMAIL: std::sync::OnceLock<regex::Regex> = std::sync::OnceLock::new();
+    EMAIL
+        .get_or_init(|| regex::Regex::new(r"^[^@\s]+@[^@\s.]+(\.[^@\s.]+)+$").unwrap())
+        .is_match(value)
+}
+
+/// Returned by `Form::submit` when validation fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidForm {
+    /// `(field name, message)` for each invalid field, in tree order.
+    pub errors: Vec<(SharedStr, String)>,
+}
+
+impl std::fmt::Display for InvalidForm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("form is invalid")?;
+        for (i, (name, message)) in self.errors.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{sep}{name}: {message}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for InvalidForm {}
@@ -0,0 +1,243 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use snow_ui::prelude::*;
+use snow_ui::{Animatable, AnimatedValue};
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+/// Step `movement` by `frame` until `total` has passed.
+fn run(movement: &mut impl InnerMovement, frame: Duration, total: Duration) {
+    let mut ctx = UpdateContext::new(Instant::now());
+    let mut passed = Duration::ZERO;
+    while passed < total {
+        ctx.advance(frame);
+        movement.update(&mut ctx);
+        passed += frame;
+    }
+}
+
+#[test]
+fn tweens_follow_their_easing_delay_and_repeats() {
+    assert_eq!(Easing::Linear.apply(0.25), 0.25);
+    for easing in [
+        Easing::Ease,
+        Easing::EaseIn,
+        Easing::EaseOut,
+        Easing::EaseInOut,
+    ] {
+        assert_eq!((easing.apply(0.0), easing.apply(1.0)), (0.0, 1.0));
+    }
+    assert!(Easing::EaseIn.apply(0.25) < 0.25);
+    assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-3);
+    assert_eq!(Easing::Steps(4).apply(0.3), 0.25);
+
+    let mut t = Tween::new(0.0f32, 100.0, ms(100)).delay(ms(50));
+    run(&mut t, ms(10), ms(40));
+    assert_eq!(t.value(), 0.0);
+    run(&mut t, ms(10), ms(60));
+    assert!((t.value() - 50.0).abs() < 1e-3, "{}", t.value());
+    assert!(!t.is_finished());
+    run(&mut t, ms(10), ms(60));
+    assert!(t.is_finished());
+    assert_eq!(t.value(), 100.0);
+
+    // Played back and forth three times, it ends where the third play ends.
+    let mut yoyo = Tween::new(Color::BLACK, Color::WHITE, ms(100))
+        .repeat(Repeat::Times(3))
+        .alternate();
+    run(&mut yoyo, ms(25), ms(150));
+    assert_eq!(yoyo.value(), Color::rgb(128, 128, 128));
+    run(&mut yoyo, ms(25), ms(50));
+    assert_eq!(yoyo.value(), Color::BLACK);
+    run(&mut yoyo, ms(25), ms(200));
+    assert!(yoyo.is_finished());
+    assert_eq!(yoyo.value(), Color::WHITE);
+
+    let mut forever =
+        Tween::new(Point::new(0.0, 0.0), Point::new(10.0, 20.0), ms(100)).repeat(Repeat::Forever);
+    run(&mut forever, ms(10), Duration::from_secs(10) + ms(50));
+    assert!(!forever.is_finished());
+    let p = forever.value();
+    assert!(
+        (p.x - 5.0).abs() < 1e-2 && (p.y - 10.0).abs() < 1e-2,
+        "{p:?}"
+    );
+}
+
+#[test]
+fn springs_settle_the_same_way_at_any_frame_rate() {
+    let spring = || Spring::new(0.0f32, 1.0);
+    let mut fast = spring();
+    let mut slow = spring();
+    run(&mut fast, ms(5), ms(300));
+    run(&mut slow, ms(30), ms(300));
+    assert_eq!(fast.value(), slow.value());
+    assert!(fast.value() > 0.5 && fast.value() < 1.0);
+    run(&mut fast, ms(16), Duration::from_secs(3));
+    assert!(fast.is_finished());
+    assert_eq!(fast.value(), 1.0);
+
+    // With little damping it shoots past its target before settling.
+    let mut bouncy = Spring::new(0.0f32, 100.0).stiffness(300.0).damping(5.0);
+    let mut peak = 0.0f32;
+    let mut ctx = UpdateContext::new(Instant::now());
+    while !bouncy.is_finished() {
+        ctx.advance(ms(16));
+        bouncy.update(&mut ctx);
+        peak = peak.max(bouncy.value());
+    }
+    assert!(peak > 120.0, "{peak}");
+    assert_eq!(bouncy.value(), 100.0);
+    assert!(ctx.frame > 60);
+
+    let halfway = AnimatedValue::Size(10.0, 20.0).lerp(&AnimatedValue::Size(30.0, 40.0), 0.5);
+    assert_eq!(halfway, AnimatedValue::Size(20.0, 30.0));
+}
+
+#[test]
+fn worlds_animate_node_properties_frame_by_frame() {
+    let finished = common::record::<AnimationFinished>();
+    let mut w = World {
+        root: obj!(Board {
+            children: list![Card {
+                children: list![Text { text: "Hello" }, Button { text: "Go" }],
+            }],
+        }),
+        ..default()
+    };
+    let card = w.layout().find(&[0]).unwrap().rect;
+    let button = w.layout().find(&[0, 1]).unwrap().rect;
+
+    // Slide the card in from the left and fade it in.
+    w.animate(
+        &[0],
+        AnimatedProperty::Offset,
+        Tween::new(
+            Point::new(-200.0, 0.0).into(),
+            Point::new(0.0, 0.0).into(),
+            ms(200),
+        ),
+    );
+    w.animate(
+        &[0],
+        AnimatedProperty::Opacity,
+        Tween::new(0.0.into(), 1.0.into(), ms(100)).easing(Easing::EaseOut),
+    );
+    assert_eq!(w.layout().find(&[0]).unwrap().rect.x, card.x - 200.0);
+    assert_eq!(w.opacity(&[0, 0]), 0.0);
+
+    assert!(w.step(ms(100)));
+    let moved = w.layout();
+    assert_eq!(moved.find(&[0]).unwrap().rect.x, card.x - 100.0);
+    // What is inside moves along, and is hit where it is drawn.
+    let inside = moved.find(&[0, 1]).unwrap().rect;
+    assert_eq!(inside.x, button.x - 100.0);
+    assert_eq!(w.opacity(&[0, 1]), 1.0);
+    assert_eq!(
+        finished.get(),
+        [AnimationFinished {
+            path: vec![0],
+            property: AnimatedProperty::Opacity,
+        }]
+    );
+
+    assert!(w.step(ms(100)));
+    assert_eq!(w.layout().find(&[0]).unwrap().rect, card);
+    assert_eq!(finished.get().len(), 2);
+    // Nothing runs any more; finished animations hold their last value.
+    assert!(!w.step(ms(100)));
+    assert_eq!(finished.get().len(), 2);
+
+    // Transitions start from the value drawn now.
+    let primary = Theme::light().palette.primary;
+    assert_eq!(w.box_style(&[0, 1]).unwrap().background, primary);
+    w.transition(
+        &[0, 1],
+        AnimatedProperty::Background,
+        Color::BLACK,
+        ms(100),
+        Easing::Linear,
+    );
+    w.step(ms(50));
+    assert_eq!(
+        w.box_style(&[0, 1]).unwrap().background,
+        primary.lerp(&Color::BLACK, 0.5)
+    );
+    w.transition(
+        &[0, 1],
+        AnimatedProperty::Background,
+        Color::WHITE,
+        ms(100),
+        Easing::Linear,
+    );
+    w.step(ms(100));
+    assert_eq!(w.box_style(&[0, 1]).unwrap().background, Color::WHITE);
+
+    // Text color and size.
+    w.animate(
+        &[0, 0],
+        AnimatedProperty::Foreground,
+        Spring::new(Color::BLACK.into(), Color::rgb(200, 0, 0).into()),
+    );
+    w.animate(
+        &[0, 1],
+        AnimatedProperty::Size,
+        Tween::new(
+            w.animated_value(&[0, 1], AnimatedProperty::Size),
+            AnimatedValue::Size(200.0, 50.0),
+            ms(10),
+        ),
+    );
+    w.step(Duration::from_secs(2));
+    assert_eq!(w.text_style_at(&[0, 0]).color, Some(Color::rgb(200, 0, 0)));
+    let grown = w.layout().find(&[0, 1]).unwrap().rect;
+    assert_eq!((grown.width, grown.height), (200.0, 50.0));
+
+    w.stop_animation(&[0, 1], AnimatedProperty::Size);
+    assert_eq!(w.layout().find(&[0, 1]).unwrap().rect, button);
+    assert_eq!(w.animations.clock().unwrap().frame, 6);
+}
+
+#[test]
+fn the_first_frame_is_frame_zero() {
+    let mut w = World::default();
+    assert!(!w.update());
+    let clock = w.animations.clock().unwrap().clone();
+    assert_eq!((clock.frame, clock.delta), (0, Duration::ZERO));
+    w.update();
+    assert_eq!(w.animations.clock().unwrap().frame, 1);
+
+    let mut w = World::default();
+    w.step(ms(10));
+    assert_eq!(w.animations.clock().unwrap().frame, 1);
+}
+
+#[test]
+fn animations_go_with_the_nodes_at_their_paths() {
+    let mut w = World {
+        root: obj!(Board {
+            children: list![Text { text: "One" }, Button { text: "Two" }],
+        }),
+        ..default()
+    };
+    let fade = || Tween::new(0.0.into(), 1.0.into(), ms(10));
+    w.animate(&[0], AnimatedProperty::Opacity, fade());
+    w.animate(&[1], AnimatedProperty::Opacity, fade());
+    w.step(ms(20));
+    assert!(w.animations.get(&[1], AnimatedProperty::Opacity).is_some());
+
+    // Another kind of node in its place, or none at all, drops it.
+    let Object::Board(board) = &mut w.root else {
+        unreachable!()
+    };
+    board.children.remove(0);
+    w.step(ms(10));
+    assert!(w.animations.get(&[0], AnimatedProperty::Opacity).is_none());
+    assert!(w.animations.get(&[1], AnimatedProperty::Opacity).is_none());
+    assert_eq!(w.opacity(&[0]), 1.0);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use snow_ui::Element;
+use snow_ui::prelude::*;
+
+#[element]
+#[derive(Clone)]
+struct CountButton {
+    button: Button,
+}
+
+static CLICKS: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+#[handler]
+impl ClickHandler for CountButton {
+    async fn on_click(&mut self) {
+        // Timers only work when the future is driven by a tokio runtime.
+        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        CLICKS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+#[message]
+struct Pressed {}
+
+#[element]
+#[derive(Clone)]
+struct PressCounter {
+    button: Button,
+}
+
+static PRESSES: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+#[handler]
+impl ClickHandler for PressCounter {
+    async fn on_click(&mut self) {
+        event_bus().send(Pressed {});
+    }
+}
+
+#[handler]
+impl MessageHandler<Pressed> for PressCounter {
+    async fn handle(&mut self, _: &Pressed, _: &mut MessageContext) {
+        PRESSES.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+fn button_of(obj: Object) -> Button {
+    match obj {
+        Object::Element(Element::Button(b)) => b,
+        other => panic!("expected a button, got {:?}", other),
+    }
+}
+
+/// A world showing just `obj`, and its button.
+fn world_of(obj: Object) -> (World, Button) {
+    let button = button_of(obj.clone());
+    let w = World {
+        root: obj,
+        ..default()
+    };
+    (w, button)
+}
+
+#[test]
+fn synthetic_click_runs_click_handler() {
+    let (mut w, button) = world_of(obj!(CountButton {
+        button: Button { text: "+1" },
+    }));
+    assert!(button.on_click.is_some());
+
+    // Clicks do not wait for their handler; `World::update` sees them finish.
+    button.click();
+    button.click();
+    assert!(button.pending.is_pending());
+    common::settle(&mut w);
+    assert_eq!(CLICKS.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[test]
+fn plain_button_click_is_a_no_op() {
+    let button = button_of(obj!(Button { text: "noop" }));
+    assert!(button.on_click.is_none());
+    button.click();
+}
+
+#[test]
+fn click_handlers_can_message_their_own_element() {
+    // The element handles the message sent by its own click handler.
+    let (mut w, button) = world_of(obj!(PressCounter {
+        button: Button { text: "Press" },
+    }));
+    button.click();
+    button.click();
+    common::settle(&mut w);
+    assert_eq!(PRESSES.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
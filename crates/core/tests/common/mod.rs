@@ -0,0 +1,55 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::time::{Duration, Instant};
+
+use snow_ui::Element;
+use snow_ui::prelude::*;
+
+struct Recorder<T>(State<Vec<T>>);
+
+impl<T: Message + Clone> MessageHandler<T> for Recorder<T> {
+    async fn handle(&mut self, msg: &T, _: &mut MessageContext) {
+        self.0.update(|v| v.push(msg.clone()));
+    }
+}
+
+/// Records every `T` sent on the event bus from now on.
+pub fn record<T: Message + Clone>() -> State<Vec<T>> {
+    let seen = State::new(vec![]);
+    event_bus().register_handler::<Recorder<T>, T>(std::sync::Arc::new(std::sync::Mutex::new(
+        Recorder(seen.clone()),
+    )));
+    seen
+}
+
+/// Run `World::update` until every form submission and button click started
+/// from the UI has finished.
+pub fn settle(w: &mut World) {
+    fn running(obj: &Object) -> bool {
+        match obj {
+            Object::Element(Element::Form(f)) if f.pending.is_pending() => true,
+            Object::Element(Element::Button(b)) if b.pending.is_pending() => true,
+            _ => obj.children().iter().any(running),
+        }
+    }
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while running(&w.root) {
+        assert!(
+            Instant::now() < deadline,
+            "submission or click did not finish"
+        );
+        w.update();
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+/// Deliver posted messages until `done` holds.
+pub fn wait_until(what: &str, mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out: {what}");
+        std::thread::sleep(Duration::from_millis(5));
+        event_bus().flush();
+    }
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{NodeMut, NodePath};
+
+// Board > Form > [Row > TextInput(user), Row > TextInput(pass)], submit, reset
+const USER: &[usize] = &[0, 0, 0];
+const PASS: &[usize] = &[0, 1, 0];
+const SUBMIT: &[usize] = &[0, 2];
+const RESET: &[usize] = &[0, 3];
+
+fn login_world(submits: &State<u32>) -> World {
+    let submits = submits.clone();
+    World {
+        root: obj!(Board {
+            children: list![Form {
+                submit_handler: std::sync::Arc::new(move |_: &Form| {
+                    let submits = submits.clone();
+                    async move { submits.update(|n| *n += 1) }
+                }),
+                submit_button: Button { text: "Login" },
+                reset_button: Button { text: "Reset" },
+                children: list![
+                    Row {
+                        children: list![TextInput { name: "user" }],
+                    },
+                    Row {
+                        children: list![TextInput {
+                            name: "pass",
+                            r#type: "password",
+                        }],
+                    },
+                ],
+            }],
+        }),
+        ..default()
+    }
+}
+
+fn tab(w: &mut World) -> Option<NodePath> {
+    w.dispatch_key(KeyEvent::new(Key::Tab));
+    w.focused().cloned()
+}
+
+fn shift_tab(w: &mut World) -> Option<NodePath> {
+    w.dispatch_key(KeyEvent::with_modifiers(Key::Tab, Modifiers::SHIFT));
+    w.focused().cloned()
+}
+
+#[test]
+fn tab_walks_inputs_and_buttons_in_tree_order() {
+    let mut w = login_world(&State::new(0));
+    assert_eq!(w.focused(), None);
+    assert_eq!(tab(&mut w).as_deref(), Some(USER));
+    assert_eq!(tab(&mut w).as_deref(), Some(PASS));
+    assert_eq!(tab(&mut w).as_deref(), Some(SUBMIT));
+    assert_eq!(tab(&mut w).as_deref(), Some(RESET));
+    assert_eq!(tab(&mut w).as_deref(), Some(USER));
+    assert_eq!(shift_tab(&mut w).as_deref(), Some(RESET));
+    assert_eq!(shift_tab(&mut w).as_deref(), Some(SUBMIT));
+}
+
+#[test]
+fn tab_index_reorders_and_negative_index_skips() {
+    let mut w = login_world(&State::new(0));
+    if let Some(NodeMut::Button(b)) = w.root.node_mut(SUBMIT) {
+        b.tab_index = 1;
+    }
+    if let Some(NodeMut::Button(b)) = w.root.node_mut(RESET) {
+        b.tab_index = -1;
+    }
+    assert_eq!(
+        w.tab_order(),
+        vec![SUBMIT.to_vec(), USER.to_vec(), PASS.to_vec()]
+    );
+
+    // Skipped by Tab, but can still be focused programmatically.
+    assert!(w.focus(RESET));
+    assert_eq!(w.focused().map(|p| p.as_slice()), Some(RESET));
+}
+
+#[test]
+fn programmatic_focus_rejects_non_focusable_nodes() {
+    let mut w = login_world(&State::new(0));
+    assert!(!w.focus(&[0, 0]));
+    assert!(w.focus(PASS));
+    assert!(!w.focus(&[9]));
+    assert_eq!(w.focused().map(|p| p.as_slice()), Some(PASS));
+    w.blur();
+    assert_eq!(w.focused(), None);
+}
+
+#[test]
+fn enter_in_a_form_field_submits_the_form() {
+    let submits = State::new(0);
+    let mut w = login_world(&submits);
+    assert!(!w.dispatch_key(KeyEvent::new(Key::Enter)));
+    w.focus(PASS);
+    assert!(w.dispatch_key(KeyEvent::new(Key::Enter)));
+    common::settle(&mut w);
+    assert_eq!(submits.get(), 1);
+}
+
+#[test]
+fn clicking_focuses_and_focus_changes_are_published() {
+    struct Recorder(State<Vec<FocusEvent>>);
+    impl MessageHandler<FocusEvent> for Recorder {
+        async fn handle(&mut self, msg: &FocusEvent, _: &mut MessageContext) {
+            self.0.update(|v| v.push(msg.clone()));
+        }
+    }
+    let seen = State::new(vec![]);
+    event_bus().register_handler::<Recorder, FocusEvent>(std::sync::Arc::new(
+        std::sync::Mutex::new(Recorder(seen.clone())),
+    ));
+
+    let mut w = login_world(&State::new(0));
+    let r = w.layout().find(PASS).unwrap().rect;
+    w.dispatch_pointer(PointerInput::Down(Point::new(r.x + 1.0, r.y + 1.0)));
+    assert_eq!(w.focused().map(|p| p.as_slice()), Some(PASS));
+    w.dispatch_pointer(PointerInput::Down(Point::new(1.0, 1.0)));
+    assert_eq!(w.focused(), None);
+
+    let mine: Vec<FocusEvent> = seen
+        .get()
+        .into_iter()
+        .filter(|e| matches!(e, FocusEvent::Focus(p) | FocusEvent::Blur(p) if p == PASS))
+        .collect();
+    assert_eq!(
+        mine,
+        vec![
+            FocusEvent::Focus(PASS.to_vec()),
+            FocusEvent::Blur(PASS.to_vec())
+        ]
+    );
+}
@@ -0,0 +1,242 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{Element, Node, NodeMut};
+
+// Board > Form > [TextInput(user), TextInput(note)], submit, reset
+const USER: &[usize] = &[0, 0];
+const SUBMIT: &[usize] = &[0, 2];
+const RESET: &[usize] = &[0, 3];
+
+fn world(name: &'static str, handler: std::sync::Arc<dyn snow_ui::form::SubmitHandler>) -> World {
+    let mut w = World {
+        root: obj!(Board {
+            children: list![Form {
+                name: name.into(),
+                submit_button: Button { text: "Save" },
+                reset_button: Button { text: "Reset" },
+                children: list![
+                    TextInput {
+                        name: "user",
+                        value: "preset".to_string(),
+                        rules: vec![Rule::required()],
+                    },
+                    TextInput { name: "note" },
+                ],
+            }],
+        }),
+        ..default()
+    };
+    if let Some(NodeMut::Object(Object::Element(Element::Form(f)))) = w.root.node_mut(&[0]) {
+        f.submit_handler = handler;
+    }
+    w
+}
+
+fn form(w: &World) -> &Form {
+    match w.root.node(&[0]) {
+        Some(Node::Object(Object::Element(Element::Form(f)))) => f,
+        _ => panic!("expected a form"),
+    }
+}
+
+fn user(w: &mut World) -> &mut TextInput {
+    match w.root.node_mut(USER) {
+        Some(NodeMut::Object(Object::Element(Element::TextInput(t)))) => t,
+        _ => panic!("expected a text input"),
+    }
+}
+
+fn click(w: &mut World, path: &[usize]) {
+    let r = w.layout().find(path).unwrap().rect;
+    let at = Point::new(r.x + 1.0, r.y + 1.0);
+    w.dispatch_pointer(PointerInput::Down(at));
+    w.dispatch_pointer(PointerInput::Up(at));
+}
+
+/// Click `path`, then let the submission it may start finish.
+fn click_and_settle(w: &mut World, path: &[usize]) {
+    click(w, path);
+    common::settle(w);
+}
+
+#[test]
+fn clicking_submit_runs_the_handler_while_submitting() {
+    // (submitting, button disabled) as seen by the handler.
+    let seen = State::new(vec![]);
+    let record = seen.clone();
+    let mut w = world(
+        "lifecycle",
+        std::sync::Arc::new(move |form: &Form| {
+            let observed = (form.submitting.get(), form.submit_button.disabled);
+            let record = record.clone();
+            async move { record.update(|v| v.push(observed)) }
+        }),
+    );
+    let submitting = form(&w).submitting.clone();
+
+    click_and_settle(&mut w, SUBMIT);
+    assert_eq!(seen.get(), vec![(true, true)]);
+    assert!(!submitting.get());
+    assert!(!form(&w).submit_button.disabled);
+
+    w.focus(SUBMIT);
+    w.dispatch_key(KeyEvent::new(Key::Char(' ')));
+    common::settle(&mut w);
+    assert_eq!(seen.get().len(), 2);
+}
+
+#[test]
+fn submitting_runs_in_the_background_across_frames() {
+    let runs = State::new(0);
+    let release = State::new(false);
+    let (count, wait) = (runs.clone(), release.clone());
+    let mut w = world(
+        "background",
+        std::sync::Arc::new(move |_: &Form| {
+            let (count, wait) = (count.clone(), wait.clone());
+            async move {
+                while !wait.get() {
+                    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                }
+                count.update(|n| *n += 1);
+            }
+        }),
+    );
+    let submitting = form(&w).submitting.clone();
+
+    click(&mut w, SUBMIT);
+    let disabled = |w: &World| match w.root.node(SUBMIT) {
+        Some(Node::Button(b)) => b.disabled,
+        _ => panic!("expected the submit button"),
+    };
+    for _ in 0..2 {
+        w.update();
+        assert!(submitting.get());
+        assert!(disabled(&w));
+    }
+    // Submitting again meanwhile does nothing.
+    w.focus(USER);
+    assert!(w.dispatch_key(KeyEvent::new(Key::Enter)));
+    w.update();
+    assert!(submitting.get());
+
+    release.set(true);
+    common::settle(&mut w);
+    assert_eq!(runs.get(), 1);
+    assert!(!submitting.get());
+    assert!(!disabled(&w));
+}
+
+#[test]
+fn handler_errors_are_kept_on_the_form_and_published() {
+    struct Recorder(State<Vec<String>>);
+    impl MessageHandler<FormSubmitFailed> for Recorder {
+        async fn handle(&mut self, msg: &FormSubmitFailed, _: &mut MessageContext) {
+            if msg.form == "failing" {
+                self.0.update(|v| v.push(msg.error.clone()));
+            }
+        }
+    }
+    let failures = State::new(vec![]);
+    event_bus().register_handler::<Recorder, FormSubmitFailed>(std::sync::Arc::new(
+        std::sync::Mutex::new(Recorder(failures.clone())),
+    ));
+
+    let mut w = world(
+        "failing",
+        std::sync::Arc::new(|_: &Form| async {
+            Err::<(), _>(anyhow::anyhow!("server unreachable").context("login failed"))
+        }),
+    );
+    let gap = |w: &World| {
+        let layout = w.layout();
+        layout.find(SUBMIT).unwrap().rect.y - layout.find(USER).unwrap().rect.y
+    };
+    let before = gap(&w);
+    w.focus(USER);
+    assert!(w.dispatch_key(KeyEvent::new(Key::Enter)));
+    common::settle(&mut w);
+    assert_eq!(
+        form(&w).error.as_deref(),
+        Some("login failed: server unreachable")
+    );
+    assert_eq!(failures.get(), vec!["login failed: server unreachable"]);
+    assert!(!form(&w).submit_button.disabled);
+    // The message is shown between the fields and the buttons.
+    assert_eq!(gap(&w), before + snow_ui::layout::LINE_HEIGHT);
+}
+
+#[test]
+fn handlers_may_fail_with_any_error_type_or_a_string() {
+    #[derive(Debug)]
+    struct LoginFailed(std::io::Error);
+    impl std::fmt::Display for LoginFailed {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("login failed")
+        }
+    }
+    impl std::error::Error for LoginFailed {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let error_of = |handler: std::sync::Arc<dyn snow_ui::form::SubmitHandler>| {
+        let mut w = world("any-error", handler);
+        click_and_settle(&mut w, SUBMIT);
+        form(&w).error.clone()
+    };
+    // The causes of an error are kept.
+    assert_eq!(
+        error_of(std::sync::Arc::new(|_: &Form| async {
+            Err::<(), _>(LoginFailed(std::io::Error::other("server unreachable")))
+        })),
+        Some("login failed: server unreachable".to_string())
+    );
+    assert_eq!(
+        error_of(std::sync::Arc::new(|_: &Form| async {
+            Err::<(), _>("user is locked".to_string())
+        })),
+        Some("user is locked".to_string())
+    );
+    assert_eq!(
+        error_of(std::sync::Arc::new(|_: &Form| async {
+            Err::<(), _>("user is locked")
+        })),
+        Some("user is locked".to_string())
+    );
+}
+
+#[test]
+fn reset_restores_initial_values_and_clears_errors() {
+    let mut w = world("resetting", std::sync::Arc::new(|_: &Form| async {}));
+    user(&mut w).set_value("");
+    click_and_settle(&mut w, SUBMIT);
+    assert!(!form(&w).is_valid());
+
+    click(&mut w, RESET);
+    assert_eq!(user(&mut w).value, "preset");
+    assert_eq!(user(&mut w).error, None);
+    assert!(form(&w).is_valid());
+}
+
+#[test]
+fn disabled_buttons_cannot_be_clicked_or_focused() {
+    let submits = State::new(0);
+    let counter = submits.clone();
+    let mut w = world(
+        "disabled",
+        std::sync::Arc::new(move |_: &Form| {
+            let counter = counter.clone();
+            async move { counter.update(|n| *n += 1) }
+        }),
+    );
+    if let Some(NodeMut::Button(b)) = w.root.node_mut(SUBMIT) {
+        b.disabled = true;
+    }
+    click_and_settle(&mut w, SUBMIT);
+    assert_eq!(submits.get(), 0);
+    assert!(!w.focus(SUBMIT));
+    assert!(!w.tab_order().contains(&SUBMIT.to_vec()));
+}
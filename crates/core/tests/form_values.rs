@@ -0,0 +1,116 @@
+use serde::Deserialize;
+use snow_ui::prelude::*;
+use snow_ui::{Element, NodeMut};
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+fn login_form() -> Object {
+    obj!(Form {
+        children: list![
+            Row {
+                children: list![TextInput {
+                    label: "User name: ",
+                    name: "username",
+                }],
+            },
+            TextInput {
+                name: "password",
+                r#type: "password",
+            },
+            TextInput { label: "Note" },
+            TextArea { name: "bio" },
+        ],
+    })
+}
+
+fn form(obj: &Object) -> &Form {
+    match obj {
+        Object::Element(Element::Form(f)) => f,
+        _ => panic!("expected a form"),
+    }
+}
+
+fn set(obj: &mut Object, path: &[usize], value: &str) {
+    match obj.node_mut(path) {
+        Some(NodeMut::Object(Object::Element(Element::TextInput(t)))) => t.set_value(value),
+        Some(NodeMut::Object(Object::Element(Element::TextArea(t)))) => t.set_value(value),
+        _ => panic!("expected a text field at {path:?}"),
+    }
+}
+
+#[test]
+fn values_are_keyed_by_name_and_properly_escaped() {
+    let mut obj = login_form();
+    set(&mut obj, &[0, 0], "ann \"the\" \\admin\\");
+    set(&mut obj, &[1], "秘密");
+    set(&mut obj, &[2], "unnamed");
+    set(&mut obj, &[3], "a\n\tb\u{1}");
+
+    let json = form(&obj).to_json().unwrap();
+    assert_eq!(
+        json,
+        r#"{"bio":"a\n\tb","password":"秘密","username":"ann \"the\" \\admin\\"}"#
+    );
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, form(&obj).values_json());
+}
+
+#[test]
+fn values_deserialize_into_a_struct() {
+    let mut obj = login_form();
+    set(&mut obj, &[0, 0], "ann");
+    set(&mut obj, &[1], "hunter2");
+    assert_eq!(
+        form(&obj).values::<LoginRequest>().unwrap(),
+        LoginRequest {
+            username: "ann".into(),
+            password: "hunter2".into(),
+        }
+    );
+}
+
+#[test]
+fn missing_and_mistyped_fields_are_reported_clearly() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Signup {
+        username: String,
+        age: u32,
+        email: String,
+    }
+
+    let mut obj = obj!(Form {
+        children: list![
+            TextInput { name: "username" },
+            TextInput {
+                name: "age",
+                r#type: "number",
+            },
+        ],
+    });
+    set(&mut obj, &[1], "old");
+    let err = form(&obj).values::<Signup>().unwrap_err().to_string();
+    assert!(err.contains("Signup"), "{err}");
+    assert!(err.contains("form field `age`"), "{err}");
+    assert!(err.contains("expected u32"), "{err}");
+
+    set(&mut obj, &[1], "42");
+    let err = form(&obj).values::<Signup>().unwrap_err().to_string();
+    assert!(err.contains("missing field `email`"), "{err}");
+}
+
+#[test]
+fn only_the_active_switch_child_contributes() {
+    let mut obj = obj!(Form {
+        children: list![Switch {
+            children: list![TextInput { name: "shown" }, TextInput { name: "hidden" },],
+        }],
+    });
+    set(&mut obj, &[0, 0], "a");
+    set(&mut obj, &[0, 1], "b");
+    assert_eq!(form(&obj).to_json().unwrap(), r#"{"shown":"a"}"#);
+}
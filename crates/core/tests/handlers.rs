@@ -0,0 +1,114 @@
+use snow_ui::prelude::*;
+
+#[message]
+struct Bump(u32);
+
+#[message]
+struct Reset;
+
+#[element]
+struct Counter {
+    count: State<u128>,
+}
+
+#[handler]
+impl MessageHandler<Bump> for Counter {
+    async fn handle(&mut self, msg: &Bump, _: &mut MessageContext) {
+        self.count.update(|c| *c += msg.0 as u128);
+    }
+}
+
+#[handler]
+impl Counter {
+    #[on(Reset)]
+    fn on_reset(&mut self, _: &Reset) {
+        self.count.set(0);
+    }
+}
+
+#[test]
+fn handler_attribute_registers_trait_impls_and_on_methods() {
+    let count = State::new(0);
+    let _: Object = obj!(Counter {
+        count: count.clone()
+    });
+
+    event_bus().send(Bump(3));
+    event_bus().send(Bump(4));
+    assert_eq!(count.get(), 7);
+
+    event_bus().send(Reset);
+    assert_eq!(count.get(), 0);
+}
+
+#[message]
+struct Tag(&'static str);
+
+struct Tagged<T> {
+    log: State<Vec<String>>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+#[handler(Tagged<u8>, Tagged<u16>)]
+impl<T: Send + Sync + 'static> Tagged<T> {
+    #[on(Tag)]
+    async fn on_tag(&mut self, msg: &Tag, _ctx: &mut MessageContext) {
+        let width = std::mem::size_of::<T>();
+        self.log.update(|l| l.push(format!("{}:{}", msg.0, width)));
+    }
+}
+
+#[test]
+fn generic_handlers_register_listed_instances() {
+    let log = State::new(Vec::new());
+    let narrow = std::sync::Arc::new(std::sync::Mutex::new(Tagged::<u8> {
+        log: log.clone(),
+        _marker: std::marker::PhantomData,
+    }));
+    let wide = std::sync::Arc::new(std::sync::Mutex::new(Tagged::<u16> {
+        log: log.clone(),
+        _marker: std::marker::PhantomData,
+    }));
+    assert!(has_registered_handlers::<Tagged<u8>>());
+    assert!(!has_registered_handlers::<Tagged<u32>>());
+
+    register_handlers_for_instance(&narrow);
+    register_handlers_for_instance(&wide);
+    event_bus().send(Tag("t"));
+    assert_eq!(log.get(), vec!["t:1".to_string(), "t:2".to_string()]);
+}
+
+#[message]
+struct Ping<T: Send + Sync + 'static>(T);
+
+struct Holder<T> {
+    log: State<Vec<String>>,
+    _marker: std::marker::PhantomData<T>,
+}
+
+#[handler(Holder<u32>, Holder<&'static str>)]
+impl<T: std::fmt::Debug + Send + Sync + 'static> MessageHandler<Ping<T>> for Holder<T> {
+    async fn handle(&mut self, msg: &Ping<T>, _: &mut MessageContext) {
+        self.log.update(|l| l.push(format!("{:?}", msg.0)));
+    }
+}
+
+#[test]
+fn generic_handlers_take_the_message_type_of_each_instance() {
+    let log = State::new(Vec::new());
+    let numbers = std::sync::Arc::new(std::sync::Mutex::new(Holder::<u32> {
+        log: log.clone(),
+        _marker: std::marker::PhantomData,
+    }));
+    let words = std::sync::Arc::new(std::sync::Mutex::new(Holder::<&'static str> {
+        log: log.clone(),
+        _marker: std::marker::PhantomData,
+    }));
+    register_handlers_for_instance(&numbers);
+    register_handlers_for_instance(&words);
+
+    // `Holder<u32>` handles `Ping<u32>` and `Holder<&str>` handles `Ping<&str>`.
+    event_bus().send(Ping(7u32));
+    event_bus().send(Ping("seven"));
+    assert_eq!(log.get(), vec!["7".to_string(), "\"seven\"".to_string()]);
+}
@@ -0,0 +1,130 @@
+use snow_ui::prelude::*;
+use snow_ui::{DisplayText, Element, Node, Underline};
+
+fn world() -> World {
+    World {
+        root: obj!(Board {
+            h_align: HAlign::Left,
+            v_align: VAlign::Top,
+            children: list![
+                TextInput {
+                    name: "ime_text",
+                    label: "Name",
+                },
+                TextInput {
+                    name: "ime_pass",
+                    r#type: "password",
+                },
+            ],
+        }),
+        ..default()
+    }
+}
+
+fn input(w: &World, path: &[usize]) -> TextInput {
+    match w.root.node(path) {
+        Some(Node::Object(Object::Element(Element::TextInput(t)))) => t.clone(),
+        _ => panic!("expected a text input"),
+    }
+}
+
+fn preedit(text: &str, cursor: Option<(usize, usize)>) -> ImeEvent {
+    ImeEvent::Preedit {
+        text: text.to_string(),
+        cursor,
+    }
+}
+
+#[test]
+fn preedit_is_shown_underlined_and_commit_inserts_it() {
+    let mut w = world();
+    w.focus(&[0]);
+    w.dispatch_key(KeyEvent::new(Key::Char('a')));
+    assert!(w.ime_allowed());
+
+    // "nihao" converted to 你好, with the first clause selected.
+    assert!(w.dispatch_ime(preedit("你好", Some((0, 3)))));
+    let t = input(&w, &[0]);
+    assert_eq!(t.value, "a");
+    assert_eq!(
+        t.display(),
+        DisplayText {
+            text: "a你好".to_string(),
+            caret: Some(4),
+            selection: 1..1,
+            underlines: vec![(1..7, Underline::Thin), (1..4, Underline::Thick)],
+        }
+    );
+
+    // Keys go to the input method while composing.
+    assert!(w.dispatch_key(KeyEvent::new(Key::Backspace)));
+    assert_eq!(input(&w, &[0]).display().text, "a你好");
+
+    w.dispatch_ime(ImeEvent::Commit("你好".to_string()));
+    let t = input(&w, &[0]);
+    assert_eq!(t.value, "a你好");
+    assert_eq!(t.composition, None);
+    assert_eq!(t.display().caret, Some(7));
+}
+
+#[test]
+fn cancel_and_empty_preedit_drop_the_composition() {
+    let mut w = world();
+    w.focus(&[0]);
+    w.dispatch_ime(preedit("に", Some((3, 3))));
+    w.dispatch_ime(ImeEvent::Cancel);
+    assert_eq!(input(&w, &[0]).display().text, "");
+    w.dispatch_ime(preedit("に", None));
+    w.dispatch_ime(preedit("", None));
+    assert_eq!(input(&w, &[0]).composition, None);
+    assert_eq!(input(&w, &[0]).value, "");
+}
+
+#[test]
+fn composition_is_committed_when_focus_moves() {
+    let mut w = world();
+    w.focus(&[0]);
+    w.dispatch_ime(preedit("日本", None));
+    w.dispatch_key(KeyEvent::new(Key::Tab));
+    assert_eq!(input(&w, &[0]).value, "日本");
+}
+
+#[test]
+fn commit_respects_max_len() {
+    let mut w = world();
+    if let Some(snow_ui::NodeMut::Object(Object::Element(Element::TextInput(t)))) =
+        w.root.node_mut(&[0])
+    {
+        t.max_len = 2;
+    }
+    w.focus(&[0]);
+    w.dispatch_ime(ImeEvent::Commit("東京都".to_string()));
+    assert_eq!(input(&w, &[0]).value, "東京");
+}
+
+#[test]
+fn caret_rect_follows_the_caret_and_active_clause() {
+    let mut w = world();
+    assert_eq!(w.ime_caret_rect(), None);
+    w.focus(&[0]);
+    let field = w.layout().find(&[0]).unwrap().rect;
+    let empty = w.ime_caret_rect().unwrap();
+    assert!(field.contains(Point::new(empty.x, empty.y)));
+    assert_eq!(empty.width, 1.0);
+
+    w.dispatch_ime(preedit("你好吗", Some((3, 9))));
+    let clause = w.ime_caret_rect().unwrap();
+    let width = |text: &str| snow_ui::measure_text(text, None).width;
+    assert_eq!(clause.x, empty.x + width("你"));
+    assert_eq!(clause.width, width("好吗"));
+    assert!(clause.width > 0.0);
+}
+
+#[test]
+fn password_fields_do_not_use_the_input_method() {
+    let mut w = world();
+    w.focus(&[1]);
+    assert!(!w.ime_allowed());
+    assert!(!w.dispatch_ime(ImeEvent::Commit("秘密".to_string())));
+    assert_eq!(input(&w, &[1]).value, "");
+}
@@ -0,0 +1,281 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{Element, Node, NodeMut};
+
+// Board > Form > [Checkbox, RadioGroup, Select, Slider, NumberInput], submit, reset
+const AGREE: &[usize] = &[0, 0];
+const SIZE: &[usize] = &[0, 1];
+const COLOR: &[usize] = &[0, 2];
+const VOLUME: &[usize] = &[0, 3];
+const COUNT: &[usize] = &[0, 4];
+const RESET: &[usize] = &[0, 6];
+
+fn world() -> World {
+    World {
+        root: obj!(Board {
+            children: list![Form {
+                submit_button: Button { text: "Order" },
+                reset_button: Button { text: "Reset" },
+                children: list![
+                    Checkbox {
+                        label: "I agree",
+                        name: "agree",
+                        rules: vec![Rule::required()],
+                    },
+                    RadioGroup {
+                        label: "Size",
+                        name: "size",
+                        options: vec!["S".into(), "M".into(), "L".into()],
+                        selected: Some(1),
+                    },
+                    Select {
+                        label: "Color",
+                        name: "color",
+                        options: vec![Choice::new("r", "Red"), Choice::new("g", "Green")],
+                    },
+                    Slider {
+                        label: "Volume",
+                        name: "volume",
+                        max: 10.0,
+                        value: 5.0,
+                    },
+                    NumberInput {
+                        label: "Count",
+                        name: "count",
+                        min: Some(1.0),
+                        max: Some(9.0),
+                    },
+                ],
+            }],
+        }),
+        ..default()
+    }
+}
+
+fn form(w: &World) -> &Form {
+    match w.root.node(&[0]) {
+        Some(Node::Object(Object::Element(Element::Form(f)))) => f,
+        _ => panic!("expected a form"),
+    }
+}
+
+fn element<'a>(w: &'a mut World, path: &[usize]) -> &'a mut Element {
+    match w.root.node_mut(path) {
+        Some(NodeMut::Object(Object::Element(e))) => e,
+        _ => panic!("expected an element at {path:?}"),
+    }
+}
+
+fn key(w: &mut World, key: Key) -> bool {
+    let handled = w.dispatch_key(KeyEvent::new(key));
+    common::settle(w);
+    handled
+}
+
+fn click_at(w: &mut World, at: Point) {
+    w.dispatch_pointer(PointerInput::Down(at));
+    w.dispatch_pointer(PointerInput::Up(at));
+}
+
+fn rect(w: &World, path: &[usize]) -> Rect {
+    w.layout().find(path).unwrap().rect
+}
+
+#[test]
+fn values_are_collected_and_reset() {
+    let mut w = world();
+    assert_eq!(
+        form(&w).values_json(),
+        serde_json::json!({
+            "agree": false,
+            "size": "M",
+            "color": null,
+            "volume": 5,
+            "count": null,
+        })
+    );
+
+    w.focus(AGREE);
+    assert!(key(&mut w, Key::Char(' ')));
+    w.focus(SIZE);
+    key(&mut w, Key::Down);
+    w.focus(COLOR);
+    key(&mut w, Key::Down);
+    w.focus(VOLUME);
+    key(&mut w, Key::PageUp);
+    w.focus(COUNT);
+    w.dispatch_key(KeyEvent::new(Key::Char('3')));
+    assert_eq!(
+        form(&w).values_json(),
+        serde_json::json!({
+            "agree": true,
+            "size": "L",
+            "color": "r",
+            "volume": 10,
+            "count": 3,
+        })
+    );
+
+    let r = rect(&w, RESET);
+    click_at(&mut w, Point::new(r.x + 1.0, r.y + 1.0));
+    assert_eq!(form(&w).values_json()["agree"], false);
+    assert_eq!(form(&w).values_json()["size"], "M");
+    assert_eq!(form(&w).values_json()["volume"], 5);
+    assert_eq!(form(&w).values_json()["count"], serde_json::Value::Null);
+}
+
+#[test]
+fn changes_are_published() {
+    struct Recorder(State<Vec<(SharedStr, serde_json::Value)>>);
+    impl MessageHandler<InputChanged> for Recorder {
+        async fn handle(&mut self, msg: &InputChanged, _: &mut MessageContext) {
+            if msg.path.starts_with(&[0]) && ["agree", "size"].contains(&msg.name.as_str()) {
+                self.0
+                    .update(|v| v.push((msg.name.clone(), msg.value.clone())));
+            }
+        }
+    }
+    let changes = State::new(vec![]);
+    event_bus().register_handler::<Recorder, InputChanged>(std::sync::Arc::new(
+        std::sync::Mutex::new(Recorder(changes.clone())),
+    ));
+
+    let mut w = world();
+    w.focus(SIZE);
+    key(&mut w, Key::Up);
+    key(&mut w, Key::Up);
+    // Wraps around.
+    key(&mut w, Key::Up);
+    let r = rect(&w, AGREE);
+    click_at(&mut w, Point::new(r.x + 1.0, r.y + 1.0));
+
+    let recorded: Vec<_> = changes
+        .get()
+        .into_iter()
+        .filter(|(name, _)| *name == "size" || *name == "agree")
+        .collect();
+    assert!(recorded.ends_with(&[
+        ("size".into(), "S".into()),
+        ("size".into(), "L".into()),
+        ("size".into(), "M".into()),
+        ("agree".into(), true.into()),
+    ]));
+    assert_eq!(w.focused(), Some(&AGREE.to_vec()));
+}
+
+#[test]
+fn select_opens_and_picks_with_keys_and_pointer() {
+    let mut w = world();
+    w.focus(COLOR);
+    let closed = rect(&w, COLOR).height;
+    assert!(key(&mut w, Key::Char(' ')));
+    let Element::Select(s) = element(&mut w, COLOR) else {
+        panic!()
+    };
+    assert!(s.open);
+    // The option list takes up room below the field.
+    assert_eq!(rect(&w, COLOR).height, closed * 3.0);
+
+    key(&mut w, Key::Down);
+    key(&mut w, Key::Enter);
+    let Element::Select(s) = element(&mut w, COLOR) else {
+        panic!()
+    };
+    assert!(!s.open);
+    assert_eq!(s.selected_choice().map(|c| c.label.as_str()), Some("Green"));
+    key(&mut w, Key::Char(' '));
+    key(&mut w, Key::Escape);
+    assert_eq!(form(&w).values_json()["color"], "g");
+
+    // Click to open, then click the first option.
+    let r = rect(&w, COLOR);
+    click_at(&mut w, Point::new(r.x + r.width - 2.0, r.y + 1.0));
+    // The board re-centres the taller form.
+    let r = rect(&w, COLOR);
+    let option = Point::new(
+        r.x + r.width - 2.0,
+        r.y + snow_ui::layout::CONTROL_HEIGHT + 1.0,
+    );
+    click_at(&mut w, option);
+    assert_eq!(form(&w).values_json()["color"], "r");
+    assert_eq!(rect(&w, COLOR).height, closed);
+}
+
+#[test]
+fn slider_follows_keys_and_drags() {
+    let mut w = world();
+    w.focus(VOLUME);
+    key(&mut w, Key::Right);
+    key(&mut w, Key::Right);
+    key(&mut w, Key::Left);
+    assert_eq!(form(&w).values_json()["volume"], 6);
+    key(&mut w, Key::Home);
+    assert_eq!(form(&w).values_json()["volume"], 0);
+
+    let r = rect(&w, VOLUME);
+    let track = r.x + r.width - snow_ui::layout::SLIDER_WIDTH;
+    let y = r.y + 1.0;
+    w.dispatch_pointer(PointerInput::Down(Point::new(track + 32.0, y)));
+    assert_eq!(form(&w).values_json()["volume"], 2);
+    // Dragging keeps going beyond the slider while the pointer is held.
+    w.dispatch_pointer(PointerInput::Move(Point::new(track + 1000.0, y + 500.0)));
+    assert_eq!(form(&w).values_json()["volume"], 10);
+    w.dispatch_pointer(PointerInput::Up(Point::new(track + 1000.0, y + 500.0)));
+    w.dispatch_pointer(PointerInput::Move(Point::new(track, y)));
+    assert_eq!(form(&w).values_json()["volume"], 10);
+}
+
+#[test]
+fn number_input_filters_steps_and_validates() {
+    let mut w = world();
+    w.focus(COUNT);
+    w.dispatch_key(KeyEvent::new(Key::Char('x')));
+    assert_eq!(form(&w).values_json()["count"], serde_json::Value::Null);
+    // Stepping an empty field starts from `min`, and stops at `max`.
+    key(&mut w, Key::Up);
+    assert_eq!(form(&w).values_json()["count"], 2);
+    for _ in 0..10 {
+        key(&mut w, Key::Up);
+    }
+    assert_eq!(form(&w).values_json()["count"], 9);
+    key(&mut w, Key::Down);
+
+    let Element::NumberInput(n) = element(&mut w, COUNT) else {
+        panic!()
+    };
+    assert_eq!(n.number(), Some(8.0));
+    n.set_value("1e");
+    assert_eq!(n.number(), None);
+
+    let Element::Checkbox(c) = element(&mut w, AGREE) else {
+        panic!()
+    };
+    c.checked = true;
+    key(&mut w, Key::Enter);
+    assert_eq!(
+        form(&w).errors(),
+        vec![("count".into(), "Enter a number".to_string())]
+    );
+    let Element::NumberInput(n) = element(&mut w, COUNT) else {
+        panic!()
+    };
+    n.set_value("12");
+    key(&mut w, Key::Enter);
+    assert_eq!(
+        form(&w).errors(),
+        vec![("count".into(), "Must be at most 9".to_string())]
+    );
+}
+
+#[test]
+fn required_checkbox_must_be_checked() {
+    let mut w = world();
+    w.focus(AGREE);
+    key(&mut w, Key::Enter);
+    assert_eq!(
+        form(&w).errors(),
+        vec![("agree".into(), "This field is required".to_string())]
+    );
+    assert_eq!(w.tab_order().len(), 7);
+}
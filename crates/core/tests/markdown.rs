@@ -0,0 +1,210 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{Element, Node, TextRole, measure_rich, parse_markdown};
+
+const NOTES: &str = "\
+# Release 2.0
+
+Adds **bold** ideas, *new* words, ~~old~~ bugs and `code`. See [the docs](https://example.com/docs).
+
+- first
+- second
+  1. nested
+  2. list
+
+```
+fn main() {}
+```
+
+> Quoted
+
+| Name | Value |
+|------|-------|
+| a    | 1     |
+| b    | 2     |
+";
+
+fn rich(obj: &Object) -> &RichText {
+    match obj {
+        Object::Element(Element::RichText(t)) => t,
+        other => panic!("expected rich text, got {other:?}"),
+    }
+}
+
+fn text(obj: &Object) -> &str {
+    match obj {
+        Object::Element(Element::Text(t)) => &t.text,
+        other => panic!("expected a text, got {other:?}"),
+    }
+}
+
+#[test]
+fn blocks_become_objects() {
+    let blocks = parse_markdown(NOTES);
+    assert_eq!(blocks.len(), 6, "{blocks:#?}");
+
+    let heading = rich(&blocks[0]);
+    assert_eq!(heading.text(), "Release 2.0");
+    assert_eq!(heading.style.role, Some(TextRole::Heading(1)));
+
+    // The list: rows of a marker and the item's blocks.
+    let Object::Column(list) = &blocks[2] else {
+        panic!("expected a list");
+    };
+    assert_eq!(list.children.len(), 2);
+    let Object::Row(second) = &list.children[1] else {
+        panic!("expected a list item");
+    };
+    assert_eq!(text(&second.children[0]), "•");
+    let Object::Column(body) = &second.children[1] else {
+        panic!("expected the item body");
+    };
+    assert_eq!(rich(&body.children[0]).text(), "second");
+    let Object::Column(nested) = &body.children[1] else {
+        panic!("expected a nested list");
+    };
+    let markers: Vec<&str> = nested
+        .children
+        .iter()
+        .map(|item| text(&item.children()[0]))
+        .collect();
+    assert_eq!(markers, ["1.", "2."]);
+
+    let Object::Card(code) = &blocks[3] else {
+        panic!("expected a code block");
+    };
+    let Object::Element(Element::Text(code)) = &code.children[0] else {
+        panic!("expected the code");
+    };
+    assert_eq!(code.text, "fn main() {}");
+    assert_eq!(code.style.family.as_deref(), Some("monospace"));
+    assert_eq!(code.style.wrap, Some(WrapMode::None));
+
+    let Object::Card(quote) = &blocks[4] else {
+        panic!("expected a block quote");
+    };
+    assert_eq!(quote.text_style.italic, Some(true));
+    assert_eq!(quote.text_style.role, Some(TextRole::Muted));
+    assert_eq!(rich(&quote.children[0]).text(), "Quoted");
+
+    // Tables are laid out column by column, with a bold header.
+    let Object::Row(table) = &blocks[5] else {
+        panic!("expected a table");
+    };
+    let columns: Vec<Vec<String>> = table
+        .children
+        .iter()
+        .map(|c| c.children().iter().map(|cell| rich(cell).text()).collect())
+        .collect();
+    assert_eq!(columns, [["Name", "a", "b"], ["Value", "1", "2"]]);
+    let header = rich(&table.children[0].children()[0]);
+    assert_eq!(header.style.weight, Some(FontWeight::BOLD));
+}
+
+#[test]
+fn inline_formatting_becomes_spans() {
+    let blocks = parse_markdown(NOTES);
+    let paragraph = rich(&blocks[1]);
+    let span = |text: &str| {
+        paragraph
+            .spans
+            .iter()
+            .find(|s| s.text == text)
+            .unwrap_or_else(|| panic!("no span {text:?} in {paragraph:?}"))
+    };
+    assert_eq!(span("bold").style.weight, Some(FontWeight::BOLD));
+    assert_eq!(span("new").style.italic, Some(true));
+    assert_eq!(span("old").style.strikethrough, Some(true));
+    assert_eq!(span("code").style.family.as_deref(), Some("monospace"));
+    let link = span("the docs");
+    assert_eq!(link.link.as_deref(), Some("https://example.com/docs"));
+    assert!(link.style.is_underlined());
+    assert_eq!(span(" words, ").style, TextStyle::default());
+    assert!(
+        paragraph
+            .text()
+            .starts_with("Adds bold ideas, new words, old bugs and code.")
+    );
+}
+
+#[test]
+fn markdown_inherits_styles_and_links_send_messages() {
+    let clicked = common::record::<LinkClicked>();
+    let mut w = World {
+        root: obj!(Board {
+            text_style: TextStyle {
+                color: Some(Color::rgb(20, 20, 20)),
+                ..default()
+            },
+            children: list![Markdown {
+                source: "Read [more](app://help) here.",
+            }],
+        }),
+        ..default()
+    };
+    let style = w.root.text_style_at(&[0, 0]);
+    assert_eq!(style.color, Some(Color::rgb(20, 20, 20)));
+
+    let Some(Node::Object(Object::Element(Element::RichText(t)))) = w.root.node(&[0, 0]) else {
+        panic!("expected the paragraph");
+    };
+    let layout = measure_rich(&t.spans, &style, Some(w.viewport.width));
+    let line = &layout.lines[0];
+    let g = line.glyphs.iter().find(|g| g.span == 1).unwrap();
+    let rect = w.layout().find(&[0, 0]).unwrap().rect;
+    let p = Point::new(
+        rect.x + g.x + g.width / 2.0,
+        rect.y + line.top + line.height / 2.0,
+    );
+    w.dispatch_pointer(PointerInput::Down(p));
+    assert!(w.dispatch_pointer(PointerInput::Up(p)));
+    assert_eq!(
+        clicked.get(),
+        [LinkClicked {
+            path: vec![0, 0],
+            target: "app://help".into(),
+        }]
+    );
+
+    let mut m = Markdown::new("one");
+    assert_eq!(m.children.len(), 1);
+    m.set_source("# two\n\nthree");
+    assert_eq!(m.children.len(), 2);
+    assert_eq!(rich(&m.children[0]).text(), "two");
+}
+
+#[test]
+fn headings_quotes_and_links_take_their_look_from_the_theme() {
+    let mut theme = Theme::dark();
+    theme.typography.headings[1] = 40.0;
+    let mut w = World {
+        root: obj!(Board {
+            children: list![Markdown {
+                source: "## Title\n\n> Quoted [link](app://x)",
+            }],
+        }),
+        theme: theme.clone(),
+        ..default()
+    };
+    let heading = w.text_style_at(&[0, 0]);
+    assert_eq!(heading.size, Some(40.0));
+    assert_eq!(heading.weight, Some(FontWeight::BOLD));
+    assert_eq!(heading.color, Some(theme.palette.text));
+    let quote = w.text_style_at(&[0, 1, 0]);
+    assert_eq!(quote.color, Some(theme.palette.muted));
+    assert_eq!(quote.italic, Some(true));
+
+    let Some(Node::Object(Object::Element(Element::RichText(t)))) = w.root.node(&[0, 1, 0]) else {
+        panic!("expected the quote's paragraph");
+    };
+    let color = |span: usize| quote.span_style(&t.spans[span]).color;
+    assert_eq!(color(0), Some(theme.palette.muted));
+    assert_eq!(color(1), Some(theme.palette.link));
+    let tall = w.layout().find(&[0, 0]).unwrap().rect.height;
+
+    // Switching theme restyles text already on screen.
+    w.theme = Theme::light();
+    assert_eq!(w.text_style_at(&[0, 0]).size, Some(24.0));
+    assert!(w.layout().find(&[0, 0]).unwrap().rect.height < tall);
+}
@@ -0,0 +1,147 @@
+use snow_ui::EventBus;
+use snow_ui::prelude::*;
+
+#[derive(Message, Debug, PartialEq)]
+enum AuthEvent {
+    LoggedIn { user: String },
+    LoggedOut,
+    Expired,
+}
+
+#[message]
+struct Ping(u32);
+
+#[message(name = "progress", priority = 5, coalesce)]
+struct Progress(u8);
+
+#[derive(Message)]
+#[message(name = "urgent", priority = 10)]
+struct Urgent;
+
+#[message(coalesce)]
+enum Session {
+    LoggedIn { user: String },
+    LoggedOut,
+}
+
+struct Recorder {
+    seen: State<Vec<String>>,
+}
+
+impl MessageHandler<AuthEvent> for Recorder {
+    async fn handle(&mut self, msg: &AuthEvent, _: &mut MessageContext) {
+        self.seen.update(|v| v.push(format!("{:?}", msg)));
+    }
+}
+
+impl MessageHandler<Progress> for Recorder {
+    async fn handle(&mut self, msg: &Progress, _: &mut MessageContext) {
+        self.seen.update(|v| v.push(format!("progress {}", msg.0)));
+    }
+}
+
+impl MessageHandler<Session> for Recorder {
+    async fn handle(&mut self, msg: &Session, _: &mut MessageContext) {
+        let entry = match msg {
+            Session::LoggedIn { user } => format!("in:{user}"),
+            Session::LoggedOut => "out".to_string(),
+        };
+        self.seen.update(|v| v.push(entry));
+    }
+}
+
+impl MessageHandler<Urgent> for Recorder {
+    async fn handle(&mut self, _: &Urgent, _: &mut MessageContext) {
+        self.seen.update(|v| v.push("urgent".to_string()));
+    }
+}
+
+fn recorder(bus: &EventBus) -> State<Vec<String>> {
+    let seen = State::new(Vec::new());
+    let r = std::sync::Arc::new(std::sync::Mutex::new(Recorder { seen: seen.clone() }));
+    bus.register_handler::<Recorder, AuthEvent>(r.clone());
+    bus.register_handler::<Recorder, Progress>(r.clone());
+    bus.register_handler::<Recorder, Session>(r.clone());
+    bus.register_handler::<Recorder, Urgent>(r);
+    seen
+}
+
+#[test]
+fn enum_and_tuple_messages_have_default_meta() {
+    let meta = AuthEvent::meta();
+    assert!(meta.name.ends_with("AuthEvent"));
+    assert_eq!(meta.priority, 0);
+    assert!(!meta.coalesce);
+    assert!(Ping::meta().name.ends_with("Ping"));
+    assert_eq!(Ping(3).0, 3);
+}
+
+#[test]
+fn message_attributes_set_meta() {
+    assert_eq!(
+        Progress::meta(),
+        MessageMeta {
+            name: "progress",
+            priority: 5,
+            coalesce: true
+        }
+    );
+    assert_eq!(Urgent::meta().name, "urgent");
+    assert_eq!(Urgent::meta().priority, 10);
+    assert!(!Urgent::meta().coalesce);
+}
+
+#[test]
+fn enum_messages_are_delivered_to_handlers() {
+    let bus = EventBus::new();
+    let seen = recorder(&bus);
+    bus.send(AuthEvent::LoggedIn {
+        user: "alice".into(),
+    });
+    bus.send(AuthEvent::Expired);
+    bus.send(AuthEvent::LoggedOut);
+    assert_eq!(
+        seen.get(),
+        vec![
+            "LoggedIn { user: \"alice\" }".to_string(),
+            "Expired".to_string(),
+            "LoggedOut".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn flush_orders_by_priority_and_coalesces() {
+    let bus = EventBus::new();
+    let seen = recorder(&bus);
+    bus.post(AuthEvent::Expired);
+    bus.post(Progress(10));
+    bus.post(Progress(50));
+    bus.post(Urgent);
+    assert_eq!(bus.pending_len(), 3);
+    assert!(format!("{:?}", bus).contains("progress"));
+
+    assert_eq!(bus.flush(), 3);
+    assert_eq!(
+        seen.get(),
+        vec![
+            "urgent".to_string(),
+            "progress 50".to_string(),
+            "Expired".to_string(),
+        ]
+    );
+    assert_eq!(bus.pending_len(), 0);
+}
+
+#[test]
+fn coalescing_keeps_one_message_per_variant() {
+    let bus = EventBus::new();
+    let seen = recorder(&bus);
+    bus.post(Session::LoggedIn { user: "ann".into() });
+    bus.post(Session::LoggedOut);
+    bus.post(Session::LoggedIn { user: "bob".into() });
+    assert_eq!(bus.pending_len(), 2);
+
+    bus.flush();
+    assert_eq!(seen.get(), vec!["in:bob".to_string(), "out".to_string()]);
+}
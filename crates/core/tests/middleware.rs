@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use snow_ui::prelude::*;
+use snow_ui::runtime::block_on;
+use snow_ui::{
+    CancelToken, Cancelled, Element, HttpError, Interceptor, Method, MockResponse, MockServer,
+    RefreshableToken, Request, Response, RetryPolicy, StaticToken,
+};
+
+fn quick_retries(max_retries: u32) -> RetryPolicy {
+    RetryPolicy {
+        max_retries,
+        base_delay: Duration::from_millis(1),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn interceptors_see_requests_and_responses() {
+    struct Tag;
+    impl Interceptor for Tag {
+        fn on_request(&self, request: &mut Request) -> anyhow::Result<()> {
+            request.set_header("X-Request-Id", "42");
+            Ok(())
+        }
+
+        fn on_response(&self, _: &Request, response: &mut Response) -> anyhow::Result<()> {
+            // Treat "soft" errors in a 200 body as failures.
+            if response.body.starts_with(b"error:") {
+                anyhow::bail!("server said {}", response.text()?);
+            }
+            response.body.extend_from_slice(b"!");
+            Ok(())
+        }
+    }
+
+    let server = MockServer::start();
+    server
+        .stub(Method::Get, "/ok", MockResponse::text(200, "hi"))
+        .stub(Method::Get, "/soft", MockResponse::text(200, "error: busy"));
+    let api = ServerApi::new(&server.url(""))
+        .interceptor(Tag)
+        .auth(StaticToken::new("secret"));
+
+    let resp = block_on(api.get("/ok").send()).unwrap();
+    assert_eq!(resp.text().unwrap(), "hi!");
+    let err = block_on(api.get("/soft").send()).unwrap_err();
+    assert_eq!(err.to_string(), "server said error: busy");
+
+    let request = &server.requests()[0];
+    assert_eq!(request.header("X-Request-Id"), Some("42"));
+    assert_eq!(request.header("Authorization"), Some("Bearer secret"));
+}
+
+#[test]
+fn rejected_tokens_are_refreshed_once() {
+    let server = MockServer::start();
+    server
+        .stub(Method::Get, "/me", MockResponse::new(401))
+        .stub(Method::Get, "/me", MockResponse::text(200, "ann"))
+        .stub(Method::Get, "/admin", MockResponse::new(401));
+    let refreshes = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = refreshes.clone();
+    let auth = RefreshableToken::new(move || {
+        let n = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+        async move { Ok(format!("token-{n}")) }
+    })
+    .with_token("expired");
+    let api = ServerApi::new(&server.url("")).auth(auth);
+
+    let resp = block_on(api.get("/me").send()).unwrap();
+    assert_eq!(resp.text().unwrap(), "ann");
+    let auth: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|r| r.header("Authorization").unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(auth, ["Bearer expired", "Bearer token-1"]);
+
+    // A request that stays unauthorized fails after a single refresh.
+    let err = block_on(api.get("/admin").send()).unwrap_err();
+    assert_eq!(err.downcast_ref::<HttpError>().map(|e| e.status), Some(401));
+    assert_eq!(refreshes.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[test]
+fn transient_failures_are_retried() {
+    let server = MockServer::start();
+    for status in [503, 502, 200] {
+        server.stub(Method::Get, "/flaky", MockResponse::new(status));
+    }
+    server
+        .stub(Method::Get, "/down", MockResponse::new(503))
+        .stub(Method::Post, "/down", MockResponse::new(503))
+        .stub(Method::Get, "/bad", MockResponse::new(400));
+    let api = ServerApi::new(&server.url("")).retry(quick_retries(2));
+    let count = |path: &str| server.requests().iter().filter(|r| r.path == path).count();
+
+    block_on(api.get("/flaky").send()).unwrap();
+    assert_eq!(count("/flaky"), 3);
+
+    let err = block_on(api.get("/down").send()).unwrap_err();
+    assert_eq!(err.downcast_ref::<HttpError>().map(|e| e.status), Some(503));
+    assert_eq!(count("/down"), 3);
+    // POST is not idempotent, and 400 is not transient.
+    block_on(api.post("/down").send()).unwrap_err();
+    block_on(api.get("/bad").send()).unwrap_err();
+    assert_eq!(count("/down"), 4);
+    assert_eq!(count("/bad"), 1);
+
+    // Connection errors are retried, then reported with their cause.
+    let closed = MockServer::start().url("/gone");
+    let err = block_on(api.get(&closed).timeout(Duration::from_secs(1)).send()).unwrap_err();
+    assert!(format!("{err:#}").contains("could not connect"));
+}
+
+#[test]
+fn retry_delays_back_off_with_jitter() {
+    let policy = RetryPolicy {
+        base_delay: Duration::from_millis(100),
+        max_delay: Duration::from_millis(500),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    };
+    let delays: Vec<_> = (0..4).map(|n| policy.delay(n).as_millis()).collect();
+    assert_eq!(delays, [100, 200, 400, 500]);
+
+    let jittered = RetryPolicy {
+        jitter: 0.5,
+        ..policy
+    };
+    for _ in 0..50 {
+        let d = jittered.delay(1);
+        assert!(d > Duration::from_millis(100) && d <= Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn cancelled_requests_stop_waiting() {
+    let server = MockServer::start();
+    server.stub(
+        Method::Get,
+        "/slow",
+        MockResponse::new(200).delay(Duration::from_secs(2)),
+    );
+    let api = ServerApi::new(&server.url(""));
+    let token = CancelToken::new();
+    let canceller = token.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        canceller.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let err = block_on(api.get("/slow").cancel_with(&token).send()).unwrap_err();
+    assert!(err.is::<Cancelled>());
+    assert!(started.elapsed() < Duration::from_secs(1));
+    // Later requests with the token are not sent at all.
+    block_on(api.get("/slow").cancel_with(&token).send()).unwrap_err();
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[test]
+fn hiding_a_form_cancels_its_requests() {
+    let mut switch = Switch {
+        children: list![
+            Form {
+                name: "login",
+                children: list![TextInput { name: "user" }],
+            },
+            Text { text: "done" },
+        ],
+        ..default()
+    };
+    let Object::Element(Element::Form(form)) = &switch.children[0] else {
+        panic!("expected a form");
+    };
+    let token = form.cancel.clone();
+
+    switch.switch_to(0);
+    assert!(!token.is_cancelled());
+    switch.switch_to(1);
+    assert!(token.is_cancelled());
+    // The form gets a fresh token for when it is shown again.
+    let Object::Element(Element::Form(form)) = &switch.children[0] else {
+        panic!("expected a form");
+    };
+    assert!(!form.cancel.is_cancelled());
+}
@@ -0,0 +1,265 @@
+mod common;
+
+use std::time::Duration;
+
+use snow_ui::prelude::*;
+use snow_ui::runtime::block_on;
+use snow_ui::{
+    CacheRefreshed, Method, MockResponse, MockServer, Outbox, OutboxChanged, Queued,
+    QueuedRequestFailed, ResponseCache, StaticToken, Timeout, Unreachable,
+};
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Note {
+    n: u32,
+}
+
+/// An empty directory for this test run.
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("snow-ui-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// The address of a server that has gone away.
+fn unreachable_addr() -> std::net::SocketAddr {
+    MockServer::start().addr()
+}
+
+#[test]
+fn offline_writes_are_queued_and_replayed_in_order() {
+    let dir = temp_dir("outbox");
+    let changes = common::record::<OutboxChanged>();
+    let failures = common::record::<QueuedRequestFailed>();
+    let addr = unreachable_addr();
+    let outbox = Outbox::open(&dir).unwrap();
+    let api = ServerApi::new(&format!("http://{addr}")).outbox(outbox.clone());
+
+    for n in 1..=3 {
+        let err = block_on(api.post("/notes").json(&Note { n }).send()).unwrap_err();
+        let queued = err.downcast_ref::<Queued>().expect("a Queued error");
+        assert_eq!(queued.pending, n as usize);
+    }
+    assert_eq!(outbox.pending.get(), 3);
+    // Reads are not queued.
+    let err = block_on(api.get("/notes").send()).unwrap_err();
+    assert!(!err.is::<Queued>());
+
+    // The queue outlives the app.
+    drop((api, outbox));
+    let outbox = Outbox::open(&dir).unwrap();
+    assert_eq!(outbox.pending.get(), 3);
+    let queued = outbox.requests().unwrap();
+    assert_eq!(queued[2].method, Method::Post);
+    assert_eq!(queued[2].body, br#"{"n":3}"#);
+
+    let server = MockServer::start_on(addr);
+    server
+        .stub(Method::Post, "/notes", MockResponse::new(201))
+        .stub(Method::Post, "/notes", MockResponse::text(422, "bad note"))
+        .stub(Method::Post, "/notes", MockResponse::new(201));
+    let api = ServerApi::new(&server.url("")).outbox(outbox.clone());
+    assert_eq!(block_on(api.replay_outbox()).unwrap(), 2);
+    assert_eq!(outbox.pending.get(), 0);
+    let sent: Vec<Note> = server
+        .requests()
+        .iter()
+        .map(|r| r.json().unwrap())
+        .collect();
+    assert_eq!(sent, [Note { n: 1 }, Note { n: 2 }, Note { n: 3 }]);
+    let failures = failures.get();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].error.contains("422"), "{}", failures[0].error);
+    assert_eq!(
+        changes.get(),
+        [1, 2, 3, 2, 1, 0].map(|pending| OutboxChanged { pending })
+    );
+
+    // Any request that reaches the server replays the queue.
+    drop(server);
+    block_on(api.post("/notes").json(&Note { n: 4 }).send()).unwrap_err();
+    assert_eq!(outbox.pending.get(), 1);
+    let server = MockServer::start_on(addr);
+    server
+        .stub(Method::Get, "/ping", MockResponse::new(204))
+        .stub(Method::Post, "/notes", MockResponse::new(201));
+    block_on(api.get("/ping").send()).unwrap();
+    common::wait_until("the outbox is replayed", || outbox.pending.get() == 0);
+    assert_eq!(server.requests()[1].json::<Note>().unwrap(), Note { n: 4 });
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn writes_that_may_have_reached_the_server_are_not_queued() {
+    let dir = temp_dir("outbox-timeout");
+    let outbox = Outbox::open(&dir).unwrap();
+    let server = MockServer::start();
+    server.stub(
+        Method::Post,
+        "/notes",
+        MockResponse::new(201).delay(Duration::from_millis(200)),
+    );
+    let api = ServerApi::new(&server.url("")).outbox(outbox.clone());
+
+    let err = block_on(
+        api.post("/notes")
+            .json(&Note { n: 1 })
+            .timeout(Duration::from_millis(50))
+            .send(),
+    )
+    .unwrap_err();
+    assert!(err.is::<Timeout>(), "{err:#}");
+    assert!(!err.is::<Unreachable>() && !err.is::<Queued>(), "{err:#}");
+    assert_eq!(outbox.pending.get(), 0);
+
+    let addr = server.addr();
+    drop(server);
+    let err = block_on(api.post("/notes").json(&Note { n: 2 }).send()).unwrap_err();
+    assert!(err.is::<Queued>(), "{err:#}");
+    let api = ServerApi::new(&format!("http://{addr}"));
+    let err = block_on(api.post("/notes").send()).unwrap_err();
+    assert!(err.is::<Unreachable>(), "{err:#}");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cached_responses_are_kept_per_credentials_and_vary_headers() {
+    let server = MockServer::start();
+    let cached = |body: &str| {
+        MockResponse::text(200, body)
+            .header("Cache-Control", "private, max-age=60")
+            .header("Vary", "Accept-Language")
+    };
+    server
+        .stub(Method::Get, "/me", cached("alice"))
+        .stub(Method::Get, "/me", cached("bob"))
+        .stub(Method::Get, "/me", cached("alice, in French"));
+    let cache = ResponseCache::new();
+    let api = |token: &str| {
+        ServerApi::new(&server.url(""))
+            .auth(StaticToken::new(token))
+            .cache(cache.clone())
+    };
+    let get = |api: &ServerApi, language: &str| {
+        block_on(api.get("/me").header("Accept-Language", language).send())
+            .unwrap()
+            .text()
+            .unwrap()
+    };
+    let (alice, bob) = (api("alice-token"), api("bob-token"));
+
+    assert_eq!(get(&alice, "en"), "alice");
+    assert_eq!(get(&bob, "en"), "bob");
+    assert_eq!(get(&alice, "en"), "alice");
+    assert_eq!(get(&bob, "en"), "bob");
+    assert_eq!(server.requests().len(), 2);
+    assert_eq!(get(&alice, "fr"), "alice, in French");
+    assert_eq!(server.requests().len(), 3);
+
+    cache.remove(&server.url("/me"));
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn fresh_responses_come_from_the_cache() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/fresh",
+            MockResponse::text(200, "one").header("Cache-Control", "public, max-age=60"),
+        )
+        .stub(Method::Get, "/fresh", MockResponse::text(200, "two"))
+        .stub(
+            Method::Get,
+            "/private",
+            MockResponse::text(200, "secret").header("Cache-Control", "no-store, max-age=60"),
+        );
+    let cache = ResponseCache::new();
+    let api = ServerApi::new(&server.url("")).cache(cache.clone());
+    let get = |path: &str| block_on(api.get(path).send()).unwrap().text().unwrap();
+    let count = |path: &str| server.requests().iter().filter(|r| r.path == path).count();
+
+    assert_eq!(get("/fresh"), "one");
+    assert_eq!(get("/fresh"), "one");
+    assert_eq!(count("/fresh"), 1);
+    get("/private");
+    get("/private");
+    assert_eq!(count("/private"), 2);
+    assert_eq!(cache.len(), 1);
+
+    cache.clear();
+    assert_eq!(get("/fresh"), "two");
+}
+
+#[test]
+fn stale_responses_are_revalidated() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/doc",
+            MockResponse::text(200, "v1")
+                .header("ETag", "\"1\"")
+                .header("Cache-Control", "no-cache"),
+        )
+        .stub(Method::Get, "/doc", MockResponse::new(304))
+        .stub(
+            Method::Get,
+            "/doc",
+            MockResponse::text(200, "v2")
+                .header("ETag", "\"2\"")
+                .header("Cache-Control", "no-cache"),
+        );
+    let api = ServerApi::new(&server.url("")).cache(ResponseCache::new());
+    let get = || block_on(api.get("/doc").send()).map(|r| r.text().unwrap());
+
+    assert_eq!(get().unwrap(), "v1");
+    assert_eq!(get().unwrap(), "v1");
+    assert_eq!(get().unwrap(), "v2");
+    let tags: Vec<_> = server
+        .requests()
+        .iter()
+        .map(|r| r.header("If-None-Match").map(str::to_string))
+        .collect();
+    assert_eq!(tags, [None, Some("\"1\"".into()), Some("\"1\"".into())]);
+
+    // Offline, the last response is better than nothing.
+    drop(server);
+    assert_eq!(get().unwrap(), "v2");
+    block_on(api.get("/other").send()).unwrap_err();
+}
+
+#[test]
+fn stale_while_revalidate_answers_at_once_and_refreshes_in_the_background() {
+    let server = MockServer::start();
+    let cache_control = "max-age=0, stale-while-revalidate=60";
+    server
+        .stub(
+            Method::Get,
+            "/feed",
+            MockResponse::text(200, "old").header("Cache-Control", cache_control),
+        )
+        .stub(
+            Method::Get,
+            "/feed",
+            MockResponse::text(200, "new")
+                .header("Cache-Control", cache_control)
+                .delay(Duration::from_millis(100)),
+        );
+    let refreshed = common::record::<CacheRefreshed>();
+    let api = ServerApi::new(&server.url("")).cache(ResponseCache::new());
+    let get = || block_on(api.get("/feed").send()).unwrap().text().unwrap();
+
+    assert_eq!(get(), "old");
+    let started = std::time::Instant::now();
+    assert_eq!(get(), "old");
+    assert!(started.elapsed() < Duration::from_millis(100));
+    let url = server.url("/feed");
+    common::wait_until("the cache is refreshed", || {
+        refreshed
+            .get()
+            .contains(&CacheRefreshed { url: url.clone() })
+    });
+    assert_eq!(get(), "new");
+}
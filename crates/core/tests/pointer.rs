@@ -0,0 +1,195 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{ClickCallback, NodeMut};
+
+fn logger(log: &State<Vec<String>>, name: &'static str) -> Option<PointerHandler> {
+    let log = log.clone();
+    Some(PointerHandler::new(move |e: &mut PointerEvent| {
+        log.update(|l| l.push(format!("{}:{:?}:{:?}", name, e.kind, e.phase)));
+    }))
+}
+
+fn center(world: &World, path: &[usize]) -> Point {
+    let r = world.layout().find(path).expect("node is laid out").rect;
+    Point::new(r.x + r.width / 2.0, r.y + r.height / 2.0)
+}
+
+// Board > Card > Row > [Text, Button]
+fn world(log: &State<Vec<String>>, clicks: &State<u32>) -> World {
+    let clicks = clicks.clone();
+    World {
+        root: obj!(Board {
+            on_pointer: logger(log, "board"),
+            children: list![Card {
+                on_pointer: logger(log, "card"),
+                children: list![Row {
+                    on_pointer: logger(log, "row"),
+                    children: list![
+                        Text { text: "label" },
+                        Button {
+                            text: "OK",
+                            on_pointer: logger(log, "button"),
+                            on_click: Some(ClickCallback::new(move || {
+                                let clicks = clicks.clone();
+                                Box::pin(async move { clicks.update(|c| *c += 1) })
+                            })),
+                        },
+                    ],
+                }],
+            }],
+        }),
+        ..default()
+    }
+}
+
+const BUTTON: &[usize] = &[0, 0, 1];
+const TEXT: &[usize] = &[0, 0, 0];
+
+#[test]
+fn layout_centers_board_content_and_hit_test_finds_deepest_node() {
+    let log = State::new(vec![]);
+    let w = world(&log, &State::new(0));
+    let layout = w.layout();
+    assert_eq!(layout.rect, w.viewport);
+
+    let card = layout.find(&[0]).unwrap().rect;
+    assert!((card.x + card.width / 2.0 - 400.0).abs() < 0.01);
+    assert!((card.y + card.height / 2.0 - 300.0).abs() < 0.01);
+
+    let text = layout.find(TEXT).unwrap().rect;
+    let button = layout.find(BUTTON).unwrap().rect;
+    assert!(button.x >= text.x + text.width);
+
+    let chain = layout.hit_test(center(&w, BUTTON));
+    assert_eq!(chain, vec![vec![], vec![0], vec![0, 0], BUTTON.to_vec()]);
+    assert!(layout.hit_test(Point::new(-1.0, 0.0)).is_empty());
+}
+
+#[test]
+fn events_capture_then_bubble_and_click_buttons() {
+    let log = State::new(vec![]);
+    let clicks = State::new(0);
+    let mut w = world(&log, &clicks);
+    let p = center(&w, BUTTON);
+
+    assert!(w.dispatch_pointer(PointerInput::Down(p)));
+    let down: Vec<String> = log
+        .get()
+        .into_iter()
+        .filter(|l| l.contains("Down"))
+        .collect();
+    assert_eq!(
+        down,
+        vec![
+            "board:Down:Capture",
+            "card:Down:Capture",
+            "row:Down:Capture",
+            "button:Down:Target",
+            "row:Down:Bubble",
+            "card:Down:Bubble",
+            "board:Down:Bubble",
+        ]
+    );
+
+    w.dispatch_pointer(PointerInput::Up(p));
+    common::settle(&mut w);
+    assert_eq!(clicks.get(), 1);
+
+    // Pressing the button and releasing elsewhere is not a click.
+    w.dispatch_pointer(PointerInput::Down(p));
+    w.dispatch_pointer(PointerInput::Up(Point::new(1.0, 1.0)));
+    common::settle(&mut w);
+    assert_eq!(clicks.get(), 1);
+}
+
+#[test]
+fn deepest_node_with_a_handler_is_the_target() {
+    let log = State::new(vec![]);
+    let mut w = world(&log, &State::new(0));
+    w.dispatch_pointer(PointerInput::Wheel {
+        position: center(&w, TEXT),
+        delta_x: 0.0,
+        delta_y: -3.0,
+    });
+    let wheel: Vec<String> = log
+        .get()
+        .into_iter()
+        .filter(|l| l.contains("Wheel"))
+        .collect();
+    assert_eq!(wheel.len(), 5);
+    assert!(wheel[2].starts_with("row:Wheel") && wheel[2].ends_with("Target"));
+}
+
+#[test]
+fn stop_propagation_ends_the_route() {
+    let log = State::new(vec![]);
+    let mut w = world(&log, &State::new(0));
+    if let Some(NodeMut::Object(Object::Card(card))) = w.root.node_mut(&[0]) {
+        let log = log.clone();
+        card.on_pointer = Some(PointerHandler::new(move |e: &mut PointerEvent| {
+            log.update(|l| l.push(format!("card:{:?}", e.phase)));
+            if e.phase == EventPhase::Capture {
+                e.stop_propagation();
+            }
+        }));
+    }
+    let p = center(&w, BUTTON);
+    w.dispatch_pointer(PointerInput::Down(p));
+    let down: Vec<String> = log
+        .get()
+        .into_iter()
+        .filter(|l| l.contains("Down") || l == "card:Capture")
+        .collect();
+    assert_eq!(down, vec!["board:Down:Capture", "card:Capture"]);
+}
+
+#[test]
+fn moving_sends_enter_and_leave() {
+    let log = State::new(vec![]);
+    let mut w = world(&log, &State::new(0));
+    w.dispatch_pointer(PointerInput::Move(center(&w, BUTTON)));
+    assert_eq!(w.pointer.hovered().len(), 4);
+    let entered: Vec<String> = log
+        .get()
+        .into_iter()
+        .filter(|l| l.contains("Enter"))
+        .collect();
+    assert_eq!(entered.len(), 4);
+    assert!(entered[0].starts_with("board") && entered[3].starts_with("button"));
+
+    log.set(vec![]);
+    w.dispatch_pointer(PointerInput::Move(center(&w, TEXT)));
+    let changes: Vec<String> = log
+        .get()
+        .into_iter()
+        .filter(|l| l.contains("Enter") || l.contains("Leave"))
+        .collect();
+    assert_eq!(changes, vec!["button:Leave:Target"]);
+}
+
+#[test]
+fn captured_pointer_keeps_its_target() {
+    let log = State::new(vec![]);
+    let mut w = world(&log, &State::new(0));
+    if let Some(NodeMut::Object(Object::Element(snow_ui::Element::Button(b)))) =
+        w.root.node_mut(BUTTON)
+    {
+        let log = log.clone();
+        b.on_pointer = Some(PointerHandler::new(move |e: &mut PointerEvent| {
+            log.update(|l| l.push(format!("button:{:?}", e.kind)));
+            if e.kind == PointerEventKind::Down {
+                e.capture_pointer();
+            }
+        }));
+    }
+    w.dispatch_pointer(PointerInput::Down(center(&w, BUTTON)));
+    assert_eq!(w.pointer.captured(), Some(&BUTTON.to_vec()));
+
+    log.set(vec![]);
+    w.dispatch_pointer(PointerInput::Move(Point::new(5.0, 5.0)));
+    assert!(log.get().contains(&"button:Move".to_string()));
+
+    w.dispatch_pointer(PointerInput::Up(Point::new(5.0, 5.0)));
+    assert_eq!(w.pointer.captured(), None);
+}
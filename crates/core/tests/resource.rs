@@ -0,0 +1,203 @@
+mod common;
+
+use std::time::Duration;
+
+use snow_ui::prelude::*;
+use snow_ui::{
+    CancelToken, Element, Method, MockResponse, MockServer, Refetch, Resource, ResourceState,
+};
+
+#[derive(Debug, PartialEq, serde::Deserialize)]
+struct User {
+    name: String,
+}
+
+fn world(child: impl Into<Object>) -> World {
+    World {
+        root: Board {
+            children: vec![child.into()],
+            ..default()
+        }
+        .into(),
+        ..default()
+    }
+}
+
+/// The text shown by the first query in the tree.
+fn shown(obj: &Object) -> Option<&str> {
+    match obj {
+        Object::Element(Element::Query(q)) => match &q.children[..] {
+            [Object::Element(Element::Text(t))] => Some(t.text.as_str()),
+            other => panic!("expected a text, got {other:?}"),
+        },
+        _ => obj.children().iter().find_map(shown),
+    }
+}
+
+fn user_resource(api: &ServerApi, name: &'static str) -> Resource<User> {
+    Resource::get(name, api, "/me")
+        .loading(Text {
+            text: "spinner".into(),
+            ..default()
+        })
+        .error(|_| {
+            Text {
+                text: "oops".into(),
+                ..default()
+            }
+            .into()
+        })
+        .ready(|user: &User| {
+            Text {
+                text: user.name.clone().into(),
+                ..default()
+            }
+            .into()
+        })
+}
+
+#[test]
+fn resources_show_loading_then_data_or_error() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/me",
+            MockResponse::text(200, r#"{"name": "Ada"}"#).delay(Duration::from_millis(50)),
+        )
+        .stub(Method::Get, "/me", MockResponse::text(500, "down"));
+    let api = ServerApi::new(&server.url(""));
+    let resource = user_resource(&api, "me");
+    let state = resource.state.clone();
+    let mut world = world(resource);
+
+    // Nothing is fetched until the resource is shown.
+    assert_eq!(state.get(), ResourceState::Idle);
+    assert_eq!(shown(&world.root), Some("spinner"));
+    assert!(world.update());
+    assert!(state.get().is_loading());
+    assert!(!world.update());
+
+    common::wait_until("the user is loaded", || !state.get().is_loading());
+    assert_eq!(state.get().data(), Some(&User { name: "Ada".into() }));
+    assert!(world.update());
+    assert_eq!(shown(&world.root), Some("Ada"));
+    assert_eq!(server.requests().len(), 1);
+
+    event_bus().send(Refetch { resource: "me" });
+    assert!(world.update());
+    assert_eq!(shown(&world.root), Some("spinner"));
+    common::wait_until("the refetch fails", || state.get().error().is_some());
+    assert!(state.get().error().unwrap().contains("500"));
+    world.update();
+    assert_eq!(shown(&world.root), Some("oops"));
+
+    // Other names are left alone.
+    event_bus().send(Refetch { resource: "not me" });
+    assert!(!world.update());
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn hidden_resources_cancel_their_fetch_and_restart_when_shown() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/me",
+            MockResponse::text(200, r#"{"name": "Ada"}"#).delay(Duration::from_secs(2)),
+        )
+        .stub(
+            Method::Get,
+            "/me",
+            MockResponse::text(200, r#"{"name": "Bob"}"#),
+        );
+    let api = ServerApi::new(&server.url(""));
+    let resource = user_resource(&api, "switched");
+    let state = resource.state.clone();
+    let mut world = world(Switch {
+        children: vec![
+            Text {
+                text: "home".into(),
+                ..default()
+            }
+            .into(),
+            resource.into(),
+        ],
+        active: 1,
+    });
+
+    world.update();
+    common::wait_until("the fetch is sent", || server.requests().len() == 1);
+    let Object::Element(Element::Switch(switch)) = &mut world.root.children_mut()[0] else {
+        unreachable!()
+    };
+    switch.switch_to(0);
+    assert_eq!(state.get(), ResourceState::Idle);
+    // Hidden resources are not updated.
+    assert!(!world.update());
+    assert_eq!(server.requests().len(), 1);
+
+    let Object::Element(Element::Switch(switch)) = &mut world.root.children_mut()[0] else {
+        unreachable!()
+    };
+    switch.switch_to(1);
+    world.update();
+    common::wait_until("the user is loaded", || state.get().data().is_some());
+    assert_eq!(state.get().data().unwrap().name, "Bob");
+    world.update();
+    assert_eq!(shown(&world.root), Some("Bob"));
+}
+
+#[test]
+fn dropped_resources_cancel_their_fetch() {
+    let token = State::new(None::<CancelToken>);
+    let seen = token.clone();
+    let resource = Resource::new("dropped", move |cancel| {
+        seen.set(Some(cancel));
+        std::future::pending::<anyhow::Result<()>>()
+    });
+    let mut world = world(resource);
+    world.update();
+    common::wait_until("the fetch starts", || token.get().is_some());
+    assert!(!token.get().unwrap().is_cancelled());
+    drop(world);
+    assert!(token.get().unwrap().is_cancelled());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn resources_share_one_background_thread() {
+    let threads = |name: &str| {
+        std::fs::read_dir("/proc/self/task")
+            .unwrap()
+            .filter(|t| {
+                let comm = t.as_ref().unwrap().path().join("comm");
+                std::fs::read_to_string(comm).is_ok_and(|c| name.starts_with(c.trim()))
+            })
+            .count()
+    };
+    let server = MockServer::start();
+    server.stub(
+        Method::Get,
+        "/me",
+        MockResponse::text(200, r#"{"name": "Ada"}"#).delay(Duration::from_millis(50)),
+    );
+    let api = ServerApi::new(&server.url(""));
+    let resources: Vec<_> = (0..8).map(|_| user_resource(&api, "many")).collect();
+    let states: Vec<_> = resources.iter().map(|r| r.state.clone()).collect();
+    let mut world = World {
+        root: Board {
+            children: resources.into_iter().map(Into::into).collect(),
+            ..default()
+        }
+        .into(),
+        ..default()
+    };
+    world.update();
+    assert!(states.iter().all(|s| s.get().is_loading()));
+    assert_eq!(threads("snow-ui-background"), 1);
+    common::wait_until("every user is loaded", || {
+        states.iter().all(|s| s.get().data().is_some())
+    });
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use snow_ui::layout::LINE_HEIGHT;
+use snow_ui::prelude::*;
+use snow_ui::{Node, measure_rich, measure_text};
+
+fn sentence() -> Vec<TextSpan> {
+    vec![
+        "Click ".into(),
+        TextSpan::new("here").bold().underline().link("app://next"),
+        " to continue. ".into(),
+        TextSpan::new("Unsaved changes are lost!").color(Color::rgb(200, 0, 0)),
+    ]
+}
+
+#[test]
+fn spans_are_shaped_as_one_text() {
+    let spans = sentence();
+    let text: String = spans.iter().map(|s| s.text.as_str()).collect();
+    let rich = measure_rich(&spans, &TextStyle::default(), None);
+    let plain = measure_text(&text, None);
+    assert_eq!(rich.lines.len(), 1);
+    // Only the bold span is wider than plain text.
+    assert!(rich.width > plain.width);
+    assert!(rich.width - plain.width < measure_text("here", None).width);
+
+    let glyphs = &rich.lines[0].glyphs;
+    assert_eq!(glyphs.len(), text.chars().count());
+    for g in glyphs {
+        let span = match g.range.start {
+            0..6 => 0,
+            6..10 => 1,
+            10..24 => 2,
+            _ => 3,
+        };
+        assert_eq!(g.span, span, "{g:?}");
+    }
+
+    // Lines break across span boundaries, and ranges are into the whole text.
+    let wrapped = measure_rich(&spans, &TextStyle::default(), Some(120.0));
+    assert!(wrapped.lines.len() > 2);
+    assert_eq!(wrapped.lines.first().unwrap().range.start, 0);
+    assert_eq!(wrapped.lines.last().unwrap().range.end, text.len());
+    assert!(wrapped.lines.iter().all(|l| l.width <= 120.0));
+
+    let clamped = TextStyle {
+        max_lines: Some(2),
+        ..default()
+    };
+    let cut = measure_rich(&spans, &clamped, Some(120.0));
+    assert!(cut.truncated);
+    assert_eq!(cut.lines.len(), 2);
+    let ellipsis = cut.lines[1].glyphs.last().unwrap();
+    assert!(ellipsis.range.is_empty());
+    assert!(ellipsis.span < spans.len());
+}
+
+#[test]
+fn the_tallest_span_sets_the_line_height() {
+    let spans = vec![TextSpan::new("small "), TextSpan::new("BIG").size(28.0)];
+    let layout = measure_rich(&spans, &TextStyle::default(), None);
+    assert_eq!(layout.lines[0].height, 2.0 * LINE_HEIGHT);
+    assert_eq!(layout.height, 2.0 * LINE_HEIGHT);
+
+    let underlined = TextStyle {
+        underline: Some(true),
+        ..default()
+    };
+    let span = TextSpan::new("x").strikethrough();
+    let style = span.style.inherit(&underlined);
+    assert!(style.is_underlined() && style.is_struck_through());
+    assert!(!TextStyle::default().is_underlined());
+}
+
+#[test]
+fn clicking_a_link_sends_its_target() {
+    let clicked = common::record::<LinkClicked>();
+    let mut w = World {
+        root: obj!(Board {
+            children: list![
+                RichText {
+                    spans: sentence(),
+                    style: TextStyle {
+                        size: Some(20.0),
+                        ..default()
+                    },
+                },
+                RichText {
+                    spans: vec!["No links here".into()],
+                },
+            ],
+        }),
+        ..default()
+    };
+    let rect = w.layout().find(&[0]).unwrap().rect;
+    let style = w.root.text_style_at(&[0]);
+    assert_eq!(style.size, Some(20.0));
+    let layout = measure_rich(&sentence(), &style, Some(w.viewport.width));
+    assert_eq!((rect.width, rect.height), (layout.width, layout.height));
+    let at = |span: usize| {
+        let line = &layout.lines[0];
+        let g = line.glyphs.iter().find(|g| g.span == span).unwrap();
+        Point::new(
+            rect.x + g.x + g.width / 2.0,
+            rect.y + line.top + line.height / 2.0,
+        )
+    };
+    let click = |w: &mut World, p: Point| {
+        w.dispatch_pointer(PointerInput::Down(p));
+        w.dispatch_pointer(PointerInput::Up(p))
+    };
+
+    assert!(click(&mut w, at(1)));
+    assert_eq!(
+        clicked.get(),
+        [LinkClicked {
+            path: vec![0],
+            target: "app://next".into(),
+        }]
+    );
+    // Text around the link is not clickable.
+    assert!(!click(&mut w, at(0)));
+    assert!(!click(&mut w, at(3)));
+    assert_eq!(clicked.get().len(), 1);
+
+    // Rich text without links does not take pointer events.
+    assert!(w.root.node(&[0]).unwrap().accepts_pointer());
+    assert!(!w.root.node(&[1]).unwrap().accepts_pointer());
+    let Some(Node::Object(Object::Element(snow_ui::Element::RichText(t)))) = w.root.node(&[1])
+    else {
+        panic!("expected rich text");
+    };
+    assert_eq!(t.text(), "No links here");
+}
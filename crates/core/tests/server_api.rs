@@ -1,10 +1,197 @@
+use std::time::Duration;
+
 use snow_ui::prelude::*;
+use snow_ui::runtime::block_on;
+use snow_ui::{HttpError, Method, MockResponse, MockServer, Timeout};
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct User {
+    id: u32,
+    name: String,
+}
+
+#[test]
+fn requests_reach_the_server_with_method_headers_and_body() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/users/1",
+            MockResponse::json(
+                200,
+                &User {
+                    id: 1,
+                    name: "ann".into(),
+                },
+            ),
+        )
+        .stub(Method::Post, "/users", MockResponse::new(201))
+        .stub(Method::Put, "/users/1", MockResponse::new(204))
+        .stub(Method::Delete, "/users/1", MockResponse::text(200, "gone"));
+    let api = ServerApi::new(&server.url("/")).header("X-App", "demo");
+
+    let user: User = block_on(api.get_json("/users/1")).unwrap();
+    assert_eq!(
+        user,
+        User {
+            id: 1,
+            name: "ann".into()
+        }
+    );
+    let created = block_on(api.post("users").json(&user).send()).unwrap();
+    assert_eq!(created.status, 201);
+    block_on(api.put("/users/1").body("raw").send()).unwrap();
+    let deleted = block_on(api.delete("/users/1").header("X-App", "other").send()).unwrap();
+    assert_eq!(deleted.text().unwrap(), "gone");
+
+    let requests = server.requests();
+    let summary: Vec<_> = requests
+        .iter()
+        .map(|r| (r.method.as_str(), r.path.as_str(), r.header("X-App")))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("GET", "/users/1", Some("demo")),
+            ("POST", "/users", Some("demo")),
+            ("PUT", "/users/1", Some("demo")),
+            ("DELETE", "/users/1", Some("other")),
+        ]
+    );
+    assert_eq!(requests[1].json::<User>().unwrap(), user);
+    assert_eq!(requests[1].header("Content-Type"), Some("application/json"));
+    assert_eq!(requests[2].text(), "raw");
+    assert_eq!(
+        requests[0].header("Host"),
+        Some(server.addr().to_string().as_str())
+    );
+}
+
+#[test]
+fn post_json_posts_to_the_endpoint() {
+    let server = MockServer::start();
+    server.stub(
+        Method::Post,
+        "/post",
+        MockResponse::text(200, "{\"ok\":true}"),
+    );
+    let api = ServerApi::new(&server.url("/post"));
+    let resp = block_on(api.post_json("{\"a\":1}")).unwrap();
+    assert_eq!(resp, "{\"ok\":true}");
+    assert_eq!(server.requests()[0].text(), "{\"a\":1}");
+}
+
+#[test]
+fn error_statuses_fail_with_http_error() {
+    let server = MockServer::start();
+    server.stub(Method::Get, "/secret", MockResponse::text(403, "not yours"));
+    let api = ServerApi::new(&server.url(""));
+
+    let err = block_on(api.get("/secret").send()).unwrap_err();
+    let http = err.downcast_ref::<HttpError>().expect("an HttpError");
+    assert_eq!(http.status, 403);
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "GET {} failed: 403 Forbidden: not yours",
+            server.url("/secret")
+        )
+    );
+    // Unstubbed routes are 404s.
+    let err = block_on(api.get("/missing").send()).unwrap_err();
+    assert_eq!(err.downcast_ref::<HttpError>().map(|e| e.status), Some(404));
+}
 
 #[test]
-fn server_api_post_echoes_payload() {
-    let api = ServerApi::new("https://example.local/post");
-    let resp = futures::executor::block_on(api.post_json("{\"a\":1}"))
-        .unwrap();
-    assert!(resp.contains("example.local"));
-    assert!(resp.contains("{\"a\":1}"));
+fn stubbed_responses_are_used_in_order_and_the_last_repeats() {
+    let server = MockServer::start();
+    server
+        .stub(Method::Get, "/n", MockResponse::text(200, "1"))
+        .stub(Method::Get, "/n", MockResponse::text(200, "2"))
+        .stub(Method::Get, "/n?x=1", MockResponse::text(200, "query"));
+    let api = ServerApi::new(&server.url(""));
+    let get = |path: &str| block_on(api.get(path).send()).unwrap().text().unwrap();
+    assert_eq!(get("/n"), "1");
+    assert_eq!(get("/n"), "2");
+    assert_eq!(get("/n?y=2"), "2");
+    assert_eq!(get("/n?x=1"), "query");
+}
+
+#[test]
+fn slow_responses_time_out() {
+    let server = MockServer::start();
+    server.stub(
+        Method::Get,
+        "/slow",
+        MockResponse::new(200).delay(Duration::from_millis(500)),
+    );
+    let api = ServerApi::new(&server.url("")).timeout(Duration::from_secs(5));
+    let err = block_on(api.get("/slow").timeout(Duration::from_millis(50)).send()).unwrap_err();
+    let timeout = err.downcast_ref::<Timeout>().expect("a Timeout");
+    assert_eq!(timeout.after, Duration::from_millis(50));
+    assert!(err.to_string().ends_with("timed out after 50ms"));
+}
+
+#[test]
+fn invalid_urls_and_bodies_are_reported() {
+    let err = block_on(ServerApi::new("ftp://example.com").get("/").send()).unwrap_err();
+    assert!(format!("{err:#}").contains("expected http:// or https://"));
+
+    let server = MockServer::start();
+    server.stub(Method::Get, "/user", MockResponse::text(200, "nope"));
+    let api = ServerApi::new(&server.url(""));
+    let err = block_on(api.get_json::<User>("/user")).unwrap_err();
+    assert!(err.to_string().contains("response body is not a valid"));
+}
+
+#[test]
+fn chunked_responses_are_decoded() {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let url = format!("http://{}/stream", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf).unwrap();
+        stream
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                  5\r\nhello\r\n7;ext=1\r\n, world\r\n0\r\n\r\n",
+            )
+            .unwrap();
+        // Keep the connection open: the terminating chunk ends the body.
+        std::thread::sleep(Duration::from_millis(200));
+    });
+    let resp = block_on(ServerApi::new(&url).get("").send()).unwrap();
+    assert_eq!(resp.text().unwrap(), "hello, world");
+    server.join().unwrap();
+}
+
+#[test]
+fn responses_split_across_many_reads_are_put_together() {
+    use std::io::{Read, Write};
+
+    let body: Vec<u8> = (0..1_000_000u32).map(|i| (i % 251) as u8).collect();
+    let sent = body.clone();
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let url = format!("http://{}/big", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = stream.read(&mut buf).unwrap();
+        // The end of the head is split between two writes.
+        let head = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r", sent.len());
+        stream.write_all(head.as_bytes()).unwrap();
+        stream.flush().unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        stream.write_all(b"\n").unwrap();
+        for part in sent.chunks(3000) {
+            stream.write_all(part).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    });
+    let resp = block_on(ServerApi::new(&url).get("").send()).unwrap();
+    assert_eq!(resp.body, body);
+    server.join().unwrap();
 }
@@ -0,0 +1,231 @@
+mod common;
+
+use std::time::Duration;
+
+use snow_ui::prelude::*;
+use snow_ui::{
+    CancelToken, ConnectionChanged, ConnectionStatus, FrameRejected, Method, MockResponse,
+    MockServer, RetryPolicy, StaticToken,
+};
+
+#[message]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+struct Quote {
+    symbol: String,
+    price: f64,
+}
+
+#[message]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+struct Notice {
+    text: String,
+}
+
+#[message]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+struct Tick {
+    n: u32,
+}
+
+/// The statuses recorded in `changes` for `url`.
+fn statuses(changes: &State<Vec<ConnectionChanged>>, url: &str) -> Vec<ConnectionStatus> {
+    changes
+        .get()
+        .into_iter()
+        .filter(|c| c.url == url)
+        .map(|c| c.status)
+        .collect()
+}
+
+fn quick_reconnects() -> RetryPolicy {
+    RetryPolicy {
+        max_retries: 5,
+        base_delay: Duration::from_millis(1),
+        jitter: 0.0,
+        ..RetryPolicy::default()
+    }
+}
+
+#[test]
+fn server_sent_events_are_published_as_messages() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/prices",
+            MockResponse::event_stream(
+                ": connected\n\
+                 event: quote\n\
+                 data: {\"symbol\": \"ACME\",\n\
+                 data: \"price\": 12.5}\n\
+                 id: 7\n\n\
+                 data: {\"text\": \"markets open\"}\r\n\r\n\
+                 event: quote\n\
+                 data: oops\n\n\
+                 event: unknown\n\
+                 data: {}\n\n",
+            ),
+        )
+        // The server ends the stream for good.
+        .stub(Method::Get, "/prices", MockResponse::new(204));
+    let quotes = common::record::<Quote>();
+    let notices = common::record::<Notice>();
+    let rejected = common::record::<FrameRejected>();
+    let changes = common::record::<ConnectionChanged>();
+
+    let api = ServerApi::new(&server.url("")).header("X-App", "demo");
+    let live = api
+        .event_source("/prices")
+        .message::<Quote>("quote")
+        .message::<Notice>("message")
+        .reconnect(quick_reconnects())
+        .connect();
+    common::wait_until("the stream closes", || {
+        statuses(&changes, live.url()).contains(&ConnectionStatus::Closed)
+    });
+
+    assert_eq!(
+        quotes.get(),
+        [Quote {
+            symbol: "ACME".into(),
+            price: 12.5
+        }]
+    );
+    assert_eq!(
+        notices.get(),
+        [Notice {
+            text: "markets open".into()
+        }]
+    );
+    let rejected: Vec<_> = rejected
+        .get()
+        .into_iter()
+        .filter(|r| r.url == live.url())
+        .map(|r| r.name)
+        .collect();
+    assert_eq!(rejected, ["quote"]);
+    assert_eq!(
+        statuses(&changes, live.url()),
+        [
+            ConnectionStatus::Connected,
+            ConnectionStatus::Reconnecting { attempt: 1 },
+            ConnectionStatus::Closed,
+        ]
+    );
+    assert_eq!(live.status.get(), ConnectionStatus::Closed);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].header("Accept"), Some("text/event-stream"));
+    assert_eq!(requests[0].header("X-App"), Some("demo"));
+    assert_eq!(requests[0].header("Last-Event-ID"), None);
+    // The reconnect resumes after the last event seen.
+    assert_eq!(requests[1].header("Last-Event-ID"), Some("7"));
+}
+
+#[test]
+fn websocket_frames_are_published_and_dropped_sockets_reconnect() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/live",
+            MockResponse::websocket(&[r#"{"type": "tick", "n": 1}"#, r#"{"n": 0}"#]),
+        )
+        .stub(Method::Get, "/live", MockResponse::new(503))
+        .stub(
+            Method::Get,
+            "/live",
+            MockResponse::websocket(&[r#"{"type": "tick", "n": 2}"#]),
+        )
+        .stub(Method::Get, "/live", MockResponse::text(403, "go away"));
+    let ticks = common::record::<Tick>();
+    let changes = common::record::<ConnectionChanged>();
+
+    let api = ServerApi::new(&server.url("")).auth(StaticToken::new("secret"));
+    let live = api
+        .websocket("/live")
+        .message::<Tick>("tick")
+        .reconnect(quick_reconnects())
+        .connect();
+    assert!(live.url().starts_with("ws://127.0.0.1:"));
+    common::wait_until("the stream fails", || {
+        matches!(live.status.get(), ConnectionStatus::Failed(_))
+            && statuses(&changes, live.url()).len() == 6
+    });
+
+    // The untyped frame is named "message", which nothing decodes.
+    assert_eq!(ticks.get(), [Tick { n: 1 }, Tick { n: 2 }]);
+    let statuses = statuses(&changes, live.url());
+    assert_eq!(
+        statuses[..5],
+        [
+            ConnectionStatus::Connected,
+            ConnectionStatus::Reconnecting { attempt: 1 },
+            ConnectionStatus::Reconnecting { attempt: 2 },
+            ConnectionStatus::Connected,
+            ConnectionStatus::Reconnecting { attempt: 1 },
+        ]
+    );
+    // 403 is not transient.
+    let ConnectionStatus::Failed(error) = &statuses[5] else {
+        panic!("expected a failure, got {:?}", statuses[5]);
+    };
+    assert!(error.contains("403 Forbidden"), "{error}");
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests.iter().all(|r| r.path == "/live"
+        && r.header("Upgrade") == Some("websocket")
+        && r.header("Authorization") == Some("Bearer secret")));
+}
+
+#[test]
+fn streams_close_when_cancelled_or_dropped() {
+    let server = MockServer::start();
+    server
+        .stub(
+            Method::Get,
+            "/slow",
+            MockResponse::event_stream("data: {}\n\n").delay(Duration::from_secs(2)),
+        )
+        .stub(Method::Get, "/plain", MockResponse::text(200, "hello"));
+    let changes = common::record::<ConnectionChanged>();
+    let api = ServerApi::new(&server.url(""));
+
+    let token = CancelToken::new();
+    let live = api.event_source("/slow").cancel_with(&token).connect();
+    assert_eq!(live.status.get(), ConnectionStatus::Connecting);
+    token.cancel();
+    common::wait_until("the cancelled stream closes", || {
+        statuses(&changes, live.url()) == [ConnectionStatus::Closed]
+    });
+
+    let url = {
+        let dropped = api.event_source("/slow?again").connect();
+        dropped.url().to_string()
+    };
+    common::wait_until("the dropped stream closes", || {
+        statuses(&changes, &url) == [ConnectionStatus::Closed]
+    });
+
+    // A response that is not an event stream is not retried.
+    let live = api
+        .event_source("/plain")
+        .reconnect(quick_reconnects())
+        .connect();
+    common::wait_until("the stream fails", || {
+        matches!(live.status.get(), ConnectionStatus::Failed(_))
+    });
+    assert!(live.status.get().to_string().contains("text/event-stream"));
+    assert_eq!(
+        server
+            .requests()
+            .iter()
+            .filter(|r| r.path == "/plain")
+            .count(),
+        1
+    );
+    live.close();
+    assert_eq!(live.status.get(), ConnectionStatus::Closed);
+}
@@ -0,0 +1,91 @@
+use snow_ui::prelude::*;
+use snow_ui::{Element, Node};
+
+fn text_of(obj: &Object) -> &str {
+    match obj {
+        Object::Element(Element::Text(t)) => &t.text,
+        other => panic!("expected a text, got {other:?}"),
+    }
+}
+
+#[derive(IntoObject)]
+struct Title(&'static str);
+
+#[derive(IntoObject)]
+struct Name(String);
+
+#[test]
+fn shared_strings_compare_and_clone_like_str() {
+    let literal = SharedStr::new("hello");
+    let owned = SharedStr::from(format!("hel{}", "lo"));
+    assert_eq!(literal, owned);
+    assert_eq!(owned, "hello");
+    assert_eq!("hello", owned);
+    assert_eq!(owned.clone().len(), 5);
+    assert_eq!(owned.to_string(), "hello");
+    assert_eq!(format!("{owned:?}"), "\"hello\"");
+    assert_eq!(SharedStr::default(), "");
+    assert_eq!(
+        serde_json::to_string(&owned).unwrap(),
+        serde_json::to_string("hello").unwrap()
+    );
+}
+
+#[test]
+fn elements_take_text_made_at_run_time() {
+    let count = 3;
+    let text: Object = Text {
+        text: format!("{count} items").into(),
+        ..default()
+    }
+    .into();
+    assert_eq!(text_of(&text), "3 items");
+
+    // Numbers and strings become texts without leaking a copy each time.
+    for n in 0..1000u128 {
+        let obj: Object = n.into();
+        assert_eq!(text_of(&obj), n.to_string());
+    }
+    assert_eq!(text_of(&Object::from(String::from("owned"))), "owned");
+    assert_eq!(text_of(&Title("title").into()), "title");
+    assert_eq!(text_of(&Name("Ada".to_string()).into()), "Ada");
+}
+
+#[test]
+fn literals_still_work_in_obj_and_list() {
+    let label = String::from("E-mail");
+    let root: Object = obj!(Board {
+        children: list![
+            Text { text: "static" },
+            Form {
+                name: "signup",
+                submit_button: Button { text: "Save" },
+                children: list![TextInput {
+                    label: label.clone().into(),
+                    name: "email",
+                    r#type: "email",
+                }],
+            },
+            Select {
+                label: "Size",
+                options: vec!["S".into(), Choice::new("m", "Medium")],
+            },
+        ],
+    });
+
+    assert_eq!(text_of(&root.children()[0]), "static");
+    let Some(Node::Object(Object::Element(Element::Form(form)))) = root.node(&[1]) else {
+        panic!("expected a form");
+    };
+    assert_eq!(form.name, "signup");
+    assert_eq!(form.submit_button.text, "Save");
+    let Some(Node::Object(Object::Element(Element::TextInput(input)))) = root.node(&[1, 0]) else {
+        panic!("expected a text input");
+    };
+    assert_eq!(input.label, label);
+    assert_eq!((&*input.name, &*input.r#type), ("email", "email"));
+    let Some(Node::Object(Object::Element(Element::Select(select)))) = root.node(&[2]) else {
+        panic!("expected a select");
+    };
+    assert_eq!(select.options[1].label, "Medium");
+}
@@ -0,0 +1,212 @@
+use snow_ui::prelude::*;
+use snow_ui::{Selector, StyleSheetError, measure_styled};
+
+const SHEET: &str = "
+/* Buttons */
+Button { radius: 2px; }
+Button.primary { background: #2563eb; color: white; }
+Button.primary.large { font-size: 20; }
+Button:hover { background: rgb(30, 30, 30); }
+Button:disabled, Button.primary:disabled { background: #ccc; }
+
+Form#login TextInput { border: 2 rgba(0, 0, 0, 0.5); font-weight: bold; }
+Form#login > TextInput:invalid { border-color: #c00; }
+Card { color: #333; padding: 20 }
+";
+
+fn world() -> World {
+    World {
+        root: obj!(Board {
+            children: list![
+                Card {
+                    children: list![
+                        Text { text: "Welcome" },
+                        Button {
+                            text: "Save",
+                            class: "primary large"
+                        },
+                        Button { text: "Cancel" },
+                        Button {
+                            text: "Delete",
+                            class: "primary",
+                            disabled: true,
+                        },
+                    ],
+                },
+                Form {
+                    key: "login",
+                    children: list![TextInput { label: "User" }],
+                },
+                Form {
+                    key: "search",
+                    children: list![TextInput { label: "Query" }],
+                },
+            ],
+        }),
+        styles: StyleSheet::parse(SHEET).unwrap(),
+        ..default()
+    }
+}
+
+fn center(w: &World, path: &[usize]) -> Point {
+    let rect = w.layout().find(path).unwrap().rect;
+    Point::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0)
+}
+
+#[test]
+fn selectors_parse_with_css_specificity() {
+    let sheet = StyleSheet::parse(SHEET).unwrap();
+    assert_eq!(sheet.rules().len(), 8);
+    assert_eq!(sheet.rules()[4].selectors.len(), 2);
+
+    let specificity = |s: &str| Selector::parse(s).unwrap().specificity();
+    assert_eq!(specificity("Button"), (0, 0, 1));
+    assert_eq!(specificity("Button.primary:hover"), (0, 2, 1));
+    assert_eq!(specificity("Form#login > TextInput"), (1, 0, 2));
+    assert_eq!(specificity("*"), (0, 0, 0));
+    assert!(specificity(".a.b") > specificity("Card Row Button"));
+
+    let error = |source: &str| StyleSheet::parse(source).unwrap_err();
+    let e = error("Button {\n  background: #fff;\n  colour: red;\n}");
+    assert_eq!(
+        (e.line, e.message.as_str()),
+        (3, "unknown property `colour`")
+    );
+    assert_eq!(e.to_string(), "line 3: unknown property `colour`");
+    assert_eq!(
+        error("Buton { color: red }").message,
+        "unknown element type `Buton`"
+    );
+    assert_eq!(
+        error("Button:hovered {}").message,
+        "unknown state `:hovered`"
+    );
+    assert_eq!(
+        error("Card {}\n\nButton { color: nope }").message,
+        "invalid color `nope`"
+    );
+    assert_eq!(error("Card { padding: 4").line, 1);
+    assert!(Selector::parse("Card >").is_err());
+}
+
+#[test]
+fn rules_cascade_onto_the_tree() {
+    let mut w = world();
+    let theme = Theme::light();
+
+    // Type, class and key selectors; more specific rules win.
+    let save = w.box_style(&[0, 1]).unwrap();
+    assert_eq!(save.background, Color::rgb(0x25, 0x63, 0xeb));
+    assert_eq!(save.foreground, Color::WHITE);
+    assert_eq!(save.radius, 2.0);
+    let cancel = w.box_style(&[0, 2]).unwrap();
+    assert_eq!(cancel.background, theme.palette.primary);
+    assert_eq!(cancel.radius, 2.0);
+    assert_eq!(
+        w.box_style(&[0, 3]).unwrap().background,
+        Color::rgb(0xcc, 0xcc, 0xcc)
+    );
+
+    let login = w.box_style(&[1, 0]).unwrap();
+    assert_eq!(login.border, Color::rgba(0, 0, 0, 128));
+    assert_eq!(login.border_width, 2.0);
+    assert_eq!(w.box_style(&[2, 0]).unwrap().border, theme.palette.border);
+    assert_eq!(w.box_style(&[0]).unwrap().padding, 20.0);
+
+    // States: hover applies while the pointer is over the button.
+    let p = center(&w, &[0, 2]);
+    w.dispatch_pointer(PointerInput::Move(p));
+    assert_eq!(
+        w.box_style(&[0, 2]).unwrap().background,
+        Color::rgb(30, 30, 30)
+    );
+    // `Button:hover` is as specific as `Button.primary`, and comes later.
+    let p = center(&w, &[0, 1]);
+    w.dispatch_pointer(PointerInput::Move(p));
+    assert_eq!(
+        w.box_style(&[0, 1]).unwrap().background,
+        Color::rgb(30, 30, 30)
+    );
+    assert_eq!(
+        w.box_style(&[0, 2]).unwrap().background,
+        theme.palette.primary
+    );
+
+    // Text properties are inherited, and change the layout.
+    assert_eq!(
+        w.text_style_at(&[0, 0]).color,
+        Some(Color::rgb(0x33, 0x33, 0x33))
+    );
+    assert_eq!(w.text_style_at(&[1, 0]).weight, Some(FontWeight::BOLD));
+    assert_eq!(w.text_style_at(&[2, 0]).weight, None);
+    let save_style = w.text_style_at(&[0, 1]);
+    assert_eq!(save_style.size, Some(20.0));
+    let width = w.layout().find(&[0, 1]).unwrap().rect.width;
+    let expected = measure_styled("Save", &save_style, None).width + 2.0 * snow_ui::layout::SPACING;
+    assert_eq!(width, expected);
+    let plain = World {
+        styles: StyleSheet::default(),
+        ..world()
+    };
+    assert!(plain.layout().find(&[0, 1]).unwrap().rect.width < width);
+
+    // So does padding.
+    let layout = w.layout();
+    let card = layout.find(&[0]).unwrap().rect;
+    let first = layout.find(&[0, 0]).unwrap().rect;
+    assert_eq!((first.x, first.y), (card.x + 20.0, card.y + 20.0));
+    let plain_card = plain.layout().find(&[0]).unwrap().rect;
+    assert_eq!(card.height, plain_card.height + 2.0 * (20.0 - 12.0));
+}
+
+#[test]
+fn a_nodes_own_style_wins_over_the_sheet() {
+    let w = World {
+        root: obj!(Board {
+            children: list![Card {
+                children: list![Text {
+                    text: "Red",
+                    class: "note",
+                    style: TextStyle {
+                        color: Some(Color::rgb(255, 0, 0)),
+                        ..default()
+                    },
+                }],
+            }],
+        }),
+        styles: StyleSheet::parse(".note { color: #00f; font-style: italic; }").unwrap(),
+        ..default()
+    };
+    let style = w.text_style_at(&[0, 0]);
+    assert_eq!(style.color, Some(Color::rgb(255, 0, 0)));
+    assert_eq!(style.italic, Some(true));
+    // Text gets a box only because the sheet styles it.
+    assert_eq!(
+        w.box_style(&[0, 0]).unwrap().foreground,
+        Color::rgb(0, 0, 255)
+    );
+}
+
+#[test]
+fn sheets_load_and_reload_from_files() {
+    let path = std::env::temp_dir().join(format!("snow-ui-{}.css", std::process::id()));
+    std::fs::write(&path, "Button { background: #000; }").unwrap();
+    let mut sheet = StyleSheet::load(&path).unwrap();
+    assert_eq!(sheet.rules().len(), 1);
+    assert!(!sheet.reload().unwrap());
+
+    std::fs::write(&path, "Button { background: #fff; }\nCard { radius: 3 }").unwrap();
+    assert!(sheet.reload().unwrap());
+    assert_eq!(sheet.rules().len(), 2);
+
+    // A broken edit keeps the last good rules.
+    std::fs::write(&path, "Button { background: #ff; }").unwrap();
+    let e: StyleSheetError = sheet.reload().unwrap_err();
+    assert_eq!(e.path.as_deref(), Some(path.as_path()));
+    assert_eq!(e.line, 1);
+    assert_eq!(sheet.rules().len(), 2);
+
+    std::fs::remove_file(&path).unwrap();
+    let e = StyleSheet::load(&path).unwrap_err();
+    assert_eq!(e.line, 0);
+}
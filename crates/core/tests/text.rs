@@ -0,0 +1,184 @@
+mod common;
+
+use snow_ui::layout::{LINE_HEIGHT, SPACING};
+use snow_ui::prelude::*;
+use snow_ui::{font_families, load_font, measure_rich, measure_text};
+
+#[test]
+fn text_is_measured_with_a_proportional_font() {
+    let narrow = measure_text("iiii", None);
+    let wide = measure_text("WWWW", None);
+    assert!(narrow.width > 0.0);
+    assert!(wide.width > 2.0 * narrow.width);
+    assert_eq!(wide.height, LINE_HEIGHT);
+    assert_eq!(wide.lines.len(), 1);
+    assert_eq!(wide.lines[0].glyphs.len(), 4);
+
+    let empty = measure_text("", None);
+    assert_eq!((empty.width, empty.height), (0.0, LINE_HEIGHT));
+}
+
+#[test]
+fn lines_break_at_the_given_width_and_at_newlines() {
+    let text = "The quick brown fox jumps over the lazy dog";
+    let one_line = measure_text(text, None);
+    let wrapped = measure_text(text, Some(100.0));
+    assert_eq!(one_line.lines.len(), 1);
+    assert!(wrapped.lines.len() > 2, "{wrapped:?}");
+    assert!(wrapped.lines.iter().all(|l| l.width <= 100.0));
+    assert_eq!(wrapped.height, wrapped.lines.len() as f32 * LINE_HEIGHT);
+    // Lines break between words.
+    assert_eq!(
+        &text[wrapped.lines[0].range.clone()].trim_end(),
+        &"The quick"
+    );
+
+    let paragraphs = measure_text("ab\ncd", None);
+    let ranges: Vec<_> = paragraphs.lines.iter().map(|l| l.range.clone()).collect();
+    assert_eq!(ranges, [0..2, 3..5]);
+    assert_eq!(paragraphs.lines[1].top, LINE_HEIGHT);
+}
+
+#[test]
+fn right_to_left_paragraphs_are_detected() {
+    let layout = measure_text("שלום עולם\nhello", None);
+    assert!(layout.lines[0].rtl);
+    assert!(!layout.lines[1].rtl);
+    let xs: Vec<f32> = layout.lines[0].glyphs.iter().map(|g| g.x).collect();
+    assert!(xs.windows(2).all(|w| w[0] >= w[1]), "{xs:?}");
+    // The first character is drawn on the right.
+    let first = layout.lines[0]
+        .glyphs
+        .iter()
+        .find(|g| g.range.start == 0)
+        .unwrap();
+    assert!(first.x > layout.lines[0].width / 2.0);
+}
+
+#[test]
+fn bundled_fonts_are_loaded_and_more_can_be_added() {
+    let families = font_families();
+    assert!(families.contains(&"DejaVu Sans".to_string()));
+    assert!(families.contains(&"DejaVu Sans Mono".to_string()));
+    let loaded = load_font(include_bytes!("../fonts/DejaVuSans-Bold.ttf").to_vec());
+    assert_eq!(loaded, ["DejaVu Sans"]);
+    assert!(load_font(b"not a font".to_vec()).is_empty());
+}
+
+#[test]
+fn layout_sizes_text_buttons_and_inputs_from_their_content() {
+    let label = "A label long enough to need more than one line in a narrow window";
+    let w = World {
+        root: Board {
+            children: vec![
+                Text {
+                    text: label.into(),
+                    ..default()
+                }
+                .into(),
+                Button {
+                    text: "OK".into(),
+                    ..default()
+                }
+                .into(),
+                TextInput {
+                    label: "Name".into(),
+                    ..default()
+                }
+                .into(),
+            ],
+            ..default()
+        }
+        .into(),
+        viewport: Rect::new(0.0, 0.0, 200.0, 600.0),
+        ..default()
+    };
+    let layout = w.layout();
+    let text = layout.find(&[0]).unwrap().rect;
+    assert_eq!(text.height, measure_text(label, Some(200.0)).height);
+    assert!(text.height > LINE_HEIGHT);
+    assert!(text.width <= 200.0);
+
+    let button = layout.find(&[1]).unwrap().rect;
+    assert_eq!(button.width, measure_text("OK", None).width + 2.0 * SPACING);
+
+    let wider = World {
+        root: Board {
+            children: vec![
+                TextInput {
+                    label: "A much longer name".into(),
+                    ..default()
+                }
+                .into(),
+            ],
+            ..default()
+        }
+        .into(),
+        ..default()
+    };
+    let input = layout.find(&[2]).unwrap().rect;
+    let longer = wider.layout().find(&[0]).unwrap().rect;
+    assert_eq!(
+        longer.width - input.width,
+        measure_text("A much longer name", None).width - measure_text("Name", None).width
+    );
+}
+
+#[test]
+fn text_wraps_in_the_room_its_container_and_siblings_leave() {
+    let clicked = common::record::<LinkClicked>();
+    let spans = || -> Vec<TextSpan> {
+        vec![
+            "Before you go on, please take a moment to read our ".into(),
+            TextSpan::new("terms of use").link("app://terms"),
+            ".".into(),
+        ]
+    };
+    // Board > Card > Row > [Button, RichText]
+    const TEXT: &[usize] = &[0, 0, 1];
+    let mut w = World {
+        root: obj!(Board {
+            children: list![Card {
+                children: list![Row {
+                    children: list![Button { text: "OK" }, RichText { spans: spans() },],
+                }],
+            }],
+        }),
+        viewport: Rect::new(0.0, 0.0, 300.0, 600.0),
+        ..default()
+    };
+    let layout = w.layout();
+    let card = layout.find(&[0]).unwrap().rect;
+    let button = layout.find(&[0, 0, 0]).unwrap().rect;
+    let rect = layout.find(TEXT).unwrap().rect;
+    assert!(card.x >= 0.0 && card.x + card.width <= 300.0);
+
+    // The text has the width of the card's content, less the button and the
+    // gap after it.
+    let spacing = &w.theme.spacing;
+    let room = 300.0 - 2.0 * spacing.md - button.width - spacing.sm;
+    let text = measure_rich(&spans(), &w.root.text_style_at(TEXT), Some(room));
+    assert_eq!((rect.width, rect.height), (text.width, text.height));
+    assert!(rect.x + rect.width <= card.x + card.width - spacing.md);
+
+    // Links are found where they are drawn, on the wrapped lines.
+    let (line, glyph) = text
+        .lines
+        .iter()
+        .find_map(|l| l.glyphs.iter().find(|g| g.span == 1).map(|g| (l, g)))
+        .unwrap();
+    assert!(line.top > 0.0);
+    let at = Point::new(
+        rect.x + glyph.x + glyph.width / 2.0,
+        rect.y + line.top + line.height / 2.0,
+    );
+    w.dispatch_pointer(PointerInput::Down(at));
+    assert!(w.dispatch_pointer(PointerInput::Up(at)));
+    assert_eq!(
+        clicked.get(),
+        [LinkClicked {
+            path: TEXT.to_vec(),
+            target: "app://terms".into(),
+        }]
+    );
+}
@@ -0,0 +1,163 @@
+use snow_ui::prelude::*;
+use snow_ui::{EditCommand, Element, Motion, Node, NodeMut};
+
+fn area(value: &str, cols: u32, rows: u32) -> TextArea {
+    let mut t = TextArea {
+        cols,
+        rows,
+        ..TextArea::default()
+    };
+    t.set_value(value);
+    t
+}
+
+fn mv(t: &mut TextArea, motion: Motion) {
+    t.edit(&EditCommand::Move {
+        motion,
+        extend: false,
+    });
+}
+
+fn line_texts(t: &TextArea) -> Vec<&str> {
+    t.lines().into_iter().map(|r| &t.value[r]).collect()
+}
+
+#[test]
+fn long_lines_soft_wrap_at_whitespace() {
+    let t = area("the quick brown fox\njumps", 10, 3);
+    assert_eq!(line_texts(&t), vec!["the quick ", "brown fox", "jumps"]);
+    // Words longer than a line are broken.
+    let t = area("abcdefghij", 4, 3);
+    assert_eq!(line_texts(&t), vec!["abcd", "efgh", "ij"]);
+}
+
+#[test]
+fn caret_moves_by_line_and_column() {
+    let mut t = area("first line\nsecond\nthird line", 40, 3);
+    assert_eq!(t.caret_line_col(), (2, 10));
+    mv(&mut t, Motion::Up);
+    // Clamped to the shorter line.
+    assert_eq!(t.caret_line_col(), (1, 6));
+    mv(&mut t, Motion::Up);
+    assert_eq!(t.caret_line_col(), (0, 6));
+    mv(&mut t, Motion::LineStart);
+    assert_eq!(t.caret_line_col(), (0, 0));
+    mv(&mut t, Motion::Down);
+    mv(&mut t, Motion::LineEnd);
+    assert_eq!(t.caret_line_col(), (1, 6));
+    mv(&mut t, Motion::Up);
+    mv(&mut t, Motion::Up);
+    assert_eq!(t.selection.caret, 0);
+}
+
+#[test]
+fn line_end_stays_on_a_soft_wrapped_line() {
+    let mut t = area("abcdefghij", 4, 3);
+    mv(&mut t, Motion::Home);
+    mv(&mut t, Motion::LineEnd);
+    assert_eq!(t.caret_line_col(), (0, 3));
+}
+
+#[test]
+fn scrolling_keeps_the_caret_visible() {
+    let mut t = area("1\n2\n3\n4\n5", 40, 2);
+    assert_eq!(t.scroll, 3);
+    assert_eq!(t.visible_lines(), vec!["4", "5"]);
+    mv(&mut t, Motion::Home);
+    assert_eq!(t.scroll, 0);
+    assert!(t.scroll_by(10));
+    assert_eq!(t.scroll, 3);
+    assert!(!t.scroll_by(1));
+}
+
+#[test]
+fn max_len_counts_newlines() {
+    let mut t = TextArea {
+        max_len: 5,
+        ..TextArea::default()
+    };
+    t.insert("ab\ncd\nef");
+    assert_eq!(t.value, "ab\ncd");
+}
+
+fn form_world() -> World {
+    World {
+        root: obj!(Board {
+            children: list![Form {
+                submit_button: Button { text: "Send" },
+                reset_button: Button { text: "Reset" },
+                children: list![
+                    TextInput { name: "subject" },
+                    TextArea {
+                        name: "body",
+                        label: "Message",
+                        rows: 2,
+                    },
+                ],
+            }],
+        }),
+        ..default()
+    }
+}
+
+fn body(w: &World) -> TextArea {
+    match w.root.node(&[0, 1]) {
+        Some(Node::Object(Object::Element(Element::TextArea(t)))) => t.clone(),
+        _ => panic!("expected a text area"),
+    }
+}
+
+#[test]
+fn keys_edit_the_focused_text_area() {
+    let mut w = form_world();
+    w.focus(&[0, 1]);
+    for key in [Key::Char('h'), Key::Enter, Key::Char('i'), Key::Up] {
+        assert!(w.dispatch_key(KeyEvent::new(key)));
+    }
+    let t = body(&w);
+    assert_eq!(t.value, "h\ni");
+    assert_eq!(t.caret_line_col(), (0, 1));
+
+    let r = w.layout().find(&[0, 1]).unwrap().rect;
+    assert_eq!(
+        r.height,
+        2.0 * snow_ui::layout::LINE_HEIGHT + snow_ui::layout::SPACING
+    );
+}
+
+#[test]
+fn wheel_scrolls_the_text_area_under_the_pointer() {
+    let mut w = form_world();
+    if let Some(NodeMut::Object(Object::Element(Element::TextArea(t)))) = w.root.node_mut(&[0, 1]) {
+        t.set_value("1\n2\n3\n4");
+        t.scroll = 0;
+    }
+    let r = w.layout().find(&[0, 1]).unwrap().rect;
+    let position = Point::new(r.x + 1.0, r.y + 1.0);
+    assert!(w.dispatch_pointer(PointerInput::Wheel {
+        position,
+        delta_x: 0.0,
+        delta_y: snow_ui::layout::LINE_HEIGHT,
+    }));
+    assert_eq!(body(&w).scroll, 1);
+}
+
+#[test]
+fn text_areas_take_part_in_json_and_reset() {
+    let mut w = form_world();
+    w.focus(&[0, 1]);
+    w.dispatch_key(KeyEvent::new(Key::Char('x')));
+
+    let Some(Node::Object(Object::Element(Element::Form(form)))) = w.root.node(&[0]) else {
+        panic!("expected a form");
+    };
+    let json = form.to_json().unwrap();
+    assert_eq!(json, r#"{"body":"x","subject":""}"#);
+
+    // Clicking the reset button clears the fields.
+    let reset = w.layout().find(&[0, 3]).unwrap().rect;
+    let at = Point::new(reset.x + 1.0, reset.y + 1.0);
+    w.dispatch_pointer(PointerInput::Down(at));
+    w.dispatch_pointer(PointerInput::Up(at));
+    assert_eq!(body(&w).value, "");
+}
@@ -0,0 +1,161 @@
+use snow_ui::prelude::*;
+use snow_ui::{EditCommand, Motion, NodeMut};
+
+fn input(value: &str) -> TextInput {
+    let mut t = TextInput::default();
+    t.set_value(value);
+    t
+}
+
+fn mv(motion: Motion, extend: bool) -> EditCommand {
+    EditCommand::Move { motion, extend }
+}
+
+#[test]
+fn insert_and_delete_at_the_caret() {
+    let mut t = input("hello");
+    assert_eq!(t.selection, TextSelection::caret_at(5));
+    t.edit(&mv(Motion::Left, false));
+    assert!(t.insert("!"));
+    assert_eq!(t.value, "hell!o");
+    assert!(t.edit(&EditCommand::Delete(Motion::Left)));
+    assert!(t.edit(&EditCommand::Delete(Motion::Right)));
+    assert_eq!(t.value, "hell");
+    assert!(!t.edit(&EditCommand::Delete(Motion::Right)));
+}
+
+#[test]
+fn caret_moves_by_grapheme() {
+    // "e" + combining acute, a family emoji, then "x".
+    let mut t = input("e\u{301}\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}x");
+    t.edit(&mv(Motion::Home, false));
+    t.edit(&mv(Motion::Right, false));
+    assert_eq!(t.selection.caret, "e\u{301}".len());
+    t.edit(&mv(Motion::Right, false));
+    t.edit(&EditCommand::Delete(Motion::Left));
+    assert_eq!(t.value, "e\u{301}x");
+    t.edit(&EditCommand::Delete(Motion::Left));
+    assert_eq!(t.value, "x");
+}
+
+#[test]
+fn word_movement_and_deletion() {
+    let mut t = input("hello brave  new world");
+    t.edit(&mv(Motion::WordLeft, false));
+    assert_eq!(&t.value[t.selection.caret..], "world");
+    t.edit(&mv(Motion::WordLeft, false));
+    assert_eq!(&t.value[t.selection.caret..], "new world");
+    t.edit(&mv(Motion::WordRight, true));
+    assert_eq!(t.selected_text(), "new");
+    t.edit(&mv(Motion::Home, false));
+    t.edit(&EditCommand::Delete(Motion::WordRight));
+    assert_eq!(t.value, " brave  new world");
+    t.edit(&mv(Motion::End, false));
+    t.edit(&EditCommand::Delete(Motion::WordLeft));
+    assert_eq!(t.value, " brave  new ");
+}
+
+#[test]
+fn selection_is_replaced_or_collapsed() {
+    let mut t = input("abcdef");
+    t.select_all();
+    assert_eq!(t.selected_text(), "abcdef");
+    t.edit(&mv(Motion::Left, false));
+    assert_eq!(t.selection, TextSelection::caret_at(0));
+
+    t.edit(&mv(Motion::Right, true));
+    t.edit(&mv(Motion::Right, true));
+    assert_eq!(t.selected_text(), "ab");
+    t.insert("X");
+    assert_eq!(t.value, "Xcdef");
+    t.edit(&mv(Motion::End, true));
+    t.edit(&EditCommand::Delete(Motion::Left));
+    assert_eq!(t.value, "X");
+}
+
+#[test]
+fn max_len_counts_graphemes() {
+    let mut t = TextInput {
+        max_len: 3,
+        ..TextInput::default()
+    };
+    t.insert("ne\u{301}e\u{301}s");
+    assert_eq!(t.value, "ne\u{301}e\u{301}");
+    assert!(!t.insert("x"));
+    t.select_all();
+    t.insert("wxyz");
+    assert_eq!(t.value, "wxy");
+}
+
+#[test]
+fn single_line_input_drops_control_characters() {
+    let t = input("a\nb\tc");
+    assert_eq!(t.value, "abc");
+}
+
+#[test]
+fn password_is_masked_and_hides_word_boundaries() {
+    let mut t = TextInput {
+        r#type: "password".into(),
+        ..TextInput::default()
+    };
+    t.set_value("open sesame");
+    assert_eq!(t.display_value(), "•".repeat(11));
+    t.edit(&mv(Motion::WordLeft, false));
+    assert_eq!(t.selection.caret, 0);
+    assert_eq!(input("open sesame").display_value(), "open sesame");
+}
+
+#[test]
+fn keys_edit_the_focused_input_and_publish_messages() {
+    struct Recorder(State<Vec<String>>);
+    impl MessageHandler<TextEdited> for Recorder {
+        async fn handle(&mut self, msg: &TextEdited, _: &mut MessageContext) {
+            if msg.name == "keys_user" {
+                self.0.update(|v| v.push(format!("input {}", msg.value)));
+            }
+        }
+    }
+    impl MessageHandler<TextCommitted> for Recorder {
+        async fn handle(&mut self, msg: &TextCommitted, _: &mut MessageContext) {
+            if msg.name == "keys_user" {
+                self.0.update(|v| v.push(format!("change {}", msg.value)));
+            }
+        }
+    }
+    let seen = State::new(vec![]);
+    let recorder = std::sync::Arc::new(std::sync::Mutex::new(Recorder(seen.clone())));
+    event_bus().register_handler::<Recorder, TextEdited>(recorder.clone());
+    event_bus().register_handler::<Recorder, TextCommitted>(recorder);
+
+    let mut w = World {
+        root: obj!(Board {
+            children: list![
+                TextInput { name: "keys_user" },
+                TextInput { name: "keys_other" },
+            ],
+        }),
+        ..default()
+    };
+    w.focus(&[0]);
+    for c in "ab".chars() {
+        assert!(w.dispatch_key(KeyEvent::new(Key::Char(c))));
+    }
+    w.dispatch_key(KeyEvent::new(Key::Left));
+    w.dispatch_key(KeyEvent::with_modifiers(Key::Left, Modifiers::SHIFT));
+    w.dispatch_key(KeyEvent::new(Key::Delete));
+    // Ctrl+letter is not text.
+    assert!(!w.dispatch_key(KeyEvent::with_modifiers(Key::Char('z'), Modifiers::CTRL)));
+    w.dispatch_key(KeyEvent::new(Key::Tab));
+
+    let Some(NodeMut::Object(Object::Element(snow_ui::Element::TextInput(t)))) =
+        w.root.node_mut(&[0])
+    else {
+        panic!("expected a text input");
+    };
+    assert_eq!(t.value, "b");
+    assert_eq!(
+        seen.get(),
+        vec!["input a", "input ab", "input b", "change b"]
+    );
+}
@@ -0,0 +1,193 @@
+use snow_ui::layout::{LINE_HEIGHT, SPACING};
+use snow_ui::prelude::*;
+use snow_ui::{Color, FontWeight, TextStyle, WrapMode, measure_styled, measure_text};
+
+const SENTENCE: &str = "The quick brown fox jumps over the lazy dog";
+
+fn width(text: &str, style: TextStyle) -> f32 {
+    measure_styled(text, &style, None).width
+}
+
+#[test]
+fn font_properties_change_the_measured_size() {
+    let plain = width("Hello", default());
+    let bold = TextStyle {
+        weight: Some(FontWeight::BOLD),
+        ..default()
+    };
+    assert!(width("Hello", bold) > plain);
+    let big = TextStyle {
+        size: Some(28.0),
+        ..default()
+    };
+    assert!((width("Hello", big.clone()) - 2.0 * plain).abs() < 1.0);
+    assert_eq!(
+        measure_styled("Hello", &big, None).height,
+        2.0 * LINE_HEIGHT
+    );
+
+    let spaced = TextStyle {
+        letter_spacing: Some(2.0),
+        ..default()
+    };
+    assert!((width("Hello", spaced) - (plain + 5.0 * 2.0)).abs() < 0.5);
+
+    let mono = TextStyle {
+        family: Some("monospace".into()),
+        ..default()
+    };
+    assert_eq!(width("iiii", mono.clone()), width("WWWW", mono));
+
+    let tall = TextStyle {
+        line_height: Some(30.0),
+        ..default()
+    };
+    assert_eq!(measure_styled("a\nb", &tall, None).height, 60.0);
+}
+
+#[test]
+fn lines_are_aligned_within_the_widest_one() {
+    let text = "a\nlonger line";
+    let lines = |align| {
+        let style = TextStyle { align, ..default() };
+        let layout = measure_styled(text, &style, None);
+        let short = &layout.lines[0];
+        (layout.width - short.width, short.x, short.glyphs[0].x)
+    };
+    let (room, x, glyph) = lines(None);
+    assert!(room > 0.0);
+    assert_eq!((x, glyph), (0.0, 0.0));
+    let (room, x, glyph) = lines(Some(HAlign::Center));
+    assert_eq!((x, glyph), (room / 2.0, room / 2.0));
+    let (room, x, _) = lines(Some(HAlign::Right));
+    assert_eq!(x, room);
+}
+
+#[test]
+fn wrap_modes_and_max_lines() {
+    let at = |wrap| {
+        let style = TextStyle {
+            wrap: Some(wrap),
+            ..default()
+        };
+        measure_styled(SENTENCE, &style, Some(60.0))
+    };
+    assert_eq!(at(WrapMode::None).lines.len(), 1);
+    let starts_a_word = |start: usize| start == 0 || SENTENCE[..start].ends_with(' ');
+    assert!(
+        at(WrapMode::Word)
+            .lines
+            .iter()
+            .all(|l| starts_a_word(l.range.start))
+    );
+    assert!(
+        !at(WrapMode::Char)
+            .lines
+            .iter()
+            .all(|l| starts_a_word(l.range.start))
+    );
+
+    let clamped = TextStyle {
+        max_lines: Some(2),
+        ..default()
+    };
+    let layout = measure_styled(SENTENCE, &clamped, Some(100.0));
+    assert!(layout.truncated);
+    assert_eq!(layout.lines.len(), 2);
+    assert_eq!(layout.height, 2.0 * LINE_HEIGHT);
+    let ellipsis = layout.lines[1].glyphs.last().unwrap();
+    assert!(ellipsis.range.is_empty());
+    assert_eq!(ellipsis.range.start, layout.lines[1].range.end);
+    assert!(layout.lines[1].width <= 100.0);
+
+    // The limit is for the whole text, not each paragraph.
+    let layout = measure_styled("one\ntwo\nthree", &clamped, None);
+    assert!(layout.truncated);
+    assert_eq!(layout.lines.len(), 2);
+    assert_eq!(layout.lines[1].range, 4..7);
+    assert!(layout.lines[1].glyphs.last().unwrap().range.is_empty());
+
+    // Where the ellipsis does not fit after the last line shown, that line
+    // gives up text for it.
+    let three = TextStyle {
+        max_lines: Some(3),
+        ..default()
+    };
+    let text = "aaaa bbbb\ncccc dddd\neeee ffff\ngggg";
+    let layout = measure_styled(text, &three, Some(40.0));
+    assert!(layout.truncated);
+    assert_eq!(layout.lines.len(), 3);
+    let ellipsis = layout.lines[2].glyphs.last().unwrap();
+    assert!(ellipsis.range.is_empty());
+    assert_eq!(ellipsis.range.start, layout.lines[2].range.end);
+    assert!(layout.lines[2].width <= 40.0);
+
+    assert!(!measure_styled("short", &clamped, Some(100.0)).truncated);
+    assert!(!measure_text(SENTENCE, Some(100.0)).truncated);
+}
+
+#[test]
+fn containers_pass_their_text_style_down() {
+    let heading = TextStyle {
+        size: Some(28.0),
+        color: Some(Color::rgb(200, 0, 0)),
+        ..default()
+    };
+    let w = World {
+        root: Board {
+            children: vec![
+                Card {
+                    text_style: heading.clone(),
+                    children: vec![
+                        Text {
+                            text: "Title".into(),
+                            ..default()
+                        }
+                        .into(),
+                        Text {
+                            text: "Body".into(),
+                            style: TextStyle {
+                                size: Some(14.0),
+                                ..default()
+                            },
+                            ..default()
+                        }
+                        .into(),
+                        Button {
+                            text: "Go".into(),
+                            ..default()
+                        }
+                        .into(),
+                    ],
+                    ..default()
+                }
+                .into(),
+            ],
+            text_style: TextStyle {
+                weight: Some(FontWeight::BOLD),
+                ..default()
+            },
+            ..default()
+        }
+        .into(),
+        ..default()
+    };
+
+    let title = w.root.text_style_at(&[0, 0]);
+    assert_eq!(title.size, Some(28.0));
+    assert_eq!(title.weight, Some(FontWeight::BOLD));
+    assert_eq!(title.color(), Color::rgb(200, 0, 0));
+    let body = w.root.text_style_at(&[0, 1]);
+    assert_eq!(body.size, Some(14.0));
+    assert_eq!(body.weight, Some(FontWeight::BOLD));
+    assert_eq!(TextStyle::default().color(), Color::BLACK);
+
+    let layout = w.layout();
+    let rect = |path: &[usize]| layout.find(path).unwrap().rect;
+    assert_eq!(rect(&[0, 0]).height, 2.0 * LINE_HEIGHT);
+    assert_eq!(rect(&[0, 0]).width, width("Title", title));
+    assert_eq!(rect(&[0, 1]).height, LINE_HEIGHT);
+    assert_eq!(rect(&[0, 1]).width, width("Body", body));
+    let button = w.root.text_style_at(&[0, 2]);
+    assert_eq!(rect(&[0, 2]).width, width("Go", button) + 2.0 * SPACING);
+}
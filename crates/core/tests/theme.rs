@@ -0,0 +1,166 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::{BoxStyle, LayoutNode, measure_text};
+
+fn form() -> World {
+    World {
+        root: obj!(Board {
+            children: list![Card {
+                children: list![
+                    Text { text: "Sign in" },
+                    TextInput { label: "E-mail" },
+                    Button { text: "Go" },
+                    Button {
+                        text: "Later",
+                        disabled: true,
+                    },
+                ],
+            }],
+        }),
+        ..default()
+    }
+}
+
+fn background(w: &World, path: &[usize]) -> Color {
+    w.box_style(path).unwrap().background
+}
+
+#[test]
+fn elements_take_their_look_from_the_theme() {
+    let mut w = form();
+    let theme = Theme::light();
+    assert_eq!(w.theme, theme);
+    assert_eq!(w.theme.spacing.sm, snow_ui::layout::SPACING);
+    assert_eq!(w.theme.spacing.md, snow_ui::layout::CARD_PADDING);
+
+    let card = w.box_style(&[0]).unwrap();
+    assert_eq!(card.background, theme.palette.surface);
+    assert_eq!(card.radius, theme.radii.large);
+    assert_eq!(card.elevation, theme.elevation.raised);
+    assert_eq!(background(&w, &[]), theme.palette.background);
+    // Plain text has no box of its own.
+    assert_eq!(w.box_style(&[0, 0]), None);
+
+    let button = w.box_style(&[0, 2]).unwrap();
+    assert_eq!(
+        (button.background, button.foreground),
+        (theme.palette.primary, theme.palette.on_primary)
+    );
+    assert_eq!(button.radius, theme.radii.medium);
+    assert_eq!(background(&w, &[0, 3]), theme.palette.disabled);
+
+    let input = w.box_style(&[0, 1]).unwrap();
+    assert_eq!(input.border, theme.palette.border);
+    assert_eq!(input.radius, theme.radii.small);
+
+    // States change the look: hover, focus and errors.
+    let rect = w.layout().find(&[0, 2]).unwrap().rect;
+    w.dispatch_pointer(PointerInput::Move(Point::new(
+        rect.x + rect.width / 2.0,
+        rect.y + rect.height / 2.0,
+    )));
+    assert_eq!(background(&w, &[0, 2]), theme.palette.primary_hover);
+    w.focus_next();
+    let focused = w.focused().cloned().unwrap();
+    assert_eq!(w.box_style(&focused).unwrap().border, theme.palette.focus);
+    w.root
+        .node_mut(&[0, 1])
+        .map(|n| match n {
+            snow_ui::NodeMut::Object(Object::Element(snow_ui::Element::TextInput(t))) => {
+                t.error = Some("required".into())
+            }
+            other => panic!("expected a text input, got {other:?}"),
+        })
+        .unwrap();
+    w.focus_next();
+    assert_eq!(w.box_style(&[0, 1]).unwrap().border, theme.palette.error);
+
+    // Text inherits the theme's typography and color.
+    let style = w.text_style_at(&[0, 0]);
+    assert_eq!(style.color, Some(theme.palette.text));
+    assert_eq!(style.size, Some(theme.typography.body));
+    let text = w.layout().find(&[0, 0]).unwrap().rect;
+    assert_eq!(text.width, measure_text("Sign in", None).width);
+}
+
+#[test]
+fn themes_switch_at_run_time() {
+    let changed = common::record::<ThemeChanged>();
+    let mut w = form();
+    let light: Vec<Option<BoxStyle>> = paths().map(|p| w.box_style(&p)).collect();
+    assert!(!w.update());
+
+    event_bus().send(SetTheme(Theme::dark()));
+    assert!(w.update());
+    assert_eq!(w.theme.mode, ThemeMode::Dark);
+    assert_eq!(
+        changed.get(),
+        [ThemeChanged {
+            name: "dark".into(),
+            mode: ThemeMode::Dark,
+        }]
+    );
+    // Nothing more to do until the theme changes again.
+    assert!(!w.update());
+
+    // Every box and all text is drawn anew from the dark tokens.
+    let dark: Vec<Option<BoxStyle>> = paths().map(|p| w.box_style(&p)).collect();
+    for (before, after) in light.iter().zip(&dark) {
+        if let (Some(before), Some(after)) = (before, after) {
+            assert_ne!(before.background, after.background);
+        }
+    }
+    assert_eq!(background(&w, &[]), Theme::dark().palette.background);
+    assert_eq!(
+        w.text_style_at(&[0, 0]).color,
+        Some(Theme::dark().palette.text)
+    );
+
+    // Worlds made later start out switched too.
+    let mut other = form();
+    assert!(other.update());
+    assert_eq!(other.theme.mode, ThemeMode::Dark);
+
+    event_bus().send(SetTheme(Theme::high_contrast()));
+    assert!(w.update());
+    let button = w.box_style(&[0, 2]).unwrap();
+    assert_eq!(button.background, Color::BLACK);
+    assert_eq!(button.elevation, 0.0);
+    assert!(w.box_style(&[0, 1]).unwrap().border_width >= 2.0);
+
+    event_bus().send(SetTheme(Theme::light()));
+    assert!(w.update());
+    assert_eq!(paths().map(|p| w.box_style(&p)).collect::<Vec<_>>(), light);
+
+    // A theme assigned directly stays until the next switch.
+    w.theme = Theme::dark();
+    assert!(!w.update());
+    assert_eq!(w.theme.mode, ThemeMode::Dark);
+    event_bus().send(SetTheme(Theme::light()));
+    assert!(w.update());
+    assert_eq!(w.theme.mode, ThemeMode::Light);
+}
+
+fn paths() -> impl Iterator<Item = Vec<usize>> {
+    [vec![], vec![0], vec![0, 1], vec![0, 2], vec![0, 3]].into_iter()
+}
+
+#[test]
+fn layout_takes_gaps_and_padding_from_the_theme() {
+    let mut w = form();
+    let before = w.layout();
+    w.theme.spacing.sm = 20.0;
+    w.theme.spacing.md = 30.0;
+    let after = w.layout();
+    let rect = |layout: &LayoutNode, path: &[usize]| layout.find(path).unwrap().rect;
+
+    let card = rect(&after, &[0]);
+    let title = rect(&after, &[0, 0]);
+    assert_eq!((title.x, title.y), (card.x + 30.0, card.y + 30.0));
+    let field = rect(&after, &[0, 1]);
+    assert_eq!(field.y, title.y + title.height + 20.0);
+    // Buttons pad their text by the small spacing too.
+    let go = |layout: &LayoutNode| rect(layout, &[0, 2]).width;
+    assert_eq!(go(&after), go(&before) + 2.0 * (20.0 - 8.0));
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use snow_ui::prelude::*;
+use snow_ui::runtime::block_on;
+use snow_ui::{Element, InvalidForm, NodeMut};
+
+fn signup_form(submits: &State<u32>) -> Object {
+    let submits = submits.clone();
+    obj!(Form {
+        submit_handler: std::sync::Arc::new(move |_: &Form| {
+            let submits = submits.clone();
+            async move { submits.update(|n| *n += 1) }
+        }),
+        children: list![
+            TextInput {
+                name: "username",
+                rules: vec![
+                    Rule::required(),
+                    Rule::min_len(3),
+                    Rule::pattern("^[a-z0-9_]+$").message("Only a-z, 0-9 and _"),
+                    Rule::custom(|name: String| async move {
+                        tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                        if name == "admin" {
+                            Err(format!("{name} is taken"))
+                        } else {
+                            Ok(())
+                        }
+                    }),
+                ],
+            },
+            TextInput {
+                name: "email",
+                rules: vec![Rule::email()],
+            },
+            TextInput {
+                name: "password",
+                r#type: "password",
+                rules: vec![Rule::required(), Rule::max_len(8)],
+            },
+            TextInput {
+                name: "confirm",
+                r#type: "password",
+                rules: vec![Rule::matches("password").message("Passwords differ")],
+            },
+            TextArea {
+                name: "bio",
+                rules: vec![Rule::max_len(5)],
+            },
+        ],
+    })
+}
+
+fn form(obj: &mut Object) -> &mut Form {
+    match obj {
+        Object::Element(Element::Form(f)) => f,
+        _ => panic!("expected a form"),
+    }
+}
+
+fn fill(obj: &mut Object, values: &[&str]) {
+    for (i, value) in values.iter().enumerate() {
+        match obj.node_mut(&[i]) {
+            Some(NodeMut::Object(Object::Element(Element::TextInput(t)))) => t.set_value(value),
+            Some(NodeMut::Object(Object::Element(Element::TextArea(t)))) => t.set_value(value),
+            _ => panic!("expected a text field at {i}"),
+        }
+    }
+}
+
+fn errors(obj: &mut Object, values: &[&str]) -> Vec<(SharedStr, String)> {
+    fill(obj, values);
+    let form = form(obj);
+    let valid = block_on(form.validate());
+    assert_eq!(valid, form.is_valid());
+    form.errors()
+}
+
+fn error(field: &'static str, message: &str) -> (SharedStr, String) {
+    (field.into(), message.to_string())
+}
+
+#[test]
+fn each_rule_reports_its_message() {
+    let mut obj = signup_form(&State::new(0));
+    assert_eq!(
+        errors(&mut obj, &["", "", "", "", ""]),
+        vec![
+            error("username", "This field is required"),
+            error("password", "This field is required")
+        ]
+    );
+    assert_eq!(
+        errors(&mut obj, &["ab", "a@b", "123456789", "x", "long bio"]),
+        vec![
+            error("username", "Must be at least 3 characters"),
+            error("email", "Enter a valid e-mail address"),
+            error("password", "Must be at most 8 characters"),
+            error("confirm", "Passwords differ"),
+            error("bio", "Must be at most 5 characters"),
+        ]
+    );
+    assert_eq!(
+        errors(&mut obj, &["Ann", "ann@example.com", "pw", "pw", "hi"]),
+        vec![error("username", "Only a-z, 0-9 and _")]
+    );
+    assert_eq!(
+        errors(&mut obj, &["admin", "", "pw", "pw", ""]),
+        vec![error("username", "admin is taken")]
+    );
+    assert!(errors(&mut obj, &["ann", "", "pw", "pw", ""]).is_empty());
+    assert_eq!(
+        errors(&mut obj, &["ann", "", "pw", "", ""]),
+        vec![error("confirm", "Passwords differ")]
+    );
+}
+
+#[test]
+fn invalid_forms_are_not_submitted() {
+    let submits = State::new(0);
+    let mut obj = signup_form(&submits);
+    fill(&mut obj, &["ann", "", "pw", "other", ""]);
+    let err = block_on(form(&mut obj).submit()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<InvalidForm>(),
+        Some(&InvalidForm {
+            errors: vec![error("confirm", "Passwords differ")],
+        })
+    );
+    assert_eq!(submits.get(), 0);
+
+    fill(&mut obj, &["ann", "", "pw", "pw", ""]);
+    block_on(form(&mut obj).submit()).unwrap();
+    assert_eq!(submits.get(), 1);
+    assert!(form(&mut obj).is_valid());
+}
+
+#[test]
+fn enter_validates_and_errors_take_up_room_below_the_field() {
+    let submits = State::new(0);
+    let mut w = World {
+        root: obj!(Board {
+            children: list![signup_form(&submits)],
+        }),
+        ..default()
+    };
+    let before = w.layout().find(&[0, 0]).unwrap().rect.height;
+    w.focus(&[0, 0]);
+    assert!(w.dispatch_key(KeyEvent::new(Key::Enter)));
+    common::settle(&mut w);
+    assert_eq!(submits.get(), 0);
+
+    let Some(NodeMut::Object(Object::Element(Element::TextInput(t)))) = w.root.node_mut(&[0, 0])
+    else {
+        panic!("expected a text input");
+    };
+    assert_eq!(t.error.as_deref(), Some("This field is required"));
+    assert_eq!(
+        w.layout().find(&[0, 0]).unwrap().rect.height,
+        before + snow_ui::layout::LINE_HEIGHT
+    );
+}
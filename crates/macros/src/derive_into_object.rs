@@ -137,7 +137,8 @@ fn gen_named_field_conversion(
     }
 }
 
-/// Generate conversion for a reference type field (handles `&'static str` vs non-static).
+/// Generate conversion for a reference type field. `&'static str` is shown as
+/// it is; other references are copied into a shared string.
 fn gen_ref_conversion(
     name: &syn::Ident,
     accessor: proc_macro2::TokenStream,
@@ -149,21 +150,15 @@ fn gen_ref_conversion(
         .map(|lt| lt.ident == "static")
         .unwrap_or(false);
 
-    if is_static {
-        quote! {
-            impl ::snow_ui::IntoObject for #name {
-                fn into_object(self) -> ::snow_ui::Object {
-                    ::snow_ui::Text { text: #accessor, .. ::snow_ui::default() }.into()
-                }
-            }
-        }
+    let text = if is_static {
+        quote! { ::snow_ui::SharedStr::from(#accessor) }
     } else {
-        quote! {
-            impl ::snow_ui::IntoObject for #name {
-                fn into_object(self) -> ::snow_ui::Object {
-                    let s: &'static str = Box::leak(#accessor.to_owned().into_boxed_str());
-                    ::snow_ui::Text { text: s, .. ::snow_ui::default() }.into()
-                }
+        quote! { ::snow_ui::SharedStr::from(#accessor.to_string()) }
+    };
+    quote! {
+        impl ::snow_ui::IntoObject for #name {
+            fn into_object(self) -> ::snow_ui::Object {
+                ::snow_ui::Text { text: #text, .. ::snow_ui::prelude::default() }.into()
             }
         }
     }
@@ -1,34 +1,156 @@
 //! Implementation of `#[derive(Message)]` and `#[message]` attribute macro.
 
 use quote::quote;
+use syn::spanned::Spanned;
 use syn::{Data, DeriveInput};
 
+/// Options accepted by `#[message(...)]`, both as the attribute macro's
+/// arguments and as a helper attribute next to `#[derive(Message)]`.
+#[derive(Default)]
+struct MessageOptions {
+    name: Option<syn::LitStr>,
+    priority: Option<syn::Expr>,
+    coalesce: Option<syn::LitBool>,
+}
+
+impl MessageOptions {
+    /// Parse `name = "..", priority = N, coalesce` / `coalesce = bool`.
+    fn parse_meta(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("name") {
+            self.name = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("priority") {
+            self.priority = Some(meta.value()?.parse()?);
+        } else if meta.path.is_ident("coalesce") {
+            self.coalesce = Some(if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse()?
+            } else {
+                syn::LitBool::new(true, meta.path.span())
+            });
+        } else {
+            return Err(meta.error("expected `name`, `priority` or `coalesce`"));
+        }
+        Ok(())
+    }
+
+    fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut opts = Self::default();
+        for attr in attrs {
+            if attr.path().is_ident("message") {
+                attr.parse_nested_meta(|meta| opts.parse_meta(meta))?;
+            }
+        }
+        Ok(opts)
+    }
+
+    fn from_tokens(tokens: proc_macro2::TokenStream) -> syn::Result<Self> {
+        let mut opts = Self::default();
+        let parser = syn::meta::parser(|meta| opts.parse_meta(meta));
+        syn::parse::Parser::parse2(parser, tokens)?;
+        Ok(opts)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.priority.is_none() && self.coalesce.is_none()
+    }
+}
+
+/// Emit `impl Message` for `name`, overriding `meta()` only when options were
+/// given and `variant()` for enums.
+fn gen_impl(
+    name: &syn::Ident,
+    generics: &syn::Generics,
+    is_enum: bool,
+    opts: &MessageOptions,
+) -> proc_macro2::TokenStream {
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let variant = if is_enum {
+        quote! {
+            fn variant(&self) -> ::std::option::Option<::std::mem::Discriminant<Self>> {
+                ::std::option::Option::Some(::std::mem::discriminant(self))
+            }
+        }
+    } else {
+        quote! {}
+    };
+    if opts.is_empty() {
+        return quote! {
+            impl #impl_generics ::snow_ui::Message for #name #ty_generics #where_clause {
+                #variant
+            }
+        };
+    }
+
+    let meta_name = match &opts.name {
+        Some(lit) => quote! { #lit },
+        None => quote! { ::std::any::type_name::<Self>() },
+    };
+    let priority = match &opts.priority {
+        Some(expr) => quote! { #expr },
+        None => quote! { 0 },
+    };
+    let coalesce = match &opts.coalesce {
+        Some(lit) => quote! { #lit },
+        None => quote! { false },
+    };
+    quote! {
+        impl #impl_generics ::snow_ui::Message for #name #ty_generics #where_clause {
+            fn meta() -> ::snow_ui::MessageMeta {
+                ::snow_ui::MessageMeta {
+                    name: #meta_name,
+                    priority: #priority,
+                    coalesce: #coalesce,
+                }
+            }
+
+            #variant
+        }
+    }
+}
+
 /// Logic for `#[derive(Message)]`.
 pub(crate) fn derive(input: DeriveInput) -> proc_macro2::TokenStream {
-    let name = input.ident;
+    if let Data::Union(_) = input.data {
+        return quote! {
+            compile_error!("Message can only be derived for structs and enums");
+        };
+    }
 
-    match input.data {
-        Data::Struct(_) => {
-            quote! {
-                impl ::snow_ui::Message for #name {}
-            }
+    match MessageOptions::from_attrs(&input.attrs) {
+        Ok(opts) => {
+            let is_enum = matches!(input.data, Data::Enum(_));
+            gen_impl(&input.ident, &input.generics, is_enum, &opts)
         }
-        _ => quote! {
-            compile_error!("Message can only be derived for structs");
-        },
+        Err(e) => e.to_compile_error(),
     }
 }
 
 /// Logic for the `#[message]` attribute macro.
-pub(crate) fn attribute(item: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
-    match syn::parse2::<syn::ItemStruct>(item) {
-        Ok(s) => {
-            let name = &s.ident;
+pub(crate) fn attribute(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let opts = match MessageOptions::from_tokens(attr) {
+        Ok(opts) => opts,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    match syn::parse2::<syn::Item>(item) {
+        Ok(syn::Item::Struct(s)) => {
+            let imp = gen_impl(&s.ident, &s.generics, false, &opts);
             quote! {
                 #s
-                impl ::snow_ui::Message for #name {}
+                #imp
+            }
+        }
+        Ok(syn::Item::Enum(e)) => {
+            let imp = gen_impl(&e.ident, &e.generics, true, &opts);
+            quote! {
+                #e
+                #imp
             }
         }
+        Ok(other) => syn::Error::new_spanned(other, "#[message] expects a struct or an enum")
+            .to_compile_error(),
         Err(e) => e.to_compile_error(),
     }
 }
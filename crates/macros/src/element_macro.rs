@@ -201,7 +201,7 @@ fn gen_into_object(
                             let rc = ::std::sync::Arc::new(::std::sync::Mutex::new(self));
                             ::snow_ui::register_handlers_for_instance(&rc);
                         }
-                        ::snow_ui::Text { text: "" }.into()
+                        ::snow_ui::Text::default().into()
                     }
                 }
             }
@@ -233,11 +233,17 @@ fn gen_single_field_into_object(
         None => quote! { 0 },
     };
 
-    // Build the conversion body.  For buttons the value goes through `Element`; for
-    // everything else `.into()` suffices.
+    // Build the conversion body.  For buttons the value goes through `Element`, with
+    // the element's `ClickHandler` (if any) attached; for everything else `.into()`
+    // suffices.
     let (value_from_ref, value_from_self) = if is_button {
         (
-            quote! { let e: ::snow_ui::Element = rc.lock().unwrap().#accessor.clone().into(); e.into() },
+            quote! {
+                let mut button = rc.lock().unwrap().#accessor.clone();
+                ::snow_ui::attach_click_handler(&rc, &mut button);
+                let e: ::snow_ui::Element = button.into();
+                e.into()
+            },
             quote! { let e: ::snow_ui::Element = self.#accessor.into(); e.into() },
         )
     } else {
@@ -254,7 +260,9 @@ fn gen_single_field_into_object(
             #default_impl
             impl ::snow_ui::IntoObject for #name {
                 fn into_object(self) -> ::snow_ui::Object {
-                    if ::snow_ui::has_registered_handlers::<#name>() {
+                    if ::snow_ui::has_registered_handlers::<#name>()
+                        || ::snow_ui::has_click_handler::<#name>()
+                    {
                         let rc = ::std::sync::Arc::new(::std::sync::Mutex::new(self));
                         ::snow_ui::register_handlers_for_instance(&rc);
                         #value_from_ref
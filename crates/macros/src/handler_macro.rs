@@ -0,0 +1,329 @@
+//! Implementation of the `#[handler]` attribute macro.
+
+use quote::{quote, quote_spanned};
+use syn::parse::Parser;
+use syn::spanned::Spanned;
+
+/// Logic for `#[handler]` / `#[handler(Elem<A>, Elem<B>)]`.
+///
+/// Accepts `impl MessageHandler<Msg> for Elem { .. }`, `impl ClickHandler for Elem { .. }`
+/// or an inherent `impl Elem { .. }` whose methods are tagged `#[on(Msg)]`. The
+/// impl is emitted and one `HandlerRegistryEntry` per (element, message) pair, or
+/// one `ClickHandlerEntry` per element, is submitted to `inventory`. Generic impls
+/// must list the concrete element types to register in the attribute arguments;
+/// the message type of each is found by substituting the impl's parameters.
+pub(crate) fn expand(
+    attr: proc_macro2::TokenStream,
+    item: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let parser = syn::punctuated::Punctuated::<syn::Type, syn::token::Comma>::parse_terminated;
+    let instances: Vec<syn::Type> = match parser.parse2(attr) {
+        Ok(p) => p.into_iter().collect(),
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let mut imp = match syn::parse2::<syn::ItemImpl>(item) {
+        Ok(i) => i,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let elements = match element_types(&imp, instances) {
+        Ok(e) => e,
+        Err(e) => return e.to_compile_error(),
+    };
+
+    let (impls, messages) = match &imp.trait_ {
+        Some((_, path, _)) => match trait_kind(path) {
+            Ok(TraitKind::Message(msg)) => (quote! { #imp }, vec![*msg]),
+            Ok(TraitKind::Click) => {
+                let entries = elements.iter().map(|Instance { ty: elem, .. }| {
+                    quote! {
+                        ::snow_ui::inventory::submit! {
+                            ::snow_ui::ClickHandlerEntry {
+                                element_type_id: ::std::any::TypeId::of::<#elem>,
+                                make_fn: ::snow_ui::make_click_callback::<#elem>,
+                            }
+                        }
+                    }
+                });
+                return quote! {
+                    #imp
+                    #(#entries)*
+                };
+            }
+            Err(e) => return e.to_compile_error(),
+        },
+        None => match expand_on_methods(&mut imp) {
+            Ok(r) => r,
+            Err(e) => return e.to_compile_error(),
+        },
+    };
+
+    let mut registrations = Vec::new();
+    for msg in &messages {
+        for instance in &elements {
+            let elem = &instance.ty;
+            let msg_ty = instance.substitute(quote! { #msg });
+            registrations.push(quote_spanned! {msg.span()=>
+                const _: fn() = ::snow_ui::handler::__assert_message::<#msg_ty>;
+            });
+            registrations.push(quote! {
+                ::snow_ui::inventory::submit! {
+                    ::snow_ui::HandlerRegistryEntry {
+                        element_type_id: ::std::any::TypeId::of::<#elem>,
+                        register_fn: ::snow_ui::register_instance_handler::<#elem, #msg_ty>,
+                    }
+                }
+            });
+        }
+    }
+
+    quote! {
+        #impls
+        #(#registrations)*
+    }
+}
+
+/// A concrete element type to register, with the types the impl's generic
+/// parameters take for it.
+struct Instance {
+    ty: syn::Type,
+    params: std::collections::HashMap<String, proc_macro2::TokenStream>,
+}
+
+impl Instance {
+    /// `tokens` with the impl's generic parameters replaced by their types for
+    /// this instance.
+    fn substitute(&self, tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        use proc_macro2::{Delimiter, Group, TokenTree};
+
+        let mut out = Vec::new();
+        // A parameter name after `::` is a path segment, not the parameter.
+        let mut after_colon = false;
+        for tt in tokens {
+            let colon = matches!(&tt, TokenTree::Punct(p) if p.as_char() == ':');
+            match tt {
+                TokenTree::Ident(ident) if !after_colon => {
+                    match self.params.get(&ident.to_string()) {
+                        Some(ty) => {
+                            out.push(TokenTree::Group(Group::new(Delimiter::None, ty.clone())))
+                        }
+                        None => out.push(TokenTree::Ident(ident)),
+                    }
+                }
+                TokenTree::Group(group) => {
+                    let mut inner = Group::new(group.delimiter(), self.substitute(group.stream()));
+                    inner.set_span(group.span());
+                    out.push(TokenTree::Group(inner));
+                }
+                other => out.push(other),
+            }
+            after_colon = colon;
+        }
+        out.into_iter().collect()
+    }
+}
+
+/// The concrete element types to register: the impl's self type, or the listed
+/// instances when the impl is generic.
+fn element_types(imp: &syn::ItemImpl, instances: Vec<syn::Type>) -> syn::Result<Vec<Instance>> {
+    if imp.generics.params.is_empty() {
+        if let Some(first) = instances.first() {
+            return Err(syn::Error::new_spanned(
+                first,
+                "element types are only listed for generic impls",
+            ));
+        }
+        return Ok(vec![Instance {
+            ty: (*imp.self_ty).clone(),
+            params: Default::default(),
+        }]);
+    }
+    if instances.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &imp.generics,
+            "generic handlers must list the element types to register, \
+             e.g. `#[handler(Counter<u32>)]`",
+        ));
+    }
+    let names: Vec<String> = imp
+        .generics
+        .params
+        .iter()
+        .filter_map(|p| match p {
+            syn::GenericParam::Type(t) => Some(t.ident.to_string()),
+            syn::GenericParam::Const(c) => Some(c.ident.to_string()),
+            syn::GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+    Ok(instances
+        .into_iter()
+        .map(|ty| {
+            let mut params = std::collections::HashMap::new();
+            bind_params(&imp.self_ty, &ty, &names, &mut params);
+            Instance { ty, params }
+        })
+        .collect())
+}
+
+/// Match the impl's self type `pattern` against a listed instance `ty`,
+/// recording the type (or const) each of the generic parameters `names` takes.
+fn bind_params(
+    pattern: &syn::Type,
+    ty: &syn::Type,
+    names: &[String],
+    out: &mut std::collections::HashMap<String, proc_macro2::TokenStream>,
+) {
+    if let Some(name) = param_name(pattern, names) {
+        out.insert(name, quote! { #ty });
+        return;
+    }
+    match (pattern, ty) {
+        (syn::Type::Path(p), syn::Type::Path(t)) => {
+            let (Some(p), Some(t)) = (p.path.segments.last(), t.path.segments.last()) else {
+                return;
+            };
+            let (
+                syn::PathArguments::AngleBracketed(p_args),
+                syn::PathArguments::AngleBracketed(t_args),
+            ) = (&p.arguments, &t.arguments)
+            else {
+                return;
+            };
+            for (p_arg, t_arg) in p_args.args.iter().zip(&t_args.args) {
+                let syn::GenericArgument::Type(p_ty) = p_arg else {
+                    continue;
+                };
+                match t_arg {
+                    syn::GenericArgument::Type(t_ty) => bind_params(p_ty, t_ty, names, out),
+                    syn::GenericArgument::Const(expr) => {
+                        if let Some(name) = param_name(p_ty, names) {
+                            out.insert(name, quote! { #expr });
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (syn::Type::Reference(p), syn::Type::Reference(t)) => {
+            bind_params(&p.elem, &t.elem, names, out)
+        }
+        (syn::Type::Slice(p), syn::Type::Slice(t)) => bind_params(&p.elem, &t.elem, names, out),
+        (syn::Type::Array(p), syn::Type::Array(t)) => bind_params(&p.elem, &t.elem, names, out),
+        (syn::Type::Tuple(p), syn::Type::Tuple(t)) => {
+            for (p, t) in p.elems.iter().zip(&t.elems) {
+                bind_params(p, t, names, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The generic parameter `ty` is, if it is just one of `names`.
+fn param_name(ty: &syn::Type, names: &[String]) -> Option<String> {
+    let syn::Type::Path(p) = ty else {
+        return None;
+    };
+    let ident = p.path.get_ident()?.to_string();
+    (p.qself.is_none() && names.contains(&ident)).then_some(ident)
+}
+
+/// Which handler trait a `#[handler]` trait impl implements.
+enum TraitKind {
+    /// `MessageHandler<Msg>`, carrying `Msg`.
+    Message(Box<syn::Type>),
+    /// `ClickHandler`.
+    Click,
+}
+
+fn trait_kind(path: &syn::Path) -> syn::Result<TraitKind> {
+    let last = path.segments.last().unwrap();
+    if last.ident == "ClickHandler" {
+        return Ok(TraitKind::Click);
+    }
+    if last.ident == "MessageHandler"
+        && let syn::PathArguments::AngleBracketed(args) = &last.arguments
+        && let Some(syn::GenericArgument::Type(ty)) = args.args.first()
+    {
+        return Ok(TraitKind::Message(Box::new(ty.clone())));
+    }
+    Err(syn::Error::new_spanned(
+        path,
+        "#[handler] expects `impl MessageHandler<Msg> for Element` or `impl ClickHandler for Element`",
+    ))
+}
+
+/// Strip `#[on(Msg)]` from the methods of an inherent impl and generate a
+/// forwarding `MessageHandler<Msg>` impl for each of them.
+fn expand_on_methods(
+    imp: &mut syn::ItemImpl,
+) -> syn::Result<(proc_macro2::TokenStream, Vec<syn::Type>)> {
+    let (impl_generics, _, where_clause) = imp.generics.split_for_impl();
+    let self_ty = &imp.self_ty;
+    let mut forwards = Vec::new();
+    let mut messages = Vec::new();
+
+    for item in imp.items.iter_mut() {
+        let syn::ImplItem::Fn(f) = item else {
+            continue;
+        };
+        let Some(pos) = f.attrs.iter().position(|a| a.path().is_ident("on")) else {
+            continue;
+        };
+        let attr = f.attrs.remove(pos);
+        let msg: syn::Type = attr.parse_args()?;
+
+        let method = &f.sig.ident;
+        let arity = f
+            .sig
+            .inputs
+            .iter()
+            .filter(|a| matches!(a, syn::FnArg::Typed(_)))
+            .count();
+        let call = match arity {
+            1 => quote! { self.#method(msg) },
+            2 => quote! { self.#method(msg, ctx) },
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &f.sig,
+                    "#[on] methods take `(&mut self, msg: &Msg)` or \
+                     `(&mut self, msg: &Msg, ctx: &mut MessageContext)`",
+                ));
+            }
+        };
+        let call = if f.sig.asyncness.is_some() {
+            quote! { #call.await; }
+        } else {
+            quote! { #call; }
+        };
+        let ctx = if arity == 2 {
+            quote! { ctx }
+        } else {
+            quote! { _ctx }
+        };
+
+        forwards.push(quote! {
+            impl #impl_generics ::snow_ui::MessageHandler<#msg> for #self_ty #where_clause {
+                async fn handle(&mut self, msg: &#msg, #ctx: &mut ::snow_ui::MessageContext) {
+                    #call
+                }
+            }
+        });
+        messages.push(msg);
+    }
+
+    if messages.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &imp.self_ty,
+            "#[handler] on an inherent impl needs at least one `#[on(Msg)]` method",
+        ));
+    }
+
+    Ok((
+        quote! {
+            #imp
+            #(#forwards)*
+        },
+        messages,
+    ))
+}
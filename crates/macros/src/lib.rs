@@ -10,6 +10,7 @@ use syn::parse_macro_input;
 mod derive_into_object;
 mod derive_message;
 mod element_macro;
+mod handler_macro;
 mod list_macro;
 mod obj_macro;
 mod utils;
@@ -21,17 +22,18 @@ pub fn derive_into_object(input: TokenStream) -> TokenStream {
     derive_into_object::derive(parse_macro_input!(input as syn::DeriveInput)).into()
 }
 
-#[proc_macro_derive(Message)]
+#[proc_macro_derive(Message, attributes(message))]
 pub fn derive_message(input: TokenStream) -> TokenStream {
     derive_message::derive(parse_macro_input!(input as syn::DeriveInput)).into()
 }
 
 // ── Attribute macros ─────────────────────────────────────────────────────────
 
-/// `#[message] struct S { .. }` — emits the struct and implements `Message`.
+/// `#[message] struct S { .. }` / `#[message] enum E { .. }` — emits the item and
+/// implements `Message`. Accepts `name = "..."`, `priority = N` and `coalesce`.
 #[proc_macro_attribute]
-pub fn message(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    derive_message::attribute(item.into()).into()
+pub fn message(attr: TokenStream, item: TokenStream) -> TokenStream {
+    derive_message::attribute(attr.into(), item.into()).into()
 }
 
 /// `#[element]` / `#[element(message = [...])]` — emits the struct with
@@ -41,6 +43,16 @@ pub fn element(attr: TokenStream, item: TokenStream) -> TokenStream {
     element_macro::expand(attr.into(), item.into()).into()
 }
 
+/// `#[handler] impl MessageHandler<Msg> for Elem { .. }`,
+/// `#[handler] impl ClickHandler for Elem { .. }` or
+/// `#[handler] impl Elem { #[on(Msg)] async fn f(&mut self, msg: &Msg) { .. } }` —
+/// emits the impl and registers it through `inventory`. Generic impls list the
+/// concrete element types to register: `#[handler(Elem<u32>)]`.
+#[proc_macro_attribute]
+pub fn handler(attr: TokenStream, item: TokenStream) -> TokenStream {
+    handler_macro::expand(attr.into(), item.into()).into()
+}
+
 // ── Function-like macros ─────────────────────────────────────────────────────
 
 /// Internal helper: process a single expression, appending defaults to struct
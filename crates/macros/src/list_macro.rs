@@ -3,7 +3,7 @@
 use quote::quote;
 use syn::parse::Parser;
 
-use crate::utils::{is_form_path, process_struct_fields, rebuild_struct_with_defaults};
+use crate::utils::{add_defaults_to_expr, rebuild_struct_with_defaults};
 
 /// Logic for `__list_item!` — process a single expression, appending defaults to
 /// struct literals that omit `..rest`.
@@ -22,7 +22,7 @@ pub(crate) fn list_item(input: proc_macro2::TokenStream) -> proc_macro2::TokenSt
 }
 
 /// Logic for `list!` — parse comma-separated expressions and produce a `Vec`
-/// with defaults appended to struct literals.
+/// with defaults appended to (nested) struct literals.
 pub(crate) fn list(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
     let parser = syn::punctuated::Punctuated::<syn::Expr, syn::token::Comma>::parse_terminated;
     let exprs = match parser.parse2(input) {
@@ -32,19 +32,10 @@ pub(crate) fn list(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream
 
     let mut out_exprs: Vec<proc_macro2::TokenStream> = Vec::new();
     for mut e in exprs.into_iter() {
-        if let syn::Expr::Struct(es) = &mut e
-            && es.rest.is_none()
-        {
-            let path = &es.path;
-            let is_form = is_form_path(path);
-            let fields_tokens = process_struct_fields(&es.fields, is_form);
-            out_exprs
-                .push(quote! { #path { #(#fields_tokens),* , .. ::snow_ui::prelude::default() } });
-        } else if let syn::Expr::Struct(es) = &mut e {
-            out_exprs.push(quote! { #es });
-        } else {
-            out_exprs.push(quote! { #e });
-        }
+        // Nested struct literals (e.g. `submit_button: Button { .. }`) get their
+        // defaults too, exactly like in `obj!`.
+        add_defaults_to_expr(&mut e);
+        out_exprs.push(quote! { #e });
     }
 
     quote! {
@@ -2,7 +2,7 @@
 
 use quote::quote;
 
-use crate::utils::add_defaults_to_expr;
+use crate::utils::{add_defaults_to_expr, convert_string_literal, defaulted_struct};
 
 /// Logic for `obj!(...)`.
 ///
@@ -31,6 +31,7 @@ pub(crate) fn expand(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
                 // Recurse into fields to add defaults to nested struct literals.
                 for field in es.fields.iter_mut() {
                     add_defaults_to_expr(&mut field.expr);
+                    convert_string_literal(field);
                 }
 
                 // If no `..rest` on the top-level struct, rebuild with defaults.
@@ -38,8 +39,7 @@ pub(crate) fn expand(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
                     let path = &es.path;
                     let fields: Vec<proc_macro2::TokenStream> =
                         es.fields.iter().map(|f| quote! { #f }).collect();
-                    let rebuilt =
-                        quote! { #path { #(#fields),* , .. ::snow_ui::prelude::default() } };
+                    let rebuilt = defaulted_struct(path, &fields);
                     expr = syn::parse2(rebuilt).expect("failed to build nested defaulting struct");
                 }
             }
@@ -12,6 +12,8 @@ pub(crate) fn process_struct_fields(
 ) -> Vec<proc_macro2::TokenStream> {
     let mut tokens = Vec::new();
     for f in fields.iter() {
+        let mut f = f.clone();
+        convert_string_literal(&mut f);
         if is_form
             && let syn::Member::Named(ident) = &f.member
             && ident == "submit_handler"
@@ -33,6 +35,19 @@ pub(crate) fn process_struct_fields(
     tokens
 }
 
+/// Wrap a string literal assigned to a field in `Into::into(...)`, so that
+/// text fields taking a `SharedStr` (or a `String`) accept plain literals.
+pub(crate) fn convert_string_literal(field: &mut syn::FieldValue) {
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit),
+        ..
+    }) = &field.expr
+    {
+        field.expr = syn::parse2(quote! { ::core::convert::Into::into(#lit) })
+            .expect("failed to convert string literal");
+    }
+}
+
 /// Returns `true` if `path`'s last segment is `"Form"`.
 pub(crate) fn is_form_path(path: &syn::Path) -> bool {
     path.segments
@@ -41,24 +56,43 @@ pub(crate) fn is_form_path(path: &syn::Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Build `path { fields, .. ::snow_ui::prelude::default() }`.
+///
+/// The literal is wrapped in a block that silences `clippy::needless_update`,
+/// since the defaults are appended even when every field is already given.
+pub(crate) fn defaulted_struct(
+    path: &syn::Path,
+    fields: &[proc_macro2::TokenStream],
+) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            #[allow(clippy::needless_update)]
+            let __snow_ui_value = #path { #(#fields),* , .. ::snow_ui::prelude::default() };
+            __snow_ui_value
+        }
+    }
+}
+
 /// Rebuild a struct-expression with `.. ::snow_ui::prelude::default()` appended,
 /// processing Form submit_handler fields along the way.
 pub(crate) fn rebuild_struct_with_defaults(es: &syn::ExprStruct) -> proc_macro2::TokenStream {
     let path = &es.path;
     let is_form = is_form_path(path);
     let fields_tokens = process_struct_fields(&es.fields, is_form);
-    quote! { #path { #(#fields_tokens),* , .. ::snow_ui::prelude::default() } }
+    defaulted_struct(path, &fields_tokens)
 }
 
 /// Recursively walk an expression tree and add `.. ::snow_ui::prelude::default()`
-/// to struct literals that don't already have a `..rest`, and wrap `Form.submit_handler`
-/// bare paths with `Arc::new(...)`.
+/// to struct literals that don't already have a `..rest`, convert string literal
+/// field values with `Into::into`, and wrap `Form.submit_handler` bare paths with
+/// `Arc::new(...)`.
 pub(crate) fn add_defaults_to_expr(e: &mut syn::Expr) {
     match e {
         syn::Expr::Struct(es) => {
             // First recurse into fields so nested struct literals are also handled.
             for field in es.fields.iter_mut() {
                 add_defaults_to_expr(&mut field.expr);
+                convert_string_literal(field);
             }
 
             // Wrap Form submit_handler bare paths.
@@ -87,10 +121,8 @@ pub(crate) fn add_defaults_to_expr(e: &mut syn::Expr) {
                 let path = &es.path;
                 let fields_tokens: Vec<proc_macro2::TokenStream> =
                     es.fields.iter().map(|f| quote! { #f }).collect();
-                *e = syn::parse2(
-                    quote! { #path { #(#fields_tokens),* , .. ::snow_ui::prelude::default() } },
-                )
-                .expect("failed to rebuild nested struct with defaults");
+                *e = syn::parse2(defaulted_struct(path, &fields_tokens))
+                    .expect("failed to rebuild nested struct with defaults");
             }
         }
         syn::Expr::Array(arr) => {
@@ -6,10 +6,12 @@ use snow_ui::prelude::*;
 struct IncreaseButtonClicked {}
 
 #[element]
+#[derive(Clone)]
 struct IncreaseButton {
     button: Button,
 }
 
+#[handler]
 impl ClickHandler for IncreaseButton {
     async fn on_click(&mut self) {
         event_bus().send(IncreaseButtonClicked {});
@@ -29,13 +31,12 @@ struct SimpleText {
     count: State<u128>,
 }
 
-register_handler!(
-    impl MessageHandler<IncreaseButtonClicked> for SimpleText {
-        async fn handle(&mut self, _: &IncreaseButtonClicked, _: &mut MessageContext) {
-            self.count.update(|c| *c += 1);
-        }
+#[handler]
+impl MessageHandler<IncreaseButtonClicked> for SimpleText {
+    async fn handle(&mut self, _: &IncreaseButtonClicked, _: &mut MessageContext) {
+        self.count.update(|c| *c += 1);
     }
-);
+}
 
 fn simple_text() -> Object {
     obj!(SimpleText {
@@ -10,10 +10,22 @@ struct LoginBoard {
     board: Board,
 }
 
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+struct LoginRequest {
+    username: String,
+    password: String,
+}
+
 async fn login(form: &Form) -> anyhow::Result<()> {
-    let json = form.to_json()?;
-    let server_api = ServerApi::new("https://httpbin.org/post");
-    let resp = server_api.post_json(&json).await?;
+    let request: LoginRequest = form.values()?;
+    let server_api = ServerApi::new("https://httpbin.org");
+    let resp: serde_json::Value = server_api
+        .post("/post")
+        .json(&request)
+        .cancel_with(&form.cancel)
+        .send()
+        .await?
+        .json()?;
     println!("Server response: {}", resp);
     event_bus().send(LoginSuccess {});
     Ok(())
@@ -70,13 +82,13 @@ struct MySwitch {
     switch: Switch,
 }
 
-register_handler!(
-    impl MessageHandler<LoginSuccess> for MySwitch {
-        async fn handle(&mut self, _: &LoginSuccess, _: &mut MessageContext) {
-            self.switch.switch_to(1);
-        }
+#[handler]
+impl MySwitch {
+    #[on(LoginSuccess)]
+    async fn on_login(&mut self, _: &LoginSuccess) {
+        self.switch.switch_to(1);
     }
-);
+}
 
 fn my_switch() -> Object {
     obj!(MySwitch {
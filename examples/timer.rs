@@ -9,16 +9,17 @@ struct SimpleTextTimerTickEvent {}
 #[element]
 struct SimpleTextTimer {
     seconds: State<u128>,
+    // Not read by user code; it only declares the tick source for this element.
+    #[allow(dead_code)]
     timer: IntervalTimer<SimpleTextTimerTickEvent>,
 }
 
-register_handler!(
-    impl MessageHandler<SimpleTextTimerTickEvent> for SimpleTextTimer {
-        async fn handle(&mut self, _: &SimpleTextTimerTickEvent, _: &mut MessageContext) {
-            self.seconds.update(|s| *s += 1);
-        }
+#[handler]
+impl MessageHandler<SimpleTextTimerTickEvent> for SimpleTextTimer {
+    async fn handle(&mut self, _: &SimpleTextTimerTickEvent, _: &mut MessageContext) {
+        self.seconds.update(|s| *s += 1);
     }
-);
+}
 
 fn simple_text_timer() -> Object {
     obj!(SimpleTextTimer {